@@ -0,0 +1,105 @@
+//! Compares decoding a large `work-list` page in a single pass against the former
+//! two pass approach, that first parsed the message into a `serde_json::Value`.
+//!
+//! Run with `cargo bench --bench response`
+//!
+//! The single pass depends on every type of a work deserializing as a plain struct.
+//! A `#[serde(flatten)]` field makes serde buffer each object before it is decoded,
+//! which roughly tripled the time of the single pass, so the unknown fields of a work are
+//! collected by the `extra_fields!` types instead. Most of the peak of the single pass is
+//! the decoded page itself, which is reported as `kept`.
+
+use crossref::response::{Response, WorkList};
+use serde_json::Value;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/// tracks the currently allocated and the peak number of bytes
+struct CountingAlloc;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let current = CURRENT.fetch_add(layout.size(), Ordering::SeqCst) + layout.size();
+            PEAK.fetch_max(current, Ordering::SeqCst);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        CURRENT.fetch_sub(layout.size(), Ordering::SeqCst);
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+const ITERATIONS: u32 = 20;
+
+/// a page of `rows` full work records
+fn work_list_page(rows: usize) -> String {
+    let work = r#"{"indexed":{"date-parts":[[2019,2,26]],"date-time":"2019-02-26T10:43:14Z","timestamp":1551177794515},"reference-count":105,"publisher":"American Psychological Association (APA)","issue":"1","content-domain":{"domain":[],"crossmark-restriction":false},"short-container-title":["American Psychologist"],"DOI":"10.1037/0003-066x.59.1.29","type":"journal-article","created":{"date-parts":[[2004,1,21]],"date-time":"2004-01-21T14:31:19Z","timestamp":1074695479000},"page":"29-40","source":"Crossref","is-referenced-by-count":84,"title":["How the Mind Hurts and Heals the Body."],"prefix":"10.1037","volume":"59","author":[{"given":"Oakley","family":"Ray","sequence":"first","affiliation":[]}],"member":"15","published-online":{"date-parts":[[2004]]},"container-title":["American Psychologist"],"original-title":[],"language":"en","link":[{"URL":"http://psycnet.apa.org/journals/amp/59/1/29.pdf","content-type":"unspecified","content-version":"vor","intended-application":"similarity-checking"}],"deposited":{"date-parts":[[2018,4,8]],"date-time":"2018-04-08T18:56:17Z","timestamp":1523213777000},"score":1,"subtitle":[],"short-title":[],"issued":{"date-parts":[[2004]]},"references-count":105,"journal-issue":{"published-online":{"date-parts":[[2004]]},"issue":"1"},"alternative-id":["2004-10043-004","14736318"],"URL":"http://dx.doi.org/10.1037/0003-066x.59.1.29","relation":{},"ISSN":["1935-990X","0003-066X"],"issn-type":[{"value":"0003-066X","type":"print"},{"value":"1935-990X","type":"electronic"}]}"#;
+    let items = vec![work; rows].join(",");
+    format!(
+        r#"{{"status":"ok","message-type":"work-list","message-version":"1.0.0","message":{{"facets":{{}},"next-cursor":"AoJ/","total-results":100000,"items":[{}],"items-per-page":{},"query":{{"start-index":0,"search-terms":null}}}}}}"#,
+        items, rows
+    )
+}
+
+/// the former decoding: parse the whole body into a `Value`, then convert the message
+fn two_pass(body: &str) -> WorkList {
+    let mut value: Value = serde_json::from_str(body).unwrap();
+    serde_json::from_value(value["message"].take()).unwrap()
+}
+
+/// the typed decoding, chosen by the calling method
+fn single_pass(body: &str) -> WorkList {
+    serde_json::from_str::<Response<WorkList>>(body)
+        .unwrap()
+        .message
+        .unwrap()
+}
+
+/// runs `f` and reports the mean duration, the peak heap usage on top of the input
+/// and the heap usage of the decoded page
+fn bench(name: &str, body: &str, f: fn(&str) -> WorkList) {
+    let mut elapsed = Duration::default();
+    let mut peak = 0;
+    let mut kept = 0;
+    for _ in 0..ITERATIONS {
+        let base = CURRENT.load(Ordering::SeqCst);
+        PEAK.store(base, Ordering::SeqCst);
+        let start = Instant::now();
+        let list = f(body);
+        elapsed += start.elapsed();
+        peak = peak.max(PEAK.load(Ordering::SeqCst) - base);
+        kept = CURRENT.load(Ordering::SeqCst) - base;
+        assert!(!list.items.is_empty());
+    }
+    println!(
+        "{:<12} {:>10.2?}/iter {:>10.2} MiB peak {:>10.2} MiB kept",
+        name,
+        elapsed / ITERATIONS,
+        peak as f64 / (1024.0 * 1024.0),
+        kept as f64 / (1024.0 * 1024.0)
+    );
+}
+
+fn main() {
+    for rows in &[20, 1000] {
+        let body = work_list_page(*rows);
+        println!(
+            "work-list with {} rows ({:.2} MiB):",
+            rows,
+            body.len() as f64 / (1024.0 * 1024.0)
+        );
+        bench("two pass", &body, two_pass);
+        bench("single pass", &body, single_pass);
+    }
+}
@@ -1,13 +1,39 @@
+use crate::error::{Error, ErrorKind, Result};
+use crate::{Crossref, CslItem};
+use reqwest::{header, StatusCode};
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::fmt;
+use std::io::Read;
+use std::str::FromStr;
+use url::percent_encoding::utf8_percent_encode;
 
-#[derive(Debug, Clone, Serialize)]
+/// the characters of a DOI that have to be encoded in the path of the resolver url
+mod encode_set {
+    use url::define_encode_set;
+    use url::percent_encoding::DEFAULT_ENCODE_SET;
+
+    define_encode_set! {
+        /// keeps the `/` between prefix and suffix, but encodes `%` and the characters of SICI DOIs
+        pub DOI_ENCODE_SET = [DEFAULT_ENCODE_SET] | {'%', '[', ']', '\\', '^', '|'}
+    }
+}
+
+/// The formats a DOI can be requested in via [content negotiation](https://citation.crosscite.org/docs.html)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 #[allow(missing_docs)]
 pub enum CnFormat {
     RdfXml,
     Turtle,
     CiteProcJson,
     CiteProcJsonIsh,
+    /// a formatted citation in the default style of the resolver
     Text,
+    /// a formatted citation, optionally in a specific `style` and `locale` like `fr-FR`
+    Citation {
+        style: Option<CitationStyle>,
+        locale: Option<String>,
+    },
     Ris,
     BibTex,
     CrossrefXml,
@@ -25,29 +51,415 @@ impl CnFormat {
             CnFormat::CiteProcJson => "application/json",
             CnFormat::CiteProcJsonIsh => "application/json",
             CnFormat::Text => "text/plain",
+            CnFormat::Citation { .. } => "text/plain",
             CnFormat::Ris => "text/plain",
-            CnFormat::BibTex => "text/xml",
+            CnFormat::BibTex => "text/plain",
             CnFormat::CrossrefXml => "text/xml",
-            CnFormat::DataciteXml => "",
+            CnFormat::DataciteXml => "text/xml",
             CnFormat::BibEntry => "text/plain",
             CnFormat::CrossrefTdm => "text/xml",
         }
     }
     /// the mime type's header
-    pub fn header(&self) -> &str {
-        match self {
+    pub fn header(&self) -> Cow<'_, str> {
+        Cow::Borrowed(match self {
             CnFormat::RdfXml => "application/rdf+xml",
             CnFormat::Turtle => "text/turtle",
-            CnFormat::CiteProcJson | CnFormat::CiteProcJsonIsh => {
-                "transform/application/vnd.citationstyles.csl+json"
-            }
+            CnFormat::CiteProcJson => "application/vnd.citationstyles.csl+json",
+            CnFormat::CiteProcJsonIsh => "transform/application/vnd.citationstyles.csl+json",
             CnFormat::Text => "text/x-bibliography",
+            CnFormat::Citation { style, locale } => {
+                let mut header = "text/x-bibliography".to_string();
+                if let Some(style) = style {
+                    header.push_str(&format!("; style={}", style));
+                }
+                if let Some(locale) = locale {
+                    header.push_str(&format!("; locale={}", locale));
+                }
+                return Cow::Owned(header);
+            }
             CnFormat::Ris => "application/x-research-info-systems",
             CnFormat::BibTex => "application/x-bibtex",
             CnFormat::CrossrefXml => "application/vnd.crossref.unixref+xml",
             CnFormat::DataciteXml => "application/vnd.datacite.datacite+xml",
             CnFormat::BibEntry => "application/x-bibtex",
             CnFormat::CrossrefTdm => "application/vnd.crossref.unixsd+xml",
+        })
+    }
+
+    /// a formatted citation in the default style of the resolver
+    pub fn text() -> Self {
+        CnFormat::Text
+    }
+
+    /// a formatted citation in the `style` and `locale`
+    pub fn citation(style: CitationStyle, locale: Option<&str>) -> Self {
+        CnFormat::Citation {
+            style: Some(style),
+            locale: locale.map(str::to_string),
         }
     }
 }
+
+/// The [CSL style](https://github.com/citation-style-language/styles) of a formatted citation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum CitationStyle {
+    /// American Psychological Association
+    Apa,
+    /// Chicago Manual of Style, author-date
+    Chicago,
+    /// Harvard, Cite Them Right
+    Harvard,
+    /// Institute of Electrical and Electronics Engineers
+    Ieee,
+    /// Modern Language Association
+    Mla,
+    /// Nature
+    Nature,
+    /// Vancouver
+    Vancouver,
+    /// any other style by its CSL name, like `elsevier-harvard`
+    Custom(String),
+}
+
+impl CitationStyle {
+    /// the CSL name of the style
+    pub fn as_str(&self) -> &str {
+        match self {
+            CitationStyle::Apa => "apa",
+            CitationStyle::Chicago => "chicago-author-date",
+            CitationStyle::Harvard => "harvard-cite-them-right",
+            CitationStyle::Ieee => "ieee",
+            CitationStyle::Mla => "modern-language-association",
+            CitationStyle::Nature => "nature",
+            CitationStyle::Vancouver => "vancouver",
+            CitationStyle::Custom(name) => name.as_str(),
+        }
+    }
+}
+
+impl fmt::Display for CitationStyle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+impl FromStr for CitationStyle {
+    type Err = Error;
+
+    /// accepts the short names as well as the CSL names, any other name is a `Custom` style
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "" => Err(ErrorKind::Config {
+                msg: "empty citation style".to_string(),
+            }
+            .into()),
+            "apa" => Ok(CitationStyle::Apa),
+            "chicago" | "chicago-author-date" => Ok(CitationStyle::Chicago),
+            "harvard" | "harvard-cite-them-right" => Ok(CitationStyle::Harvard),
+            "ieee" => Ok(CitationStyle::Ieee),
+            "mla" | "modern-language-association" => Ok(CitationStyle::Mla),
+            "nature" => Ok(CitationStyle::Nature),
+            "vancouver" => Ok(CitationStyle::Vancouver),
+            name => Ok(CitationStyle::Custom(name.to_string())),
+        }
+    }
+}
+
+impl Crossref {
+    /// Request the DOI from the resolver in the `format` via content negotiation.
+    ///
+    /// # Example
+    ///
+    /// ```edition2018
+    /// use crossref::{Crossref, CnFormat};
+    /// # fn run() -> Result<(), crossref::Error> {
+    /// let client = Crossref::builder().build()?;
+    ///
+    /// let bibtex = client.content_negotiate("10.1037/0003-066X.59.1.29", CnFormat::BibTex)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the resolver could not find the DOI `DoiNotFound`
+    /// Fails if the DOI is not available in the `format` `NotAcceptable`
+    pub fn content_negotiate(&self, doi: &str, format: CnFormat) -> Result<String> {
+        Ok(self.cn_request(doi, &format)?.text()?)
+    }
+
+    /// Request the DOI from the resolver in the `format` via content negotiation and return the raw body.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the resolver could not find the DOI `DoiNotFound`
+    /// Fails if the DOI is not available in the `format` `NotAcceptable`
+    pub fn content_negotiate_bytes(&self, doi: &str, format: CnFormat) -> Result<Vec<u8>> {
+        let mut body = Vec::new();
+        self.cn_request(doi, &format)?.read_to_end(&mut body)?;
+        Ok(body)
+    }
+
+    /// Request the DOI as [CSL-JSON](https://citeproc-js.readthedocs.io/en/latest/csl-json/markup.html)
+    ///
+    /// # Example
+    ///
+    /// ```edition2018
+    /// use crossref::Crossref;
+    /// # fn run() -> Result<(), crossref::Error> {
+    /// let client = Crossref::builder().build()?;
+    ///
+    /// let item = client.cite_proc("10.1037/0003-066X.59.1.29")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the resolver could not find the DOI `DoiNotFound`
+    /// Also fails if the response could not be decoded into a `CslItem`
+    pub fn cite_proc(&self, doi: &str) -> Result<CslItem> {
+        Ok(serde_json::from_str(
+            &self.content_negotiate(doi, CnFormat::CiteProcJson)?,
+        )?)
+    }
+
+    /// Return the formatted citation of the DOI in the `style` and `locale`, like `en-US`.
+    ///
+    /// # Example
+    ///
+    /// ```edition2018
+    /// use crossref::Crossref;
+    /// use crossref::cn::CitationStyle;
+    /// # fn run() -> Result<(), crossref::Error> {
+    /// let client = Crossref::builder().build()?;
+    ///
+    /// let citation = client.format_citation(
+    ///     "10.1037/0003-066X.59.1.29",
+    ///     CitationStyle::Apa,
+    ///     Some("fr-FR"),
+    /// )?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the resolver could not find the DOI `DoiNotFound`
+    /// Fails if the style or locale is not supported `NotAcceptable`
+    pub fn format_citation(
+        &self,
+        doi: &str,
+        style: CitationStyle,
+        locale: Option<&str>,
+    ) -> Result<String> {
+        self.content_negotiate(doi, CnFormat::citation(style, locale))
+            .map(|citation| citation.trim().to_string())
+    }
+
+    /// Return the formatted citations of all DOIs in the `style` and `locale`, in the same order.
+    ///
+    /// Each DOI is requested separately, a failed DOI does not affect the others.
+    ///
+    /// # Example
+    ///
+    /// ```edition2018
+    /// use crossref::Crossref;
+    /// use crossref::cn::CitationStyle;
+    /// # fn run() -> Result<(), crossref::Error> {
+    /// let client = Crossref::builder().build()?;
+    ///
+    /// let references = client.format_citations(
+    ///     &["10.1037/0003-066X.59.1.29", "10.1126/science.169.3946.635"],
+    ///     CitationStyle::Ieee,
+    ///     None,
+    /// );
+    /// for (i, citation) in references.into_iter().enumerate() {
+    ///     println!("[{}] {}", i + 1, citation?);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn format_citations<T: AsRef<str>>(
+        &self,
+        dois: &[T],
+        style: CitationStyle,
+        locale: Option<&str>,
+    ) -> Vec<Result<String>> {
+        dois.iter()
+            .map(|doi| self.format_citation(doi.as_ref(), style.clone(), locale))
+            .collect()
+    }
+
+    /// The url of the DOI at the resolver, with the DOI percent encoded.
+    ///
+    /// # Example
+    ///
+    /// ```edition2018
+    /// use crossref::Crossref;
+    /// # fn run() -> Result<(), crossref::Error> {
+    /// let client = Crossref::builder().build()?;
+    ///
+    /// assert_eq!(
+    ///     "https://doi.org/10.1002/(SICI)1097-4571(199806)49:8%3C693::AID-ASI4%3E3.0.CO;2-0",
+    ///     client.resolver_link("10.1002/(SICI)1097-4571(199806)49:8<693::AID-ASI4>3.0.CO;2-0")
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn resolver_link(&self, doi: &str) -> String {
+        format!(
+            "{}/{}",
+            self.resolver_url,
+            utf8_percent_encode(doi, encode_set::DOI_ENCODE_SET)
+        )
+    }
+
+    /// executes the content negotiation request and checks the status
+    fn cn_request(&self, doi: &str, format: &CnFormat) -> Result<reqwest::Response> {
+        let resp = self
+            .resolver_client
+            .get(&self.resolver_link(doi))
+            .header(header::ACCEPT, format.header().as_ref())
+            .send()?;
+        match resp.status() {
+            StatusCode::NOT_FOUND => Err(ErrorKind::DoiNotFound {
+                doi: doi.to_string(),
+            }
+            .into()),
+            StatusCode::NOT_ACCEPTABLE => Err(ErrorKind::NotAcceptable {
+                doi: doi.to_string(),
+                format: format.header().to_string(),
+            }
+            .into()),
+            _ => Ok(resp.error_for_status()?),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{serve, serve_once};
+
+    #[test]
+    fn content_negotiate_accept_header() {
+        let (url, handle) = serve_once("200 OK", "@article{Ray_2004}");
+        let client = Crossref::builder().resolver_url(&url).build().unwrap();
+
+        let bibtex = client
+            .content_negotiate("10.1037/0003-066x.59.1.29", CnFormat::BibTex)
+            .unwrap();
+        let request = handle.join().unwrap().to_lowercase();
+
+        assert_eq!("@article{Ray_2004}", bibtex);
+        assert!(request.starts_with("get /10.1037/0003-066x.59.1.29 "));
+        assert!(request.contains("accept: application/x-bibtex"));
+    }
+
+    #[test]
+    fn content_negotiate_without_token() {
+        let (url, handle) = serve_once("200 OK", "TY  - JOUR");
+        let client = Crossref::builder()
+            .token("Bearer secret")
+            .resolver_url(&url)
+            .build()
+            .unwrap();
+
+        client
+            .content_negotiate("10.1000/a#b?c d", CnFormat::Ris)
+            .unwrap();
+        let request = handle.join().unwrap().to_lowercase();
+
+        assert!(request.starts_with("get /10.1000/a%23b%3fc%20d "));
+        assert!(!request.contains("authorization"));
+        assert!(!request.contains("secret"));
+    }
+
+    #[test]
+    fn text_header() {
+        assert_eq!("text/x-bibliography", CnFormat::text().header());
+        assert_eq!(
+            "text/x-bibliography; style=apa; locale=fr-FR",
+            CnFormat::citation(CitationStyle::Apa, Some("fr-FR")).header()
+        );
+        assert_eq!(
+            "text/x-bibliography; style=elsevier-harvard",
+            CnFormat::citation("elsevier-harvard".parse().unwrap(), None).header()
+        );
+        assert_eq!(
+            CitationStyle::Chicago,
+            "chicago".parse::<CitationStyle>().unwrap()
+        );
+    }
+
+    #[test]
+    fn format_citation() {
+        let (url, handle) = serve_once(
+            "200 OK",
+            "Ray, O. (2004). How the Mind Hurts and Heals the Body. American Psychologist, 59(1), 29-40.\n",
+        );
+        let client = Crossref::builder().resolver_url(&url).build().unwrap();
+
+        let citation = client
+            .format_citation("10.1037/0003-066x.59.1.29", CitationStyle::Apa, None)
+            .unwrap();
+        let request = handle.join().unwrap().to_lowercase();
+
+        assert!(citation.starts_with("Ray, O. (2004)."));
+        assert!(!citation.ends_with('\n'));
+        assert!(request.contains("accept: text/x-bibliography; style=apa"));
+    }
+
+    #[test]
+    fn content_negotiate_not_found() {
+        let (url, handle) = serve_once("404 Not Found", "");
+        let client = Crossref::builder().resolver_url(&url).build().unwrap();
+
+        let err = client
+            .content_negotiate_bytes("10.1037/missing", CnFormat::Ris)
+            .unwrap_err();
+        handle.join().unwrap();
+
+        assert_eq!(
+            "DOI `10.1037/missing` could not be resolved",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn content_negotiate_not_acceptable() {
+        let (url, handle) = serve_once("406 Not Acceptable", "");
+        let client = Crossref::builder().resolver_url(&url).build().unwrap();
+
+        let err = client
+            .content_negotiate("10.5061/dryad.8515", CnFormat::CrossrefTdm)
+            .unwrap_err();
+        handle.join().unwrap();
+
+        assert!(err.to_string().contains("is not available as"));
+    }
+
+    #[test]
+    fn content_negotiate_read_error() {
+        // the body is not valid chunked encoding, so reading it fails
+        let (url, handle) = serve(vec![(
+            "200 OK",
+            "Transfer-Encoding: chunked\r\n",
+            "not a chunk",
+        )]);
+        let client = Crossref::builder().resolver_url(&url).build().unwrap();
+
+        let err = client
+            .content_negotiate_bytes("10.1037/0003-066x.59.1.29", CnFormat::Ris)
+            .unwrap_err();
+        handle.join().unwrap();
+
+        // displayed as is, not as an error of serde
+        assert_eq!(
+            "error reading a body from connection: Invalid chunk size line: Invalid Size",
+            err.to_string()
+        );
+    }
+}
@@ -1,6 +1,24 @@
-use crossref::{query::*, Crossref, Order, Sort, WorkResultControl, WorksQuery};
-use std::{fs, path::PathBuf};
+use chrono::{Datelike, NaiveDate, Utc};
+use crossref::export::{ExportFormat, ExportWriter};
+use crossref::query::{facet::FacetCount, funders::FundersFilter, members::MembersFilter};
+use crossref::response::FacetMap;
+use crossref::Funder;
+use crossref::{doi, CitationStyle, CnFormat, CslItem, Work};
+use crossref::{
+    query::*, Crossref, FieldQuery, Order, Sort, WorkListQuery, WorkResultControl, Works,
+    WorksFilter, WorksQuery,
+};
+use flate2::{write::GzEncoder, Compression};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::io::{BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::Duration;
+use std::{fmt, fs, str::FromStr, thread};
 use structopt::StructOpt;
+use url::Url;
 
 macro_rules! query {
     ($query:ident, $opts:ident) => {
@@ -40,9 +58,242 @@ enum App {
         deep_page: bool,
         #[structopt(flatten)]
         opts: Opts,
+        #[structopt(flatten)]
+        fields: FieldQueries,
+        #[structopt(
+            long = "facet",
+            parse(try_from_str),
+            help = "Count the values of a facet as `name[:count]` and print them as table, e.g. type-name or publisher-name:10"
+        )]
+        facets: Vec<FacetCount>,
+        #[structopt(flatten)]
+        format: FormatOpts,
+        #[structopt(subcommand)]
+        combined: Option<Combined>,
+    },
+    #[structopt(
+        name = "count",
+        about = "Print the number of works that match the query"
+    )]
+    Count {
+        #[structopt(flatten)]
+        opts: Opts,
+        #[structopt(flatten)]
+        fields: FieldQueries,
+        #[structopt(subcommand)]
+        combined: Option<Combined>,
+    },
+    #[structopt(
+        name = "stats",
+        about = "Print the facets of the works that match the query, such as works per year, type and publisher"
+    )]
+    Stats {
+        #[structopt(flatten)]
+        opts: Opts,
+        #[structopt(flatten)]
+        fields: FieldQueries,
+        #[structopt(
+            long = "facet",
+            parse(try_from_str),
+            help = "The facets to count as `name[:count]`, published, type-name, publisher-name:20 and container-title:20 by default"
+        )]
+        facets: Vec<FacetCount>,
+        #[structopt(long = "json", help = "Print the facets as json instead of tables")]
+        json: bool,
+        #[structopt(subcommand)]
+        combined: Option<Combined>,
+    },
+    #[structopt(
+        name = "harvest",
+        about = "Deep page all works of the query into gzipped JSON Lines files, resumable from checkpoints. \
+                 Crossref cursors expire after five minutes without a request."
+    )]
+    Harvest {
+        #[structopt(
+            long = "out",
+            parse(from_os_str),
+            help = "The directory of the files and the checkpoints, one sub directory per shard"
+        )]
+        out_dir: PathBuf,
+        #[structopt(
+            long = "resume",
+            help = "Continue an interrupted harvest from its checkpoints"
+        )]
+        resume: bool,
+        #[structopt(
+            long = "rotate",
+            default_value = "100000",
+            help = "How many works are written to a file before the next one is started"
+        )]
+        rotate: usize,
+        #[structopt(
+            long = "rows",
+            default_value = "1000",
+            help = "How many works are requested per page, at most 1000"
+        )]
+        rows: usize,
+        #[structopt(
+            long = "shard-by",
+            parse(try_from_str),
+            help = "Split the query into publication date ranges of a year or month, requires --from"
+        )]
+        shard_by: Option<ShardBy>,
+        #[structopt(
+            long = "from",
+            help = "The first year or month of the publication dates, e.g. 2015 or 2015-06"
+        )]
+        from: Option<String>,
+        #[structopt(
+            long = "until",
+            help = "The last year or month of the publication dates, the current one by default"
+        )]
+        until: Option<String>,
+        #[structopt(
+            short = "j",
+            long = "concurrency",
+            default_value = "2",
+            help = "How many shards are harvested at the same time"
+        )]
+        concurrency: usize,
+        #[structopt(
+            long = "retries",
+            default_value = "3",
+            help = "How often timeouts, rate limits and server errors are retried"
+        )]
+        retries: u32,
+        #[structopt(flatten)]
+        opts: Opts,
+        #[structopt(flatten)]
+        fields: FieldQueries,
         #[structopt(subcommand)]
         combined: Option<Combined>,
     },
+    #[structopt(
+        name = "agency",
+        about = "Print the registration agency of DOIs as tab separated DOI, id and label"
+    )]
+    Agency {
+        #[structopt(flatten)]
+        dois: DoiInput,
+        #[structopt(flatten)]
+        client_opts: ClientOpts,
+        #[structopt(flatten)]
+        out: Out,
+    },
+    #[structopt(name = "random", about = "Print random DOIs, one per line")]
+    Random {
+        #[structopt(
+            short = "n",
+            long = "count",
+            default_value = "10",
+            help = "How many DOIs to print, more than 100 are sampled in several requests"
+        )]
+        count: usize,
+        #[structopt(
+            short = "f",
+            long = "filter",
+            help = "Sample only works that match `name:value`, repeatable or comma separated"
+        )]
+        filters: Vec<String>,
+        #[structopt(flatten)]
+        client_opts: ClientOpts,
+        #[structopt(flatten)]
+        out: Out,
+    },
+    #[structopt(
+        name = "funder-tree",
+        about = "Print the hierarchy of a funder as indented tree with the work counts"
+    )]
+    FunderTree {
+        #[structopt(help = "The id of the funder in the funder registry, e.g. 100000001")]
+        id: String,
+        #[structopt(
+            long = "no-counts",
+            help = "Skip the request per descendant for its work count. \
+                    The descendants are only known from the response of the funder, \
+                    so a dry run only lists the request of the funder"
+        )]
+        no_counts: bool,
+        #[structopt(flatten)]
+        client_opts: ClientOpts,
+        #[structopt(flatten)]
+        out: Out,
+    },
+    #[structopt(name = "cite", about = "Print formatted citations of DOIs")]
+    Cite {
+        #[structopt(flatten)]
+        dois: DoiInput,
+        #[structopt(
+            long = "style",
+            parse(try_from_str),
+            help = "The CSL citation style, e.g. apa, chicago, harvard, ieee, mla, nature, vancouver or any CSL name"
+        )]
+        style: Option<CitationStyle>,
+        #[structopt(long = "locale", help = "The locale of the citations, e.g. en-US")]
+        locale: Option<String>,
+        #[structopt(flatten)]
+        client_opts: ClientOpts,
+        #[structopt(flatten)]
+        out: Out,
+    },
+    #[structopt(name = "export", about = "Fetch DOIs in a reference format")]
+    Export {
+        #[structopt(flatten)]
+        dois: DoiInput,
+        #[structopt(
+            long = "format",
+            parse(try_from_str),
+            default_value = "bibtex",
+            help = "The format of the DOIs: bibtex, ris, csl-json, turtle or unixref"
+        )]
+        format: CnExport,
+        #[structopt(flatten)]
+        client_opts: ClientOpts,
+        #[structopt(flatten)]
+        out: Out,
+    },
+    #[structopt(
+        name = "resolve",
+        about = "Fetch the works of many DOIs as JSON Lines, in the order they are resolved"
+    )]
+    Resolve {
+        #[structopt(flatten)]
+        dois: DoiInput,
+        #[structopt(
+            short = "j",
+            long = "concurrency",
+            default_value = "4",
+            help = "How many DOIs are fetched at the same time"
+        )]
+        concurrency: usize,
+        #[structopt(
+            long = "retries",
+            default_value = "3",
+            help = "How often timeouts, rate limits and server errors are retried"
+        )]
+        retries: u32,
+        #[structopt(
+            long = "errors",
+            parse(from_os_str),
+            default_value = "errors.jsonl",
+            help = "The JSON Lines report of the DOIs that could not be resolved"
+        )]
+        errors: PathBuf,
+        #[structopt(flatten)]
+        client_opts: ClientOpts,
+        #[structopt(flatten)]
+        out: Out,
+    },
+    #[structopt(
+        name = "config",
+        about = "Inspect the client settings. Flags take precedence over the environment variables \
+                 CROSSREF_MAILTO, CROSSREF_PLUS_TOKEN and CROSSREF_BASE_URL, \
+                 which take precedence over ~/.config/crossref/config.toml"
+    )]
+    Config {
+        #[structopt(subcommand)]
+        cmd: ConfigCmd,
+    },
     #[structopt(name = "funders", about = "Query crossref funders")]
     Funders {
         #[structopt(flatten)]
@@ -82,148 +333,1994 @@ enum App {
     },
 }
 
-impl App {
-    pub fn client_opts(&self) -> &ClientOpts {
-        match self {
-            App::Works { opts, .. } | App::Funders { opts, .. } | App::Members { opts, .. } => {
-                &opts.client_opts
-            }
+impl App {
+    pub fn client_opts(&self) -> &ClientOpts {
+        match self {
+            App::Works { opts, .. }
+            | App::Count { opts, .. }
+            | App::Stats { opts, .. }
+            | App::Harvest { opts, .. }
+            | App::Funders { opts, .. }
+            | App::Members { opts, .. } => &opts.client_opts,
+
+            App::Prefixes { client_opts, .. }
+            | App::Types { client_opts, .. }
+            | App::Journals { client_opts, .. }
+            | App::Cite { client_opts, .. }
+            | App::Export { client_opts, .. }
+            | App::Resolve { client_opts, .. }
+            | App::Agency { client_opts, .. }
+            | App::Random { client_opts, .. }
+            | App::FunderTree { client_opts, .. }
+            | App::Config {
+                cmd: ConfigCmd::Show { client_opts, .. },
+            } => client_opts,
+        }
+    }
+
+    pub fn out(&self) -> &Out {
+        match self {
+            App::Works { opts, .. }
+            | App::Count { opts, .. }
+            | App::Stats { opts, .. }
+            | App::Harvest { opts, .. }
+            | App::Funders { opts, .. }
+            | App::Members { opts, .. } => &opts.out,
+
+            App::Prefixes { out, .. }
+            | App::Types { out, .. }
+            | App::Journals { out, .. }
+            | App::Cite { out, .. }
+            | App::Export { out, .. }
+            | App::Resolve { out, .. }
+            | App::Agency { out, .. }
+            | App::Random { out, .. }
+            | App::FunderTree { out, .. }
+            | App::Config {
+                cmd: ConfigCmd::Show { out, .. },
+            } => out,
+        }
+    }
+
+    /// the requests the command would send, without sending them
+    pub fn requests(&self, client: &Crossref) -> Result<Vec<Request>, failure::Error> {
+        let url = |query: &dyn Fn() -> crossref::Result<String>| -> crossref::Result<Request> {
+            Ok(Request {
+                url: query()?,
+                accept: None,
+            })
+        };
+        let base = client.base_url.as_str();
+        Ok(match self {
+            App::Types { id, .. } => vec![url(&|| match id {
+                Some(id) => Types::Identifier(id.id().to_string()).to_url(base),
+                None => Types::All.to_url(base),
+            })?],
+            App::Prefixes { id, .. } => {
+                vec![url(&|| Prefixes::Identifier(id.clone()).to_url(base))?]
+            }
+            App::Journals { id, .. } => {
+                vec![url(&|| Journals::Identifier(id.clone()).to_url(base))?]
+            }
+            App::Members { opts, .. } => vec![url(&|| match &opts.id {
+                Some(id) => Members::Identifier(id.clone()).to_url(base),
+                None => Members::Query(members_query(opts)?).to_url(base),
+            })?],
+            App::Funders { opts, .. } => vec![url(&|| match &opts.id {
+                Some(id) => Funders::Identifier(id.clone()).to_url(base),
+                None => Funders::Query(funders_query(opts)?).to_url(base),
+            })?],
+            App::Works {
+                opts,
+                fields,
+                facets,
+                combined,
+                deep_page,
+                ..
+            } => vec![url(&|| match &opts.id {
+                Some(id) => Works::Identifier(id.clone()).to_url(base),
+                None => {
+                    let mut query = works_query(opts, fields, facets, combined.as_ref())?;
+                    if *deep_page && query.query().result_control.is_none() {
+                        // the first page of the deep paging
+                        query.query_mut().result_control = Some(WorkResultControl::new_cursor());
+                    }
+                    query.to_url(base)
+                }
+            })?],
+            App::Count {
+                opts,
+                fields,
+                combined,
+            } => vec![url(&|| {
+                count_query(opts, fields, combined.as_ref())?.to_url(base)
+            })?],
+            App::Stats {
+                opts,
+                fields,
+                facets,
+                combined,
+                ..
+            } => vec![url(&|| {
+                stats_query(opts, fields, facets, combined.as_ref())?.to_url(base)
+            })?],
+            App::Harvest { .. } => {
+                let mut requests = Vec::new();
+                for shard in self.shards()? {
+                    let mut query = shard.query;
+                    query.query_mut().result_control = Some(WorkResultControl::new_cursor());
+                    requests.push(url(&|| query.to_url(base))?);
+                }
+                requests
+            }
+            App::Resolve { dois, .. } => {
+                let mut requests = Vec::new();
+                for doi in valid_dois(&dois.read()?) {
+                    requests.push(url(&|| Works::Identifier(doi.clone()).to_url(base))?);
+                }
+                requests
+            }
+            App::Agency { dois, .. } => {
+                let mut requests = Vec::new();
+                for doi in valid_dois(&dois.read()?) {
+                    requests.push(url(&|| Works::Agency(doi.clone()).to_url(base))?);
+                }
+                requests
+            }
+            App::Random { count, filters, .. } => {
+                vec![url(&|| {
+                    WorkListQuery::Works(random_query(*count, filters)?).to_url(base)
+                })?]
+            }
+            // the requests of the descendants depend on the response of the funder
+            App::FunderTree { id, .. } => {
+                vec![url(&|| Funders::Identifier(id.clone()).to_url(base))?]
+            }
+            App::Cite {
+                dois,
+                style,
+                locale,
+                ..
+            } => {
+                let format = CnFormat::Citation {
+                    style: style.clone(),
+                    locale: locale.clone(),
+                };
+                Request::negotiate(client, &dois.read()?, &format)
+            }
+            App::Export { dois, format, .. } => {
+                Request::negotiate(client, &dois.read()?, &format.cn_format())
+            }
+            App::Config { .. } => Vec::new(),
+        })
+    }
+
+    /// the date range shards of the `harvest` command, a single one if it is not sharded
+    fn shards(&self) -> Result<Vec<Shard>, failure::Error> {
+        let (shard_by, from, until, opts, fields, combined) = match self {
+            App::Harvest {
+                shard_by,
+                from,
+                until,
+                opts,
+                fields,
+                combined,
+                ..
+            } => (shard_by, from, until, opts, fields, combined),
+            _ => return Ok(Vec::new()),
+        };
+        if opts.id.is_some()
+            || opts.limit.is_some()
+            || opts.offset.is_some()
+            || opts.sample.is_some()
+        {
+            failure::bail!("harvest pages through all results, --id, --limit, --offset and --sample are not supported");
+        }
+        let query = works_query(opts, fields, &[], combined.as_ref())?;
+        let ranges = match (shard_by, from) {
+            (Some(shard_by), Some(from)) => {
+                shard_by.ranges(from, until.as_ref().map(String::as_str))?
+            }
+            (Some(_), None) => failure::bail!("--shard-by requires --from"),
+            (None, Some(from)) => {
+                let (name, start, _) = ShardBy::period(from)?;
+                let end = match until {
+                    Some(until) => ShardBy::period(until)?.2,
+                    None => Utc::now().naive_utc().date(),
+                };
+                vec![(
+                    format!(
+                        "{}-{}",
+                        name,
+                        until.as_ref().map(String::as_str).unwrap_or("now")
+                    ),
+                    start,
+                    end,
+                )]
+            }
+            (None, None) => {
+                return Ok(vec![Shard {
+                    name: "all".to_string(),
+                    query,
+                }]);
+            }
+        };
+        let dated = query.query().filter.iter().any(|filter| {
+            matches!(
+                filter,
+                WorksFilter::FromPubDate(_) | WorksFilter::UntilPubDate(_)
+            )
+        });
+        if dated {
+            failure::bail!("the shards set the publication dates, remove the from-pub-date and until-pub-date filters");
+        }
+        Ok(ranges
+            .into_iter()
+            .map(|(name, from, until)| {
+                let mut query = query.clone();
+                let filter = &mut query.query_mut().filter;
+                filter.push(WorksFilter::FromPubDate(from));
+                filter.push(WorksFilter::UntilPubDate(until));
+                Shard { name, query }
+            })
+            .collect())
+    }
+
+    pub fn get_value<W>(&self, writer: W, client: &Crossref) -> Result<(), failure::Error>
+    where
+        W: std::io::Write,
+    {
+        match self {
+            App::Types { id, .. } => {
+                if let Some(id) = id {
+                    Ok(serde_json::to_writer_pretty(writer, &client.type_(id)?)?)
+                } else {
+                    Ok(serde_json::to_writer_pretty(writer, &client.types()?)?)
+                }
+            }
+            App::Prefixes { id, .. } => Ok(serde_json::to_writer_pretty(
+                writer,
+                &client.prefix(id.as_str())?,
+            )?),
+            App::Journals { id, .. } => Ok(serde_json::to_writer_pretty(
+                writer,
+                &client.journal(id.as_str())?,
+            )?),
+            App::Members { opts, .. } => {
+                if let Some(id) = &opts.id {
+                    Ok(serde_json::to_writer_pretty(
+                        writer,
+                        &client.member(id.as_str())?,
+                    )?)
+                } else {
+                    Ok(serde_json::to_writer_pretty(
+                        writer,
+                        &client.members(members_query(opts)?)?,
+                    )?)
+                }
+            }
+            App::Funders { opts, .. } => {
+                if let Some(id) = &opts.id {
+                    Ok(serde_json::to_writer_pretty(
+                        writer,
+                        &client.funder(id.as_str())?,
+                    )?)
+                } else {
+                    Ok(serde_json::to_writer_pretty(
+                        writer,
+                        &client.funders(funders_query(opts)?)?,
+                    )?)
+                }
+            }
+            App::Works {
+                opts,
+                fields,
+                facets,
+                combined,
+                deep_page,
+                format,
+            } => {
+                let writer = BufWriter::new(writer);
+                if let Some(id) = &opts.id {
+                    let work = client.work(id.as_str())?;
+                    return match format.format {
+                        None | Some(Format::Json) => {
+                            Ok(serde_json::to_writer_pretty(writer, &work)?)
+                        }
+                        Some(f) => Ok(RecordWriter::write_all(
+                            writer,
+                            f,
+                            &format.columns,
+                            Some(work),
+                        )?),
+                    };
+                }
+                let query = works_query(opts, fields, facets, combined.as_ref())?;
+                if !facets.is_empty() && format.format.is_none() {
+                    let mut writer = writer;
+                    Ok(write!(
+                        writer,
+                        "{}",
+                        facet_table(&client.works(query)?.facets)
+                    )?)
+                } else if *deep_page {
+                    // records are written as the pages arrive
+                    let works = client.deep_page(query).into_work_results();
+                    let f = format.format.unwrap_or(Format::Json);
+                    Ok(RecordWriter::write_results(
+                        writer,
+                        f,
+                        &format.columns,
+                        works,
+                    )?)
+                } else {
+                    let list = client.works(query)?;
+                    match format.format {
+                        None | Some(Format::Json) => {
+                            Ok(serde_json::to_writer_pretty(writer, &list)?)
+                        }
+                        Some(f) => Ok(RecordWriter::write_all(
+                            writer,
+                            f,
+                            &format.columns,
+                            list.items,
+                        )?),
+                    }
+                }
+            }
+            App::Cite {
+                dois,
+                style,
+                locale,
+                ..
+            } => {
+                let format = CnFormat::Citation {
+                    style: style.clone(),
+                    locale: locale.clone(),
+                };
+                let mut writer = BufWriter::new(writer);
+                let dois = dois.read()?;
+                let failed = each_doi(&dois, |input| -> Result<(), failure::Error> {
+                    let doi =
+                        doi::normalize(input).ok_or_else(|| failure::format_err!("invalid DOI"))?;
+                    let citation = client.content_negotiate(&doi, format.clone())?;
+                    writeln!(writer, "{}", citation.trim())?;
+                    Ok(())
+                });
+                writer.flush()?;
+                failed_dois(failed, dois.len())
+            }
+            App::Export { dois, format, .. } => {
+                let mut writer = BufWriter::new(writer);
+                let dois = dois.read()?;
+                let normalize = |input: &str| {
+                    doi::normalize(input).ok_or_else(|| failure::format_err!("invalid DOI"))
+                };
+                let failed = match format {
+                    CnExport::CslJson => {
+                        // the items are collected into one array
+                        let mut items = 0;
+                        let failed = each_doi(&dois, |input| -> Result<(), failure::Error> {
+                            let item = client.cite_proc(&normalize(input)?)?;
+                            writer.write_all(if items == 0 { b"[\n" } else { b",\n" })?;
+                            serde_json::to_writer_pretty(&mut writer, &item)?;
+                            items += 1;
+                            Ok(())
+                        });
+                        writer.write_all(if items == 0 { b"[]\n" } else { b"\n]\n" })?;
+                        failed
+                    }
+                    CnExport::Unixref => {
+                        // the records are collected under one root element
+                        writer.write_all(
+                            b"<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<doi_records>\n",
+                        )?;
+                        let failed = each_doi(&dois, |input| -> Result<(), failure::Error> {
+                            let body =
+                                client.content_negotiate(&normalize(input)?, format.cn_format())?;
+                            writeln!(writer, "{}", unixref_records(&body))?;
+                            Ok(())
+                        });
+                        writer.write_all(b"</doi_records>\n")?;
+                        failed
+                    }
+                    _ => each_doi(&dois, |input| -> Result<(), failure::Error> {
+                        let body = client
+                            .content_negotiate_bytes(&normalize(input)?, format.cn_format())?;
+                        writer.write_all(&body)?;
+                        if !body.ends_with(b"\n") {
+                            writer.write_all(b"\n")?;
+                        }
+                        Ok(())
+                    }),
+                };
+                writer.flush()?;
+                failed_dois(failed, dois.len())
+            }
+            App::Resolve {
+                dois,
+                concurrency,
+                retries,
+                errors,
+                client_opts,
+                out,
+            } => {
+                let mut writer = BufWriter::new(writer);
+                let mut report = BufWriter::new(fs::File::create(errors)?);
+                let mut failed = 0;
+
+                let inputs = dois.read()?;
+                let mut seen = HashSet::new();
+                let mut jobs = Vec::new();
+                for input in &inputs {
+                    match doi::normalize(input) {
+                        Some(doi) => {
+                            if seen.insert(doi.clone()) {
+                                jobs.push((input.clone(), doi));
+                            }
+                        }
+                        None => {
+                            ResolveError::write(&mut report, input, None, "invalid DOI", 0)?;
+                            failed += 1;
+                        }
+                    }
+                }
+
+                let mut progress = Progress::new(jobs.len(), out.silent);
+                let results = resolve_all(client_opts, jobs, *concurrency, *retries);
+                for resolved in results {
+                    match resolved.result {
+                        Ok(work) => {
+                            serde_json::to_writer(&mut writer, &work)?;
+                            writer.write_all(b"\n")?;
+                            progress.inc(false);
+                        }
+                        Err(err) => {
+                            ResolveError::write(
+                                &mut report,
+                                &resolved.input,
+                                Some(&resolved.doi),
+                                &err,
+                                resolved.attempts,
+                            )?;
+                            failed += 1;
+                            progress.inc(true);
+                        }
+                    }
+                }
+                progress.finish();
+                writer.flush()?;
+                report.flush()?;
+                if failed > 0 {
+                    Err(failure::format_err!(
+                        "{} of {} DOIs could not be resolved, see {}",
+                        failed,
+                        inputs.len(),
+                        errors.display()
+                    ))
+                } else {
+                    Ok(())
+                }
+            }
+            App::Count {
+                opts,
+                fields,
+                combined,
+            } => {
+                let mut writer = writer;
+                let list = client.works(count_query(opts, fields, combined.as_ref())?)?;
+                Ok(writeln!(writer, "{}", list.total_results)?)
+            }
+            App::Stats {
+                opts,
+                fields,
+                facets,
+                json,
+                combined,
+            } => {
+                let mut writer = writer;
+                let list = client.works(stats_query(opts, fields, facets, combined.as_ref())?)?;
+                if *json {
+                    let stats = serde_json::json!({
+                        "total-results": list.total_results,
+                        "facets": list.facets,
+                    });
+                    serde_json::to_writer_pretty(&mut writer, &stats)?;
+                    Ok(writeln!(writer)?)
+                } else {
+                    writeln!(writer, "total-results {}\n", list.total_results)?;
+                    Ok(write!(writer, "{}", facet_table(&list.facets))?)
+                }
+            }
+            App::Harvest {
+                out_dir,
+                resume,
+                rotate,
+                rows,
+                concurrency,
+                retries,
+                opts,
+                ..
+            } => {
+                let shards = self.shards()?;
+                let harvester = Harvester {
+                    out_dir: out_dir.clone(),
+                    resume: *resume,
+                    rotate: (*rotate).max(1),
+                    rows: (*rows).clamp(1, 1000),
+                    retries: *retries,
+                };
+                harvester.run(&opts.client_opts, shards, *concurrency, opts.out.silent)
+            }
+            App::Agency { dois, .. } => {
+                let mut writer = BufWriter::new(writer);
+                let dois = dois.read()?;
+                let failed = each_doi(&dois, |input| -> Result<(), failure::Error> {
+                    let doi =
+                        doi::normalize(input).ok_or_else(|| failure::format_err!("invalid DOI"))?;
+                    let agency = client.work_agency(&doi)?;
+                    writeln!(
+                        writer,
+                        "{}\t{}\t{}",
+                        agency.doi(),
+                        agency.agency().id,
+                        agency.agency().label.as_deref().unwrap_or_default()
+                    )?;
+                    Ok(())
+                });
+                writer.flush()?;
+                failed_dois(failed, dois.len())
+            }
+            App::Random { count, filters, .. } => {
+                let mut writer = BufWriter::new(writer);
+                let mut dois = Vec::new();
+                let mut seen = HashSet::new();
+                // samples of different requests may overlap, but not forever
+                let mut requests = count / MAX_SAMPLE + 10;
+                while dois.len() < *count && requests > 0 {
+                    let sample = (count - dois.len()).min(MAX_SAMPLE);
+                    let list = client.works(random_query(sample, filters)?)?;
+                    if list.items.is_empty() {
+                        break;
+                    }
+                    for work in list.items {
+                        if dois.len() < *count && seen.insert(work.doi.clone()) {
+                            dois.push(work.doi);
+                        }
+                    }
+                    requests -= 1;
+                }
+                for doi in dois {
+                    writeln!(writer, "{}", doi)?;
+                }
+                Ok(writer.flush()?)
+            }
+            App::FunderTree { id, no_counts, .. } => {
+                let funder = client.funder(id)?;
+                let mut counts = HashMap::new();
+                counts.insert(
+                    funder.id.clone(),
+                    (funder.work_count, funder.descendant_work_count),
+                );
+                if !no_counts {
+                    // a descendant that could not be requested is shown without counts
+                    for (id, descendant) in client.funder_descendants(id)? {
+                        match descendant {
+                            Ok(descendant) => {
+                                counts.insert(
+                                    descendant.id,
+                                    (descendant.work_count, descendant.descendant_work_count),
+                                );
+                            }
+                            Err(err) => eprintln!("{}: {}", id, err),
+                        }
+                    }
+                }
+                let mut writer = writer;
+                Ok(write!(writer, "{}", funder_tree(&funder, &counts))?)
+            }
+            App::Config {
+                cmd: ConfigCmd::Show { client_opts, .. },
+            } => Ok(client_opts.settings()?.show(writer)?),
+        }
+    }
+}
+
+#[derive(Debug, StructOpt)]
+enum ConfigCmd {
+    #[structopt(
+        name = "show",
+        about = "Print the effective settings and where they come from, secrets are redacted"
+    )]
+    Show {
+        #[structopt(flatten)]
+        client_opts: ClientOpts,
+        #[structopt(flatten)]
+        out: Out,
+    },
+}
+
+/// how the `harvest` command splits the publication dates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ShardBy {
+    Year,
+    Month,
+}
+
+impl ShardBy {
+    /// the name, first and last day of a `YYYY` or `YYYY-MM` period
+    fn period(s: &str) -> Result<(String, NaiveDate, NaiveDate), failure::Error> {
+        let invalid = || failure::format_err!("invalid period `{}`, expected YYYY or YYYY-MM", s);
+        let mut parts = s.trim().splitn(2, '-');
+        let year: i32 = parts
+            .next()
+            .unwrap_or_default()
+            .parse()
+            .map_err(|_| invalid())?;
+        match parts.next() {
+            None => Ok((
+                year.to_string(),
+                NaiveDate::from_ymd_opt(year, 1, 1).ok_or_else(invalid)?,
+                NaiveDate::from_ymd_opt(year, 12, 31).ok_or_else(invalid)?,
+            )),
+            Some(month) => {
+                let month: u32 = month.parse().map_err(|_| invalid())?;
+                let first = NaiveDate::from_ymd_opt(year, month, 1).ok_or_else(invalid)?;
+                Ok((
+                    format!("{}-{:02}", year, month),
+                    first,
+                    next_month(first).pred_opt().ok_or_else(invalid)?,
+                ))
+            }
+        }
+    }
+
+    /// the consecutive `(name, first day, last day)` ranges from the period `from` until the period `until`
+    fn ranges(
+        self,
+        from: &str,
+        until: Option<&str>,
+    ) -> Result<Vec<(String, NaiveDate, NaiveDate)>, failure::Error> {
+        let (_, mut start, _) = ShardBy::period(from)?;
+        let end = match until {
+            Some(until) => ShardBy::period(until)?.2,
+            None => Utc::now().naive_utc().date(),
+        };
+        if start > end {
+            failure::bail!("--from is after --until");
+        }
+        let mut ranges = Vec::new();
+        while start <= end {
+            let next = match self {
+                ShardBy::Year => NaiveDate::from_ymd_opt(start.year() + 1, 1, 1),
+                ShardBy::Month => Some(next_month(start)),
+            }
+            .ok_or_else(|| failure::format_err!("date out of range"))?;
+            let name = match self {
+                ShardBy::Year => start.year().to_string(),
+                ShardBy::Month => format!("{}-{:02}", start.year(), start.month()),
+            };
+            let last = next.pred_opt().unwrap_or(next).min(end);
+            ranges.push((name, start, last));
+            start = next;
+        }
+        Ok(ranges)
+    }
+}
+
+/// the first day of the month after the month of `date`
+fn next_month(date: NaiveDate) -> NaiveDate {
+    if date.month() == 12 {
+        NaiveDate::from_ymd_opt(date.year() + 1, 1, 1)
+    } else {
+        NaiveDate::from_ymd_opt(date.year(), date.month() + 1, 1)
+    }
+    .unwrap_or(date)
+}
+
+impl FromStr for ShardBy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "year" => Ok(ShardBy::Year),
+            "month" => Ok(ShardBy::Month),
+            other => Err(format!(
+                "unknown shard period `{}`, expected year or month",
+                other
+            )),
+        }
+    }
+}
+
+/// a part of the harvest with its own directory and checkpoint
+struct Shard {
+    name: String,
+    query: WorkListQuery,
+}
+
+/// the name of the checkpoint file in the directory of a shard
+const CHECKPOINT: &str = "checkpoint.json";
+
+/// the state of a shard after its last completed page
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct Checkpoint {
+    /// the query without cursor, a resumed harvest must use the same one
+    url: String,
+    /// the cursor of the next page
+    cursor: Option<String>,
+    /// whether all pages were harvested
+    done: bool,
+    total_results: Option<usize>,
+    /// the works written so far
+    records: usize,
+    /// the index of the file that is written to
+    file: usize,
+    /// the works in the current file
+    file_records: usize,
+    /// the length of the current file after the last page, anything after it is discarded on resume
+    file_len: u64,
+}
+
+impl Checkpoint {
+    fn read(path: &Path) -> Result<Self, failure::Error> {
+        serde_json::from_str(&fs::read_to_string(path)?)
+            .map_err(|err| failure::format_err!("invalid checkpoint {}: {}", path.display(), err))
+    }
+
+    /// replaces the checkpoint at once, so it is never half written
+    fn write(&self, path: &Path) -> Result<(), failure::Error> {
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_vec_pretty(self)?)?;
+        Ok(fs::rename(&tmp, path)?)
+    }
+
+    /// drops a page of the current file in `dir` that was written after the checkpoint
+    fn discard_unfinished(&self, dir: &Path) -> Result<(), failure::Error> {
+        let part = dir.join(part_name(self.file));
+        if part.exists() {
+            fs::OpenOptions::new()
+                .write(true)
+                .open(&part)?
+                .set_len(self.file_len)?;
+        }
+        Ok(())
+    }
+}
+
+/// the name of the `index`th file of a shard
+fn part_name(index: usize) -> String {
+    format!("part-{:05}.jsonl.gz", index)
+}
+
+/// what a shard reports after each page
+struct PageEvent {
+    shard: usize,
+    records: usize,
+    total_results: Option<usize>,
+    /// `Some` once the shard finished
+    result: Option<Result<(), String>>,
+}
+
+/// deep pages shards into rotated gzipped JSON Lines files
+struct Harvester {
+    out_dir: PathBuf,
+    resume: bool,
+    rotate: usize,
+    rows: usize,
+    retries: u32,
+}
+
+impl Harvester {
+    /// Harvests the shards on `concurrency` threads and prints the progress on stderr.
+    fn run(
+        self,
+        client_opts: &ClientOpts,
+        shards: Vec<Shard>,
+        concurrency: usize,
+        silent: bool,
+    ) -> Result<(), failure::Error> {
+        fs::create_dir_all(&self.out_dir)?;
+        let names: Vec<_> = shards.iter().map(|shard| shard.name.clone()).collect();
+        let jobs: Vec<_> = shards.into_iter().enumerate().collect();
+        let rx = spawn_workers(
+            client_opts,
+            jobs,
+            concurrency,
+            move |client, (index, shard), tx| {
+                let result = match client {
+                    Ok(client) => self.harvest(client, index, &shard, tx),
+                    Err(err) => Err(failure::format_err!("{}", err)),
+                };
+                tx.send(PageEvent {
+                    shard: index,
+                    records: 0,
+                    total_results: None,
+                    result: Some(result.map_err(|err| err.to_string())),
+                })
+                .is_ok()
+            },
+        );
+
+        let mut records = vec![0; names.len()];
+        let mut totals = vec![None; names.len()];
+        let (mut finished, mut failed) = (0, 0);
+        for event in rx {
+            match event.result {
+                Some(Ok(())) => finished += 1,
+                Some(Err(err)) => {
+                    finished += 1;
+                    failed += 1;
+                    if !silent {
+                        eprintln!();
+                    }
+                    eprintln!("shard {}: {}", names[event.shard], err);
+                }
+                None => {
+                    records[event.shard] = event.records;
+                    totals[event.shard] = event.total_results;
+                }
+            }
+            if !silent {
+                let harvested: usize = records.iter().sum();
+                let total: usize = totals.iter().flatten().sum();
+                let percent = if total > 0 {
+                    format!(" ({:.1}%)", harvested as f64 * 100.0 / total as f64)
+                } else {
+                    String::new()
+                };
+                eprint!(
+                    "\rharvested {}/{} works{}, {}/{} shards finished",
+                    harvested,
+                    total,
+                    percent,
+                    finished,
+                    names.len()
+                );
+            }
+        }
+        if !silent {
+            eprintln!();
+        }
+        if failed > 0 {
+            failure::bail!(
+                "{} of {} shards failed, the finished pages are checkpointed and continued with --resume",
+                failed,
+                names.len()
+            );
+        }
+        Ok(())
+    }
+
+    /// Pages through the shard and writes a checkpoint after every page.
+    ///
+    /// Every page is appended to the current file as a gzip member of its own,
+    /// so the file stays readable if the harvest is interrupted.
+    fn harvest(
+        &self,
+        client: &Crossref,
+        index: usize,
+        shard: &Shard,
+        events: &mpsc::Sender<PageEvent>,
+    ) -> Result<(), failure::Error> {
+        let dir = self.out_dir.join(&shard.name);
+        fs::create_dir_all(&dir)?;
+        let url = shard.query.to_url(&client.base_url)?;
+        let checkpoint_path = dir.join(CHECKPOINT);
+        let mut checkpoint = if checkpoint_path.exists() {
+            if !self.resume {
+                failure::bail!(
+                    "{} already contains a harvest, continue it with --resume",
+                    dir.display()
+                );
+            }
+            let checkpoint = Checkpoint::read(&checkpoint_path)?;
+            if checkpoint.url != url {
+                failure::bail!(
+                    "the harvest in {} was started with the query {}",
+                    dir.display(),
+                    checkpoint.url
+                );
+            }
+            checkpoint
+        } else {
+            Checkpoint {
+                url,
+                cursor: None,
+                done: false,
+                total_results: None,
+                records: 0,
+                file: 0,
+                file_records: 0,
+                file_len: 0,
+            }
+        };
+
+        checkpoint.discard_unfinished(&dir)?;
+
+        while !checkpoint.done {
+            let mut query = shard.query.clone();
+            query.query_mut().result_control = Some(WorkResultControl::Cursor {
+                token: checkpoint.cursor.clone(),
+                rows: Some(self.rows),
+            });
+            let list = with_retries(self.retries, || client.works(query.clone())).0?;
+
+            if !list.items.is_empty() {
+                if checkpoint.file_records >= self.rotate {
+                    checkpoint.file += 1;
+                    checkpoint.file_records = 0;
+                    checkpoint.file_len = 0;
+                }
+                let file = fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(dir.join(part_name(checkpoint.file)))?;
+                let mut gz = GzEncoder::new(BufWriter::new(file), Compression::default());
+                for work in &list.items {
+                    serde_json::to_writer(&mut gz, work)?;
+                    gz.write_all(b"\n")?;
+                }
+                let file = gz.finish()?.into_inner().map_err(|err| err.into_error())?;
+                file.sync_all()?;
+                checkpoint.file_len = file.metadata()?.len();
+                checkpoint.records += list.items.len();
+                checkpoint.file_records += list.items.len();
+            }
+            if list.items.is_empty() && checkpoint.records < list.total_results {
+                // cursors expire five minutes after their last use
+                failure::bail!(
+                    "cursor expired after {} of {} works, restart the shard by removing {}",
+                    checkpoint.records,
+                    list.total_results,
+                    dir.display()
+                );
+            }
+            checkpoint.total_results = Some(list.total_results);
+            checkpoint.done = list.items.is_empty() || list.next_cursor.is_none();
+            checkpoint.cursor = list.next_cursor;
+            checkpoint.write(&checkpoint_path)?;
+
+            let event = PageEvent {
+                shard: index,
+                records: checkpoint.records,
+                total_results: checkpoint.total_results,
+                result: None,
+            };
+            if events.send(event).is_err() {
+                break;
+            }
+        }
+        if checkpoint.done {
+            // resumed shards that were already finished still report their numbers
+            let _ = events.send(PageEvent {
+                shard: index,
+                records: checkpoint.records,
+                total_results: checkpoint.total_results,
+                result: None,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// the outcome of a DOI of the `resolve` command
+struct Resolved {
+    input: String,
+    doi: String,
+    result: Result<Work, String>,
+    attempts: u32,
+}
+
+/// a line of the error report of the `resolve` command
+#[derive(Serialize)]
+struct ResolveError<'a> {
+    input: &'a str,
+    doi: Option<&'a str>,
+    error: &'a str,
+    attempts: u32,
+}
+
+impl<'a> ResolveError<'a> {
+    fn write<W: Write>(
+        writer: W,
+        input: &str,
+        doi: Option<&str>,
+        error: &str,
+        attempts: u32,
+    ) -> crossref::Result<()> {
+        let mut writer = writer;
+        let error = ResolveError {
+            input,
+            doi,
+            error,
+            attempts,
+        };
+        serde_json::to_writer(&mut writer, &error)?;
+        writer.write_all(b"\n")?;
+        Ok(())
+    }
+}
+
+/// Fetches the `(input, doi)` jobs on `concurrency` threads.
+fn resolve_all(
+    client_opts: &ClientOpts,
+    jobs: Vec<(String, String)>,
+    concurrency: usize,
+    retries: u32,
+) -> mpsc::Receiver<Resolved> {
+    spawn_workers(
+        client_opts,
+        jobs,
+        concurrency,
+        move |client, (input, doi), tx| {
+            let (result, attempts) = match client {
+                Ok(client) => resolve(client, &doi, retries),
+                Err(err) => (Err(err.to_string()), 0),
+            };
+            tx.send(Resolved {
+                input,
+                doi,
+                result,
+                attempts,
+            })
+            .is_ok()
+        },
+    )
+}
+
+/// Runs `work` for each of the `jobs` on `concurrency` threads and returns what it sends.
+///
+/// The client is not `Send`, so every thread builds its own and passes it to `work`,
+/// or the error if it could not be built. A thread stops once `work` returns `false`,
+/// e.g. because the receiver is gone.
+fn spawn_workers<J, T, F>(
+    client_opts: &ClientOpts,
+    jobs: Vec<J>,
+    concurrency: usize,
+    work: F,
+) -> mpsc::Receiver<T>
+where
+    J: Send + 'static,
+    T: Send + 'static,
+    F: Fn(Result<&Crossref, &failure::Error>, J, &mpsc::Sender<T>) -> bool + Send + Sync + 'static,
+{
+    let work = Arc::new(work);
+    let queue = Arc::new(Mutex::new(jobs.into_iter()));
+    let (tx, rx) = mpsc::channel();
+    for _ in 0..concurrency.max(1) {
+        let work = Arc::clone(&work);
+        let queue = Arc::clone(&queue);
+        let tx = tx.clone();
+        let client_opts = client_opts.clone();
+        thread::spawn(move || {
+            let client = client_opts.create_client();
+            loop {
+                let next = queue.lock().unwrap().next();
+                let job = match next {
+                    Some(job) => job,
+                    None => break,
+                };
+                if !work(client.as_ref(), job, &tx) {
+                    break;
+                }
+            }
+        });
+    }
+    rx
+}
+
+/// fetches the work, transient failures are retried
+fn resolve(client: &Crossref, doi: &str, retries: u32) -> (Result<Work, String>, u32) {
+    let (result, attempts) = with_retries(retries, || client.work(doi));
+    (result.map_err(|err| err.to_string()), attempts)
+}
+
+/// calls `f` until it succeeds, fails permanently or `retries` transient failures were retried,
+/// waits exponentially longer between the attempts. Returns the result and the number of attempts.
+fn with_retries<T, F>(retries: u32, mut f: F) -> (crossref::Result<T>, u32)
+where
+    F: FnMut() -> crossref::Result<T>,
+{
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        match f() {
+            Err(ref err) if err.is_transient() && attempts <= retries => {
+                thread::sleep(Duration::from_millis(500 << (attempts - 1).min(6)));
+            }
+            result => return (result, attempts),
+        }
+    }
+}
+
+/// a progress line on stderr
+struct Progress {
+    total: usize,
+    done: usize,
+    failed: usize,
+    silent: bool,
+}
+
+impl Progress {
+    fn new(total: usize, silent: bool) -> Self {
+        Progress {
+            total,
+            done: 0,
+            failed: 0,
+            silent,
+        }
+    }
+
+    fn inc(&mut self, failed: bool) {
+        self.done += 1;
+        if failed {
+            self.failed += 1;
+        }
+        if !self.silent {
+            eprint!(
+                "\r{}/{} resolved, {} failed",
+                self.done, self.total, self.failed
+            );
+        }
+    }
+
+    fn finish(&self) {
+        if !self.silent && self.done > 0 {
+            eprintln!();
+        }
+    }
+}
+
+/// calls `f` for every DOI and reports the failed ones on stderr, returns the number of failures
+fn each_doi<F, E>(dois: &[String], mut f: F) -> usize
+where
+    F: FnMut(&str) -> Result<(), E>,
+    E: fmt::Display,
+{
+    let mut failed = 0;
+    for doi in dois {
+        if let Err(err) = f(doi) {
+            eprintln!("{}: {}", doi, err);
+            failed += 1;
+        }
+    }
+    failed
+}
+
+/// the normalized DOIs of the inputs for a dry run, reports each invalid input like a failed DOI
+fn valid_dois(inputs: &[String]) -> Vec<String> {
+    inputs
+        .iter()
+        .filter_map(|input| {
+            let doi = doi::normalize(input);
+            if doi.is_none() {
+                eprintln!("{}: invalid DOI", input);
+            }
+            doi
+        })
+        .collect()
+}
+
+/// fails if any DOI failed so the process exits with a non-zero code
+fn failed_dois(failed: usize, total: usize) -> Result<(), failure::Error> {
+    if failed > 0 {
+        Err(failure::format_err!("{} of {} DOIs failed", failed, total))
+    } else {
+        Ok(())
+    }
+}
+
+/// the facets of the `stats` command if none are selected
+const DEFAULT_STATS: &[&str] = &[
+    "published",
+    "type-name",
+    "publisher-name:20",
+    "container-title:20",
+];
+
+/// the query of the `count` command, only the number of results is requested
+fn count_query(
+    opts: &Opts,
+    fields: &FieldQueries,
+    combined: Option<&Combined>,
+) -> crossref::Result<WorkListQuery> {
+    let mut query = works_query(opts, fields, &[], combined)?;
+    query.query_mut().result_control = Some(WorkResultControl::Standard(ResultControl::Rows(0)));
+    Ok(query)
+}
+
+/// the query of the `stats` command, only the facets are requested
+fn stats_query(
+    opts: &Opts,
+    fields: &FieldQueries,
+    facets: &[FacetCount],
+    combined: Option<&Combined>,
+) -> crossref::Result<WorkListQuery> {
+    let facets = if facets.is_empty() {
+        DEFAULT_STATS
+            .iter()
+            .map(|facet| facet.parse())
+            .collect::<crossref::Result<Vec<_>>>()?
+    } else {
+        facets.to_vec()
+    };
+    let mut query = works_query(opts, fields, &facets, combined)?;
+    query.query_mut().result_control = Some(WorkResultControl::Standard(ResultControl::Rows(0)));
+    Ok(query)
+}
+
+/// the largest sample crossref returns for a single request
+const MAX_SAMPLE: usize = 100;
+
+/// the query of the `random` command
+fn random_query(count: usize, expressions: &[String]) -> crossref::Result<WorksQuery> {
+    let mut query = WorksQuery::random(count.min(MAX_SAMPLE));
+    query.filter = filters(expressions, WorksFilter::NAMES)?;
+    Ok(query)
+}
+
+/// Renders the hierarchy of the funder indented by depth.
+///
+/// `counts` holds the work count and the work count including the descendants of the funders, by id.
+fn funder_tree(
+    funder: &Funder,
+    counts: &HashMap<String, (Option<usize>, Option<usize>)>,
+) -> String {
+    let tree = funder.tree();
+    let mut out = String::new();
+    if tree.is_empty() {
+        out.push_str(&format!("{} ({})\n", funder.name, funder.id));
+    }
+    for node in tree.iter() {
+        let name = if node.id == funder.id {
+            Some(funder.name.as_str())
+        } else {
+            node.name.as_deref()
+        };
+        out.push_str(&"  ".repeat(node.depth));
+        out.push_str(name.unwrap_or("unnamed"));
+        out.push_str(&format!(" ({})", node.id));
+        match counts.get(&node.id) {
+            Some((Some(works), Some(total))) if !node.children.is_empty() => {
+                out.push_str(&format!(": {} works, {} with descendants", works, total))
+            }
+            Some((Some(works), _)) => out.push_str(&format!(": {} works", works)),
+            _ => {}
+        }
+        if node.more {
+            out.push_str(" …");
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// builds the query of the `members` command
+fn members_query(opts: &Opts) -> crossref::Result<MembersQuery> {
+    let mut query = MembersQuery::default();
+    query!(query, opts);
+    query.filter = filters(&opts.filters, MembersFilter::NAMES)?;
+    Ok(query)
+}
+
+/// builds the query of the `funders` command
+fn funders_query(opts: &Opts) -> crossref::Result<FundersQuery> {
+    let mut query = FundersQuery::default();
+    query!(query, opts);
+    query.filter = filters(&opts.filters, FundersFilter::NAMES)?;
+    Ok(query)
+}
+
+/// a request of a command, printed by `--dry-run` and `--explain`
+struct Request {
+    url: String,
+    /// the `Accept` header of content negotiation requests
+    accept: Option<String>,
+}
+
+impl Request {
+    /// the content negotiation requests of the DOIs
+    fn negotiate(client: &Crossref, dois: &[String], format: &CnFormat) -> Vec<Request> {
+        valid_dois(dois)
+            .into_iter()
+            .map(|doi| Request {
+                url: client.resolver_link(&doi),
+                accept: Some(format.header().to_string()),
+            })
+            .collect()
+    }
+
+    /// Decomposes the url into route, query terms, field queries, filters, facets, sort and paging,
+    /// each labeled with the type it originates from.
+    fn explain(&self) -> Result<String, failure::Error> {
+        let url = Url::parse(&self.url)?;
+        let segments: Vec<_> = url
+            .path_segments()
+            .map(|segments| segments.filter(|s| !s.is_empty()).collect())
+            .unwrap_or_default();
+        let mut parts = vec![(
+            "route".to_string(),
+            url.path().to_string(),
+            route_origin(&segments),
+        )];
+        if let Some(accept) = &self.accept {
+            parts.push(("accept".to_string(), accept.clone(), "CnFormat".to_string()));
+        }
+        // the filters of combined routes like `/members/{id}/works` are works filters
+        let resource = if segments.len() == 3 {
+            segments[2]
+        } else {
+            segments.first().cloned().unwrap_or_default()
+        };
+        for (key, value) in url.query_pairs() {
+            let mut part = |name: &str, value: String, origin: String| {
+                parts.push((name.to_string(), value, origin))
+            };
+            match key.as_ref() {
+                "query" => part("query terms", value.to_string(), "query".to_string()),
+                "filter" => match resource {
+                    "members" => {
+                        for filter in parse_filters::<MembersFilter>(&value, MembersFilter::NAMES)?
+                        {
+                            part(
+                                "filter",
+                                filter.fragment().to_string(),
+                                variant("MembersFilter", &filter),
+                            );
+                        }
+                    }
+                    "funders" => {
+                        for filter in parse_filters::<FundersFilter>(&value, FundersFilter::NAMES)?
+                        {
+                            part(
+                                "filter",
+                                filter.fragment().to_string(),
+                                variant("FundersFilter", &filter),
+                            );
+                        }
+                    }
+                    _ => {
+                        for filter in parse_filters::<WorksFilter>(&value, WorksFilter::NAMES)? {
+                            part(
+                                "filter",
+                                filter.fragment().to_string(),
+                                variant("WorksFilter", &filter),
+                            );
+                        }
+                    }
+                },
+                "facet" => {
+                    for facet in value.split(',') {
+                        let origin = match facet.parse::<FacetCount>() {
+                            Ok(count) => variant("Facet", &count.facet),
+                            Err(_) => "unknown facet".to_string(),
+                        };
+                        part("facet", facet.to_string(), origin);
+                    }
+                }
+                "sort" => {
+                    let origin = value
+                        .parse::<Sort>()
+                        .map(|sort| variant("Sort", &sort))
+                        .unwrap_or_else(|_| "unknown sort".to_string());
+                    part("sort", value.to_string(), origin);
+                }
+                "order" => {
+                    let origin = value
+                        .parse::<Order>()
+                        .map(|order| variant("Order", &order))
+                        .unwrap_or_else(|_| "unknown order".to_string());
+                    part("order", value.to_string(), origin);
+                }
+                "rows" | "offset" | "sample" => part(
+                    "paging",
+                    format!("{}={}", key, value),
+                    "ResultControl".to_string(),
+                ),
+                "cursor" => part(
+                    "paging",
+                    format!("{}={}", key, value),
+                    "WorkResultControl::Cursor".to_string(),
+                ),
+                field if field.starts_with("query.") => part(
+                    "field query",
+                    format!("{}={}", &field["query.".len()..], value),
+                    format!("FieldQuery::{}", field["query.".len()..].replace('-', "_")),
+                ),
+                other => part(other, value.to_string(), "unknown parameter".to_string()),
+            }
+        }
+
+        let width = parts
+            .iter()
+            .map(|(_, value, _)| value.len())
+            .max()
+            .unwrap_or_default();
+        let mut explanation = format!("{}\n", self.url);
+        for (name, value, origin) in parts {
+            explanation.push_str(&format!(
+                "  {:<12} {:<width$}  {}\n",
+                name,
+                value,
+                origin,
+                width = width
+            ));
+        }
+        Ok(explanation)
+    }
+}
+
+/// the route type of the url path segments
+fn route_origin(segments: &[&str]) -> String {
+    let component = match segments.first().cloned().unwrap_or_default() {
+        "works" => "Works",
+        "members" => "Members",
+        "funders" => "Funders",
+        "journals" => "Journals",
+        "prefixes" => "Prefixes",
+        "types" => "Types",
+        _ => return "DOI resolver".to_string(),
+    };
+    match (component, segments.len()) {
+        ("Works", 1) => "WorkListQuery::Works".to_string(),
+        ("Works", _) if segments.len() > 2 && segments[segments.len() - 1] == "agency" => {
+            "Works::Agency".to_string()
+        }
+        ("Works", _) => "Works::Identifier".to_string(),
+        ("Types", 1) => "Types::All".to_string(),
+        (_, 1) => format!("{}::Query", component),
+        (_, 2) => format!("{}::Identifier", component),
+        _ => format!("WorkListQuery::{}", component),
+    }
+}
+
+/// the name of the enum variant of the `value` like `WorksFilter::FromPubDate`
+fn variant<T: fmt::Debug>(ty: &str, value: &T) -> String {
+    let debug = format!("{:?}", value);
+    let name = debug
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .next()
+        .unwrap_or_default();
+    format!("{}::{}", ty, name)
+}
+
+/// builds the query of the `works` command
+fn works_query(
+    opts: &Opts,
+    fields: &FieldQueries,
+    facets: &[FacetCount],
+    combined: Option<&Combined>,
+) -> crossref::Result<WorkListQuery> {
+    let mut query = WorksQuery::default();
+    query.free_form_queries = opts.query_terms.clone();
+    query.filter = filters(&opts.filters, WorksFilter::NAMES)?;
+    query.sort = opts.sort.clone();
+    query.order = opts.order.clone();
+    if let Some(offset) = opts.offset {
+        if let Some(rows) = opts.limit {
+            query.result_control = Some(WorkResultControl::Standard(ResultControl::RowsOffset {
+                rows,
+                offset,
+            }))
+        } else {
+            query.result_control = Some(WorkResultControl::Standard(ResultControl::Offset(offset)))
+        }
+    }
+    if let Some(limit) = opts.limit {
+        query.result_control = Some(WorkResultControl::Standard(ResultControl::Rows(limit)))
+    }
+    if let Some(sample) = opts.sample {
+        query.result_control = Some(WorkResultControl::Standard(ResultControl::Sample(sample)))
+    }
+
+    query.field_queries = fields.field_queries();
+    if !facets.is_empty() {
+        query.facets = facets.to_vec();
+        if query.result_control.is_none() {
+            // only the facets are printed
+            query.result_control = Some(WorkResultControl::Standard(ResultControl::Rows(0)))
+        }
+    }
+
+    Ok(match combined {
+        Some(Combined::Journal { id, .. }) => query.into_combined_query::<Journals>(id.as_str()),
+        Some(Combined::Type { id, .. }) => query.into_combined_query::<Types>(id.as_str()),
+        Some(Combined::Funder { id, .. }) => query.into_combined_query::<Funders>(id.as_str()),
+        Some(Combined::Member { id, .. }) => query.into_combined_query::<Members>(id.as_str()),
+        Some(Combined::Prefix { id, .. }) => query.into_combined_query::<Prefixes>(id.as_str()),
+        None => WorkListQuery::Works(query),
+    })
+}
+
+/// The DOIs to process, from the arguments, a file or stdin
+#[derive(Debug, StructOpt)]
+struct DoiInput {
+    #[structopt(help = "The DOIs, read from --input or stdin if none are given")]
+    dois: Vec<String>,
+    #[structopt(
+        long = "input",
+        parse(from_os_str),
+        help = "A file with one DOI per line, `-` reads stdin"
+    )]
+    input: Option<PathBuf>,
+}
 
-            App::Prefixes { client_opts, .. }
-            | App::Types { client_opts, .. }
-            | App::Journals { client_opts, .. } => client_opts,
+impl DoiInput {
+    /// the DOIs of the arguments and the input file, stdin if neither has any.
+    ///
+    /// Blank lines and lines starting with `#` are skipped.
+    fn read(&self) -> std::io::Result<Vec<String>> {
+        let mut dois: Vec<String> = self.dois.iter().map(|doi| doi.trim().to_string()).collect();
+        let text = match &self.input {
+            Some(path) if path.as_os_str() != "-" => Some(fs::read_to_string(path)?),
+            Some(_) => Some(read_stdin()?),
+            None if dois.is_empty() => Some(read_stdin()?),
+            None => None,
+        };
+        if let Some(text) = text {
+            dois.extend(
+                text.lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                    .map(str::to_string),
+            );
         }
+        Ok(dois)
     }
+}
 
-    pub fn out(&self) -> &Out {
+fn read_stdin() -> std::io::Result<String> {
+    let mut text = String::new();
+    std::io::stdin().read_to_string(&mut text)?;
+    Ok(text)
+}
+
+/// The `<doi_record>` elements of a unixref document without the `<doi_records>` root,
+/// so the records of several DOIs can be collected into one document.
+fn unixref_records(xml: &str) -> &str {
+    let start = xml
+        .match_indices("<doi_record")
+        .map(|(i, tag)| i + tag.len())
+        .find(|&i| !xml[i..].starts_with('s'))
+        .map(|i| i - "<doi_record".len());
+    let end = xml
+        .rfind("</doi_record>")
+        .map(|i| i + "</doi_record>".len());
+    match (start, end) {
+        (Some(start), Some(end)) if start < end => &xml[start..end],
+        _ => "",
+    }
+}
+
+/// the formats of the `export` command, fetched via content negotiation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CnExport {
+    BibTex,
+    Ris,
+    CslJson,
+    Turtle,
+    Unixref,
+}
+
+impl CnExport {
+    fn cn_format(self) -> CnFormat {
         match self {
-            App::Works { opts, .. } | App::Funders { opts, .. } | App::Members { opts, .. } => {
-                &opts.out
-            }
+            CnExport::BibTex => CnFormat::BibTex,
+            CnExport::Ris => CnFormat::Ris,
+            CnExport::CslJson => CnFormat::CiteProcJson,
+            CnExport::Turtle => CnFormat::Turtle,
+            CnExport::Unixref => CnFormat::CrossrefXml,
+        }
+    }
+}
 
-            App::Prefixes { out, .. } | App::Types { out, .. } | App::Journals { out, .. } => out,
+impl FromStr for CnExport {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bibtex" => Ok(CnExport::BibTex),
+            "ris" => Ok(CnExport::Ris),
+            "csl-json" => Ok(CnExport::CslJson),
+            "turtle" => Ok(CnExport::Turtle),
+            "unixref" => Ok(CnExport::Unixref),
+            other => Err(format!(
+                "unknown format `{}`, valid formats are: bibtex, ris, csl-json, turtle, unixref",
+                other
+            )),
+        }
+    }
+}
+
+/// the formats works can be written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Json,
+    Jsonl,
+    Csv,
+    Bibtex,
+    Ris,
+    CslJson,
+}
+
+impl Format {
+    const NAMES: &'static [&'static str] = &["json", "jsonl", "csv", "bibtex", "ris", "csl-json"];
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(Format::Json),
+            "jsonl" => Ok(Format::Jsonl),
+            "csv" => Ok(Format::Csv),
+            "bibtex" => Ok(Format::Bibtex),
+            "ris" => Ok(Format::Ris),
+            "csl-json" => Ok(Format::CslJson),
+            other => Err(format!(
+                "unknown format `{}`, valid formats are: {}",
+                other,
+                Format::NAMES.join(", ")
+            )),
         }
     }
+}
+
+#[derive(Debug, StructOpt)]
+struct FormatOpts {
+    #[structopt(
+        long = "format",
+        parse(try_from_str),
+        help = "The output format of the works: json, jsonl, csv, bibtex, ris or csl-json"
+    )]
+    format: Option<Format>,
+    #[structopt(
+        long = "columns",
+        raw(use_delimiter = "true"),
+        help = "The work fields written as csv columns, e.g. DOI,title,author,issued"
+    )]
+    columns: Vec<String>,
+}
+
+/// the csv columns if none are selected
+const DEFAULT_COLUMNS: &[&str] = &[
+    "DOI",
+    "type",
+    "title",
+    "author",
+    "container-title",
+    "issued",
+    "publisher",
+    "URL",
+];
+
+/// writes works one by one in a `Format`
+enum RecordWriter<W: Write> {
+    Export(ExportWriter<W>),
+    Json {
+        writer: W,
+        records: usize,
+        csl: bool,
+    },
+    Jsonl(W),
+    Csv {
+        writer: W,
+        columns: Vec<String>,
+    },
+}
+
+impl<W: Write> RecordWriter<W> {
+    /// writes all works and finishes the document
+    fn write_all<I>(
+        writer: W,
+        format: Format,
+        columns: &[String],
+        works: I,
+    ) -> Result<(), failure::Error>
+    where
+        I: IntoIterator<Item = Work>,
+    {
+        RecordWriter::write_results(writer, format, columns, works.into_iter().map(Ok))
+    }
 
-    pub fn get_value<W>(&self, writer: W, client: &Crossref) -> crossref::Result<()>
+    /// writes the works until the first error, which is returned
+    fn write_results<I>(
+        writer: W,
+        format: Format,
+        columns: &[String],
+        works: I,
+    ) -> Result<(), failure::Error>
     where
-        W: std::io::Write,
+        I: IntoIterator<Item = crossref::Result<Work>>,
     {
-        match self {
-            App::Types { id, .. } => {
-                if let Some(id) = id {
-                    Ok(serde_json::to_writer_pretty(writer, &client.type_(id)?)?)
+        let mut writer = RecordWriter::new(writer, format, columns)?;
+        for work in works {
+            writer.write(&work?)?;
+        }
+        Ok(writer.finish()?)
+    }
+
+    /// fails if a csv column is not a field of `Work`
+    fn new(mut writer: W, format: Format, columns: &[String]) -> Result<Self, failure::Error> {
+        Ok(match format {
+            Format::Json | Format::CslJson => RecordWriter::Json {
+                writer,
+                records: 0,
+                csl: format == Format::CslJson,
+            },
+            Format::Jsonl => RecordWriter::Jsonl(writer),
+            Format::Csv => {
+                let columns: Vec<String> = if columns.is_empty() {
+                    DEFAULT_COLUMNS.iter().map(|c| c.to_string()).collect()
                 } else {
-                    Ok(serde_json::to_writer_pretty(writer, &client.types()?)?)
+                    columns.iter().map(|c| c.trim().to_string()).collect()
+                };
+                let keys = work_keys();
+                if let Some(column) = columns
+                    .iter()
+                    .find(|c| !keys.iter().any(|key| key.eq_ignore_ascii_case(c)))
+                {
+                    return Err(failure::format_err!(
+                        "unknown column `{}`, expected one of: {}",
+                        column,
+                        keys.join(", ")
+                    ));
                 }
+                csv_row(&mut writer, &columns)?;
+                RecordWriter::Csv { writer, columns }
             }
-            App::Prefixes { id, .. } => Ok(serde_json::to_writer_pretty(
-                writer,
-                &client.prefix(id.as_str())?,
-            )?),
-            App::Journals { id, .. } => Ok(serde_json::to_writer_pretty(
+            Format::Bibtex => RecordWriter::Export(ExportWriter::new(writer, ExportFormat::BibTex)),
+            Format::Ris => RecordWriter::Export(ExportWriter::new(writer, ExportFormat::Ris)),
+        })
+    }
+
+    fn write(&mut self, work: &Work) -> crossref::Result<()> {
+        match self {
+            RecordWriter::Export(writer) => writer.write(work)?,
+            RecordWriter::Json {
                 writer,
-                &client.journal(id.as_str())?,
-            )?),
-            App::Members { opts, .. } => {
-                if let Some(id) = &opts.id {
-                    Ok(serde_json::to_writer_pretty(
-                        writer,
-                        &client.member(id.as_str())?,
-                    )?)
+                records,
+                csl,
+            } => {
+                writer.write_all(if *records == 0 { b"[\n" } else { b",\n" })?;
+                if *csl {
+                    serde_json::to_writer_pretty(&mut *writer, &CslItem::from(work))?;
                 } else {
-                    let mut query = MembersQuery::default();
-                    query!(query, opts);
-                    Ok(serde_json::to_writer_pretty(
-                        writer,
-                        &client.members(query)?,
-                    )?)
+                    serde_json::to_writer_pretty(&mut *writer, work)?;
                 }
+                *records += 1;
             }
-            App::Funders { opts, .. } => {
-                if let Some(id) = &opts.id {
-                    Ok(serde_json::to_writer_pretty(
-                        writer,
-                        &client.funder(id.as_str())?,
-                    )?)
-                } else {
-                    let mut query = FundersQuery::default();
-                    query!(query, opts);
-                    Ok(serde_json::to_writer_pretty(
-                        writer,
-                        &client.funders(query)?,
-                    )?)
-                }
+            RecordWriter::Jsonl(writer) => {
+                serde_json::to_writer(&mut *writer, work)?;
+                writer.write_all(b"\n")?;
             }
-            App::Works {
-                opts,
-                combined,
-                deep_page,
+            RecordWriter::Csv { writer, columns } => {
+                let value = serde_json::to_value(work)?;
+                let row: Vec<_> = columns.iter().map(|c| csv_value(&value, c)).collect();
+                csv_row(writer, &row)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> crossref::Result<()> {
+        let mut writer = match self {
+            RecordWriter::Export(writer) => writer.finish()?,
+            RecordWriter::Json {
+                mut writer,
+                records,
+                ..
             } => {
-                if let Some(id) = &opts.id {
-                    Ok(serde_json::to_writer_pretty(
-                        writer,
-                        &client.work(id.as_str())?,
-                    )?)
-                } else {
-                    let mut query = WorksQuery::default();
-                    query.free_form_queries = opts.query_terms.clone();
-                    query.sort = opts.sort.clone();
-                    query.order = opts.order.clone();
-                    if let Some(offset) = opts.offset {
-                        if let Some(rows) = opts.limit {
-                            query.result_control =
-                                Some(WorkResultControl::Standard(ResultControl::RowsOffset {
-                                    rows,
-                                    offset,
-                                }))
-                        } else {
-                            query.result_control =
-                                Some(WorkResultControl::Standard(ResultControl::Offset(offset)))
-                        }
-                    }
-                    if let Some(limit) = opts.limit {
-                        query.result_control =
-                            Some(WorkResultControl::Standard(ResultControl::Rows(limit)))
-                    }
-                    if let Some(sample) = opts.sample {
-                        query.result_control =
-                            Some(WorkResultControl::Standard(ResultControl::Sample(sample)))
-                    }
+                writer.write_all(if records == 0 { b"[]\n" } else { b"\n]\n" })?;
+                writer
+            }
+            RecordWriter::Jsonl(writer) | RecordWriter::Csv { writer, .. } => writer,
+        };
+        Ok(writer.flush()?)
+    }
+}
 
-                    if let Some(combined) = combined {
-                        let query = match combined {
-                            Combined::Journal { id, .. } => {
-                                query.into_combined_query::<Journals>(id.as_str())
-                            }
-                            Combined::Type { id, .. } => {
-                                query.into_combined_query::<Types>(id.as_str())
-                            }
-                            Combined::Funder { id, .. } => {
-                                query.into_combined_query::<Funders>(id.as_str())
-                            }
-                            Combined::Member { id, .. } => {
-                                query.into_combined_query::<Members>(id.as_str())
-                            }
-                            Combined::Prefix { id, .. } => {
-                                query.into_combined_query::<Prefixes>(id.as_str())
-                            }
-                        };
+/// writes a csv line, fields with separators, quotes or line breaks are quoted
+fn csv_row<W: Write, T: AsRef<str>>(writer: &mut W, fields: &[T]) -> std::io::Result<()> {
+    let fields: Vec<_> = fields
+        .iter()
+        .map(|field| {
+            let field = field.as_ref();
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.to_string()
+            }
+        })
+        .collect();
+    writeln!(writer, "{}", fields.join(","))
+}
 
-                        if *deep_page {
-                            Ok(serde_json::to_writer_pretty(
-                                writer,
-                                &client.deep_page(query).into_work_iter().collect::<Vec<_>>(),
-                            )?)
-                        } else {
-                            Ok(serde_json::to_writer_pretty(writer, &client.works(query)?)?)
-                        }
-                    } else {
-                        Ok(serde_json::to_writer_pretty(writer, &client.works(query)?)?)
-                    }
-                }
+/// the keys of the modeled fields of a serialized `Work`
+fn work_keys() -> &'static [&'static str] {
+    /// records the fields the derived `Deserialize` of a struct asks for and stops
+    struct FieldNames<'a>(&'a mut &'static [&'static str]);
+
+    impl<'de, 'a> serde::Deserializer<'de> for FieldNames<'a> {
+        type Error = serde::de::value::Error;
+
+        fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: serde::de::Visitor<'de>,
+        {
+            Err(serde::de::Error::custom("not a struct"))
+        }
+
+        fn deserialize_struct<V>(
+            self,
+            _name: &'static str,
+            fields: &'static [&'static str],
+            _visitor: V,
+        ) -> Result<V::Value, Self::Error>
+        where
+            V: serde::de::Visitor<'de>,
+        {
+            *self.0 = fields;
+            Err(serde::de::Error::custom("fields recorded"))
+        }
+
+        serde::forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+            bytes byte_buf option unit unit_struct newtype_struct seq tuple
+            tuple_struct map enum identifier ignored_any
+        }
+    }
+
+    let mut fields: &'static [&'static str] = &[];
+    let _ = Work::deserialize(FieldNames(&mut fields));
+    fields
+}
+
+/// the text of the field `column` of the serialized work, the case of the column is ignored
+fn csv_value(work: &Value, column: &str) -> String {
+    let value = work.as_object().and_then(|work| {
+        work.get(column).or_else(|| {
+            work.iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(column))
+                .map(|(_, value)| value)
+        })
+    });
+    value.map(csv_text).unwrap_or_default()
+}
+
+/// renders strings, lists, dates and names as text, anything else as json
+fn csv_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        Value::Array(values) => values
+            .iter()
+            .map(csv_text)
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+            .join("; "),
+        Value::Object(object) => {
+            if let Some(parts) = object
+                .get("date-parts")
+                .and_then(|parts| parts.get(0))
+                .and_then(Value::as_array)
+            {
+                let parts: Vec<_> = parts.iter().filter_map(Value::as_u64).collect();
+                return match parts.as_slice() {
+                    [year] => format!("{}", year),
+                    [year, month] => format!("{}-{:02}", year, month),
+                    [year, month, day, ..] => format!("{}-{:02}-{:02}", year, month, day),
+                    _ => String::new(),
+                };
+            }
+            if let Some(family) = object.get("family").and_then(Value::as_str) {
+                return match object.get("given").and_then(Value::as_str) {
+                    Some(given) => format!("{}, {}", family, given),
+                    None => family.to_string(),
+                };
             }
+            if let Some(name) = object.get("name").and_then(Value::as_str) {
+                return name.to_string();
+            }
+            value.to_string()
+        }
+        other => other.to_string(),
+    }
+}
+
+/// renders the facets as a table, one section per facet with the most frequent values first
+fn facet_table(facets: &FacetMap) -> String {
+    let mut names: Vec<_> = facets.keys().collect();
+    names.sort();
+    let mut table = String::new();
+    for name in names {
+        let facet = &facets[name];
+        let mut values = facet.sorted_values();
+        if name == "published" {
+            // years read best in chronological order
+            values.sort_by(|a, b| a.0.cmp(b.0));
+        }
+        let width = values
+            .iter()
+            .map(|(value, _)| value.chars().count())
+            .max()
+            .unwrap_or_default();
+        let count_width = values
+            .iter()
+            .map(|(_, count)| count.to_string().len())
+            .max()
+            .unwrap_or_default();
+        table.push_str(&format!("{} ({} values)\n", name, facet.value_count));
+        for (value, count) in values {
+            let padding = width - value.chars().count();
+            table.push_str(&format!(
+                "  {}{}  {:>count_width$}\n",
+                value,
+                " ".repeat(padding),
+                count,
+                count_width = count_width
+            ));
         }
+        table.push('\n');
+    }
+    table
+}
+
+/// field queries that match only particular fields of the metadata of works
+#[derive(Debug, StructOpt)]
+struct FieldQueries {
+    #[structopt(long = "author", help = "Query author given and family names")]
+    author: Option<String>,
+    #[structopt(long = "editor", help = "Query editor given and family names")]
+    editor: Option<String>,
+    #[structopt(long = "chair", help = "Query chair given and family names")]
+    chair: Option<String>,
+    #[structopt(long = "translator", help = "Query translator given and family names")]
+    translator: Option<String>,
+    #[structopt(
+        long = "contributor",
+        help = "Query author, editor, chair and translator given and family names"
+    )]
+    contributor: Option<String>,
+    #[structopt(long = "title", help = "Query title and subtitle")]
+    title: Option<String>,
+    #[structopt(
+        long = "container-title",
+        help = "Query the container title, such as the journal"
+    )]
+    container_title: Option<String>,
+    #[structopt(
+        long = "bibliographic",
+        help = "Query titles, authors, ISSNs and publication years, e.g. for citation look up"
+    )]
+    bibliographic: Option<String>,
+    #[structopt(long = "affiliation", help = "Query contributor affiliations")]
+    affiliation: Option<String>,
+}
+
+impl FieldQueries {
+    /// the `FieldQuery` for every set flag
+    fn field_queries(&self) -> Vec<FieldQuery> {
+        // the value of a flag and the field query it sets
+        type Flag<'a> = (&'a Option<String>, fn(&str) -> FieldQuery);
+        let fields: [Flag<'_>; 9] = [
+            (&self.author, FieldQuery::author),
+            (&self.editor, FieldQuery::editor),
+            (&self.chair, FieldQuery::chair),
+            (&self.translator, FieldQuery::translator),
+            (&self.contributor, FieldQuery::contributor),
+            (&self.title, FieldQuery::title),
+            (&self.container_title, FieldQuery::container_title),
+            (&self.bibliographic, FieldQuery::bibliographic),
+            (&self.affiliation, FieldQuery::affiliation),
+        ];
+        fields
+            .iter()
+            .filter_map(|(value, field)| value.as_ref().map(|value| field(value)))
+            .collect()
     }
 }
 
@@ -259,36 +2356,196 @@ struct Out {
     silent: bool,
 }
 
-#[derive(Debug, StructOpt)]
+#[derive(Debug, Clone, StructOpt)]
 struct ClientOpts {
     #[structopt(
         long = "user-agent",
         help = "The user agent to use for the crossref client"
     )]
     user_agent: Option<String>,
-    #[structopt(long = "token", help = "The token to use for the crossref client")]
+    #[structopt(
+        long = "token",
+        help = "The Plus token to use for the crossref client, prefer CROSSREF_PLUS_TOKEN or the config file"
+    )]
     token: Option<String>,
     #[structopt(
         long = "polite",
         help = "The email to use to get into crossref's polite pool"
     )]
     polite: Option<String>,
+    #[structopt(
+        long = "base-url",
+        help = "Use another api than https://api.crossref.org"
+    )]
+    base_url: Option<String>,
+    #[structopt(
+        long = "dry-run",
+        help = "Print the request urls instead of sending them"
+    )]
+    dry_run: bool,
+    #[structopt(
+        long = "explain",
+        help = "Print the request urls decomposed into route, queries, filters, facets, sort and paging"
+    )]
+    explain: bool,
 }
 
 impl ClientOpts {
-    pub fn create_client(&self) -> Result<Crossref, crossref::Error> {
+    pub fn create_client(&self) -> Result<Crossref, failure::Error> {
+        let settings = self.settings()?;
         let mut builder = Crossref::builder();
 
-        if let Some(agent) = &self.user_agent {
-            builder = builder.user_agent(agent.as_str());
+        match (&settings.user_agent, &settings.mailto) {
+            (Some(agent), Some(mailto)) => {
+                builder = builder.user_agent(&format!("{} (mailto:{})", agent.value, mailto.value));
+            }
+            (Some(agent), None) => builder = builder.user_agent(&agent.value),
+            (None, Some(mailto)) => builder = builder.polite(&mailto.value),
+            (None, None) => {}
+        }
+        if let Some(token) = &settings.plus_token {
+            builder = builder.token(&token.value);
+        }
+        if let Some(base_url) = &settings.base_url {
+            builder = builder.base_url(&base_url.value);
+        }
+        Ok(builder.build()?)
+    }
+
+    /// Merges the flags with the environment and the config file.
+    ///
+    /// Flags take precedence over environment variables, which take precedence over the config file.
+    fn settings(&self) -> Result<Settings, failure::Error> {
+        let file = match config_path() {
+            Some(path) if path.exists() => ConfigFile::read(&path)?,
+            _ => ConfigFile::default(),
+        };
+        Ok(Settings {
+            mailto: Setting::pick(&self.polite, Some("CROSSREF_MAILTO"), &file.mailto),
+            plus_token: Setting::pick(&self.token, Some("CROSSREF_PLUS_TOKEN"), &file.plus_token),
+            user_agent: Setting::pick(&self.user_agent, None, &file.user_agent),
+            base_url: Setting::pick(&self.base_url, Some("CROSSREF_BASE_URL"), &file.base_url),
+        })
+    }
+}
+
+/// `$XDG_CONFIG_HOME/crossref/config.toml`, `~/.config/crossref/config.toml` by default
+fn config_path() -> Option<PathBuf> {
+    let dir = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(dir.join("crossref").join("config.toml"))
+}
+
+/// the defaults of the client options in the config file
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct ConfigFile {
+    mailto: Option<String>,
+    plus_token: Option<String>,
+    user_agent: Option<String>,
+    base_url: Option<String>,
+}
+
+impl ConfigFile {
+    fn read(path: &Path) -> Result<Self, failure::Error> {
+        toml::from_str(&fs::read_to_string(path)?)
+            .map_err(|err| failure::format_err!("invalid config file {}: {}", path.display(), err))
+    }
+}
+
+/// where the value of a setting comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Source {
+    Flag,
+    Env(&'static str),
+    File,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Source::Flag => write!(f, "command line"),
+            Source::Env(var) => write!(f, "environment {}", var),
+            Source::File => write!(f, "config file"),
         }
-        if let Some(token) = &self.token {
-            builder = builder.token(token.as_str());
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Setting {
+    value: String,
+    source: Source,
+}
+
+impl Setting {
+    /// the flag, else the non empty environment variable, else the value of the config file
+    fn pick(
+        flag: &Option<String>,
+        env: Option<&'static str>,
+        file: &Option<String>,
+    ) -> Option<Self> {
+        let setting = |value: &String, source| Setting {
+            value: value.clone(),
+            source,
+        };
+        flag.as_ref()
+            .map(|value| setting(value, Source::Flag))
+            .or_else(|| {
+                let var = env?;
+                std::env::var(var)
+                    .ok()
+                    .filter(|value| !value.is_empty())
+                    .map(|value| setting(&value, Source::Env(var)))
+            })
+            .or_else(|| file.as_ref().map(|value| setting(value, Source::File)))
+    }
+}
+
+/// the effective client options
+#[derive(Debug)]
+struct Settings {
+    mailto: Option<Setting>,
+    plus_token: Option<Setting>,
+    user_agent: Option<Setting>,
+    base_url: Option<Setting>,
+}
+
+impl Settings {
+    /// prints every setting and its source, the Plus token is redacted
+    fn show<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+        match config_path() {
+            Some(path) if path.exists() => writeln!(writer, "{:<12}{}", "config", path.display())?,
+            Some(path) => writeln!(writer, "{:<12}{} (not found)", "config", path.display())?,
+            None => writeln!(writer, "{:<12}- (no home directory)", "config")?,
         }
-        if let Some(polite) = &self.polite {
-            builder = builder.polite(polite.as_str());
+        let settings = [
+            ("mailto", &self.mailto, false, None),
+            ("plus-token", &self.plus_token, true, None),
+            ("user-agent", &self.user_agent, false, None),
+            (
+                "base-url",
+                &self.base_url,
+                false,
+                Some("https://api.crossref.org"),
+            ),
+        ];
+        for (name, setting, secret, default) in settings.iter() {
+            match (setting, default) {
+                (Some(setting), _) => {
+                    let value = if *secret {
+                        "<redacted>"
+                    } else {
+                        setting.value.as_str()
+                    };
+                    writeln!(writer, "{:<12}{} ({})", name, value, setting.source)?
+                }
+                (None, Some(default)) => writeln!(writer, "{:<12}{} (default)", name, default)?,
+                (None, None) => writeln!(writer, "{:<12}-", name)?,
+            }
         }
-        builder.build()
+        Ok(())
     }
 }
 
@@ -309,6 +2566,13 @@ struct Opts {
     )]
     query_terms: Vec<String>,
 
+    #[structopt(
+        short = "f",
+        long = "filter",
+        help = "Filter the results with `name:value`, repeatable or comma separated, e.g. type:journal-article,from-pub-date:2020"
+    )]
+    filters: Vec<String>,
+
     #[structopt(
         long = "sort",
         help = "How to sort the results, such as updated, indexed, published, issued"
@@ -332,12 +2596,43 @@ struct Opts {
     client_opts: ClientOpts,
 }
 
-fn main() -> Result<(), failure::Error> {
+/// parses the `--filter` expressions into the filters of the route
+fn filters<F>(expressions: &[String], names: &[&str]) -> crossref::Result<Vec<F>>
+where
+    F: FromStr<Err = crossref::Error>,
+{
+    let mut filters = Vec::new();
+    for expression in expressions {
+        filters.extend(parse_filters(expression, names)?);
+    }
+    Ok(filters)
+}
+
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<(), failure::Error> {
     pretty_env_logger::try_init()?;
     let app = App::from_args();
 
     let client = app.client_opts().create_client()?;
 
+    let client_opts = app.client_opts();
+    if client_opts.dry_run || client_opts.explain {
+        for request in app.requests(&client)? {
+            if client_opts.explain {
+                print!("{}", request.explain()?);
+            } else {
+                println!("{}", request.url);
+            }
+        }
+        return Ok(());
+    }
+
     let out = app.out();
     if let Some(path) = &out.output {
         let file = if out.append && path.exists() {
@@ -351,3 +2646,248 @@ fn main() -> Result<(), failure::Error> {
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collect_unixref_records() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<doi_records>
+  <doi_record owner="10.1037" timestamp="2018-06-05 10:38:58">
+    <crossref><journal/></crossref>
+  </doi_record>
+</doi_records>"#;
+        assert_eq!(
+            "<doi_record owner=\"10.1037\" timestamp=\"2018-06-05 10:38:58\">\n    <crossref><journal/></crossref>\n  </doi_record>",
+            unixref_records(xml)
+        );
+        assert_eq!("", unixref_records("<doi_records/>"));
+    }
+
+    #[test]
+    fn pick_setting() {
+        let flag = Some("flag@example.org".to_string());
+        let file = Some("file@example.org".to_string());
+        let env = "CROSSREF_TEST_PICK_MAILTO";
+
+        std::env::set_var(env, "env@example.org");
+        let setting = Setting::pick(&flag, Some(env), &file).unwrap();
+        assert_eq!(
+            ("flag@example.org", Source::Flag),
+            (setting.value.as_str(), setting.source)
+        );
+        let setting = Setting::pick(&None, Some(env), &file).unwrap();
+        assert_eq!(
+            ("env@example.org", Source::Env(env)),
+            (setting.value.as_str(), setting.source)
+        );
+
+        std::env::set_var(env, "");
+        let setting = Setting::pick(&None, Some(env), &file).unwrap();
+        assert_eq!(
+            ("file@example.org", Source::File),
+            (setting.value.as_str(), setting.source)
+        );
+        std::env::remove_var(env);
+        assert!(Setting::pick(&None, Some(env), &None).is_none());
+        assert_eq!(
+            Source::File,
+            Setting::pick(&None, None, &file).unwrap().source
+        );
+    }
+
+    #[test]
+    fn show_settings() {
+        let setting = |value: &str, source| {
+            Some(Setting {
+                value: value.to_string(),
+                source,
+            })
+        };
+        let settings = Settings {
+            mailto: setting("me@example.org", Source::File),
+            plus_token: setting("Bearer secret", Source::Env("CROSSREF_PLUS_TOKEN")),
+            user_agent: None,
+            base_url: None,
+        };
+        let mut shown = Vec::new();
+        settings.show(&mut shown).unwrap();
+        let shown = String::from_utf8(shown).unwrap();
+
+        assert!(!shown.contains("secret"));
+        let lines: Vec<_> = shown.lines().skip(1).collect();
+        assert_eq!(
+            vec![
+                "mailto      me@example.org (config file)",
+                "plus-token  <redacted> (environment CROSSREF_PLUS_TOKEN)",
+                "user-agent  -",
+                "base-url    https://api.crossref.org (default)",
+            ],
+            lines
+        );
+    }
+
+    #[test]
+    fn explain_request() {
+        let request = Request {
+            url: "https://api.crossref.org/members/98/works?query=Machine+Learning&query.author=Feynman&filter=type:journal-article,from-pub-date:2020-01-01&facet=type-name:*&sort=published&order=desc&rows=20".to_string(),
+            accept: None,
+        };
+        let explanation = request.explain().unwrap();
+        let parts: Vec<Vec<_>> = explanation
+            .lines()
+            .skip(1)
+            .map(|line| line.split_whitespace().collect())
+            .collect();
+        assert_eq!(
+            vec![
+                vec!["route", "/members/98/works", "WorkListQuery::Members"],
+                vec!["query", "terms", "Machine", "Learning", "query"],
+                vec!["field", "query", "author=Feynman", "FieldQuery::author"],
+                vec!["filter", "type:journal-article", "WorksFilter::Type"],
+                vec![
+                    "filter",
+                    "from-pub-date:2020-01-01",
+                    "WorksFilter::FromPubDate"
+                ],
+                vec!["facet", "type-name:*", "Facet::TypeName"],
+                vec!["sort", "published", "Sort::Published"],
+                vec!["order", "desc", "Order::Desc"],
+                vec!["paging", "rows=20", "ResultControl"],
+            ],
+            parts
+        );
+
+        let request = Request {
+            url: "https://doi.org/10.1037/0003-066x.59.1.29".to_string(),
+            accept: Some("application/x-bibtex".to_string()),
+        };
+        let explanation = request.explain().unwrap();
+        let parts: Vec<Vec<_>> = explanation
+            .lines()
+            .skip(1)
+            .map(|line| line.split_whitespace().collect())
+            .collect();
+        assert_eq!(
+            vec![
+                vec!["route", "/10.1037/0003-066x.59.1.29", "DOI", "resolver"],
+                vec!["accept", "application/x-bibtex", "CnFormat"],
+            ],
+            parts
+        );
+    }
+
+    #[test]
+    fn csv_quoting() {
+        let mut row = Vec::new();
+        csv_row(
+            &mut row,
+            &[
+                "10.5555/1",
+                "Title, with comma",
+                "say \"hi\"",
+                "two\nlines",
+                "",
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            "10.5555/1,\"Title, with comma\",\"say \"\"hi\"\"\",\"two\nlines\",\n",
+            String::from_utf8(row).unwrap()
+        );
+
+        let work = serde_json::json!({
+            "DOI": "10.5555/1",
+            "title": ["A title", "", "A subtitle"],
+            "author": [{"given": "Richard", "family": "Feynman"}, {"family": "Dirac"}, {"name": "CERN"}],
+            "issued": {"date-parts": [[2019, 6, 3]]},
+            "published-print": {"date-parts": [[2019]]},
+            "is-referenced-by-count": 7,
+            "abstract": null
+        });
+        assert_eq!("A title; A subtitle", csv_value(&work, "title"));
+        assert_eq!("Feynman, Richard; Dirac; CERN", csv_value(&work, "author"));
+        assert_eq!("2019-06-03", csv_value(&work, "issued"));
+        assert_eq!("2019", csv_value(&work, "published-print"));
+        assert_eq!("10.5555/1", csv_value(&work, "doi"));
+        assert_eq!("7", csv_value(&work, "is-referenced-by-count"));
+        assert_eq!("", csv_value(&work, "abstract"));
+        assert_eq!("", csv_value(&work, "missing"));
+    }
+
+    #[test]
+    fn csv_columns() {
+        let columns =
+            |columns: &[&str]| -> Vec<String> { columns.iter().map(|c| c.to_string()).collect() };
+        assert!(RecordWriter::new(Vec::new(), Format::Csv, &columns(&["doi", "URL"])).is_ok());
+        assert!(RecordWriter::new(Vec::new(), Format::Csv, &[]).is_ok());
+
+        let err = match RecordWriter::new(Vec::new(), Format::Csv, &columns(&["DOI", "titel"])) {
+            Err(err) => err.to_string(),
+            Ok(_) => panic!("unknown column accepted"),
+        };
+        assert!(err.starts_with("unknown column `titel`, expected one of: "));
+        assert!(err.contains(", is-referenced-by-count, "));
+        assert!(!err.contains("extra"));
+    }
+
+    #[test]
+    fn shard_periods() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        assert_eq!(
+            ("2020-02".to_string(), date(2020, 2, 1), date(2020, 2, 29)),
+            ShardBy::period("2020-02").unwrap()
+        );
+        assert_eq!(
+            ("2019".to_string(), date(2019, 1, 1), date(2019, 12, 31)),
+            ShardBy::period("2019").unwrap()
+        );
+        assert!(ShardBy::period("2019-13").is_err());
+
+        let ranges = ShardBy::Month.ranges("2021-01", Some("2021-03")).unwrap();
+        assert_eq!(
+            vec![
+                ("2021-01".to_string(), date(2021, 1, 1), date(2021, 1, 31)),
+                ("2021-02".to_string(), date(2021, 2, 1), date(2021, 2, 28)),
+                ("2021-03".to_string(), date(2021, 3, 1), date(2021, 3, 31)),
+            ],
+            ranges
+        );
+        let ranges = ShardBy::Year.ranges("2019-06", Some("2020")).unwrap();
+        assert_eq!(
+            vec![
+                ("2019".to_string(), date(2019, 6, 1), date(2019, 12, 31)),
+                ("2020".to_string(), date(2020, 1, 1), date(2020, 12, 31)),
+            ],
+            ranges
+        );
+        let err = ShardBy::Month.ranges("2021", Some("2020")).unwrap_err();
+        assert_eq!("--from is after --until", err.to_string());
+    }
+
+    #[test]
+    fn resume_checkpoint() {
+        let dir = std::env::temp_dir().join(format!("crossref-harvest-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let checkpoint = Checkpoint {
+            url: "https://api.crossref.org/works?filter=from-pub-date:2020-01-01".to_string(),
+            cursor: Some("AoJ/x+y=".to_string()),
+            done: false,
+            total_results: Some(120),
+            records: 100,
+            file: 1,
+            file_records: 40,
+            file_len: 4,
+        };
+        checkpoint.write(&dir.join(CHECKPOINT)).unwrap();
+        assert_eq!(checkpoint, Checkpoint::read(&dir.join(CHECKPOINT)).unwrap());
+        assert!(!dir.join("checkpoint.json.tmp").exists());
+
+        fs::write(dir.join(part_name(1)), b"pagehalf a page").unwrap();
+        checkpoint.discard_unfinished(&dir).unwrap();
+        assert_eq!(b"page".to_vec(), fs::read(dir.join(part_name(1))).unwrap());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,721 @@
+// see https://github.com/citation-style-language/schema/blob/master/schemas/input/csl-data.json
+
+use crate::error::{Error, ErrorKind};
+use crate::response::work::{Contributor, DateParts, PartialDate, Work};
+use serde::de::{DeserializeOwned, Deserializer, Error as DeError};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::convert::TryFrom;
+
+/// A single item in the [CSL-JSON](https://citeproc-js.readthedocs.io/en/latest/csl-json/markup.html) format,
+/// as returned by content negotiation with `CnFormat::CiteProcJson`.
+///
+/// A `CslItem` can be created from any `Work` and converted back into a `Work` if it has a DOI.
+///
+/// # Example
+///
+/// ```edition2018
+/// use crossref::{Crossref, CslItem, Work};
+/// use std::convert::TryFrom;
+/// # fn run() -> Result<(), crossref::Error> {
+/// let client = Crossref::builder().build()?;
+///
+/// let work = client.work("10.1037/0003-066x.59.1.29")?;
+/// let item = CslItem::from(&work);
+/// println!("{}", serde_json::to_string(&item)?);
+///
+/// let work = Work::try_from(item)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct CslItem {
+    /// identifier of the item
+    #[serde(deserialize_with = "string", skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// the CSL type of the item, like `article-journal` or `chapter`
+    #[serde(rename = "type")]
+    pub type_: String,
+    /// the primary title of the item
+    #[serde(deserialize_with = "string", skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// title of the container holding the item, like the journal or book title
+    #[serde(deserialize_with = "string", skip_serializing_if = "Option::is_none")]
+    pub container_title: Option<String>,
+    /// the authors of the item
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub author: Vec<CslName>,
+    /// the editors of the item
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub editor: Vec<CslName>,
+    /// the date the item was issued
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issued: Option<CslDate>,
+    /// range of pages the item occupies in the container
+    #[serde(deserialize_with = "string", skip_serializing_if = "Option::is_none")]
+    pub page: Option<String>,
+    /// volume holding the item
+    #[serde(deserialize_with = "string", skip_serializing_if = "Option::is_none")]
+    pub volume: Option<String>,
+    /// issue holding the item
+    #[serde(deserialize_with = "string", skip_serializing_if = "Option::is_none")]
+    pub issue: Option<String>,
+    /// the publisher of the item
+    #[serde(deserialize_with = "string", skip_serializing_if = "Option::is_none")]
+    pub publisher: Option<String>,
+    /// DOI of the item
+    #[serde(
+        rename = "DOI",
+        deserialize_with = "string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub doi: Option<String>,
+    /// URL of the item
+    #[serde(
+        rename = "URL",
+        deserialize_with = "string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub url: Option<String>,
+    /// ISSNs of the container
+    #[serde(rename = "ISSN", deserialize_with = "strings")]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub issn: Vec<String>,
+    /// ISBNs of the item
+    #[serde(rename = "ISBN", deserialize_with = "strings")]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub isbn: Vec<String>,
+    /// abstract of the item
+    #[serde(
+        rename = "abstract",
+        deserialize_with = "string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub abstract_: Option<String>,
+    /// all fields of the item that are not explicitly modeled
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// A person or institution in CSL-JSON
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct CslName {
+    /// the family name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub family: Option<String>,
+    /// the given name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub given: Option<String>,
+    /// a name that should not be split, like an institution
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub literal: Option<String>,
+    /// suffix like `Jr.`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suffix: Option<String>,
+    /// particle that is kept when only the family name is displayed, like `van` in `van Gogh`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub non_dropping_particle: Option<String>,
+    /// particle that is dropped when only the family name is displayed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dropping_particle: Option<String>,
+    /// all fields of the name that are not explicitly modeled
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// A date in CSL-JSON
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct CslDate {
+    /// year, month and day of the date, two entries represent a range
+    #[serde(deserialize_with = "date_parts")]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub date_parts: Vec<Vec<i32>>,
+    /// the date as literal string
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub literal: Option<String>,
+    /// the date in a format that still has to be parsed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw: Option<String>,
+    /// all fields of the date that are not explicitly modeled
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// the CSL type for the crossref type of a work
+pub fn csl_type(crossref_type: &str) -> &'static str {
+    match crossref_type {
+        "journal-article" => "article-journal",
+        "book-chapter" | "book-section" | "book-part" | "book-track" => "chapter",
+        "proceedings-article" => "paper-conference",
+        "book" | "monograph" | "edited-book" | "reference-book" | "book-set" | "book-series" => {
+            "book"
+        }
+        "reference-entry" => "entry-encyclopedia",
+        "dissertation" => "thesis",
+        "report" | "report-series" => "report",
+        "dataset" => "dataset",
+        "standard" | "standard-series" => "standard",
+        "peer-review" => "review",
+        "journal" | "journal-issue" | "journal-volume" => "periodical",
+        _ => "article",
+    }
+}
+
+/// the crossref type for the CSL type of an item
+pub fn crossref_type(csl_type: &str) -> &'static str {
+    match csl_type {
+        "article-journal" => "journal-article",
+        "chapter" => "book-chapter",
+        "paper-conference" => "proceedings-article",
+        "book" => "book",
+        "entry" | "entry-dictionary" | "entry-encyclopedia" => "reference-entry",
+        "thesis" => "dissertation",
+        "report" => "report",
+        "dataset" => "dataset",
+        "standard" => "standard",
+        "review" | "review-book" => "peer-review",
+        "periodical" => "journal",
+        "article" | "manuscript" => "posted-content",
+        _ => "other",
+    }
+}
+
+impl From<&Contributor> for CslName {
+    fn from(contributor: &Contributor) -> Self {
+        let particle = |key: &str| Some(contributor.extra.get(key)?.as_str()?.to_string());
+        CslName {
+            family: contributor.family.clone(),
+            given: contributor.given.clone(),
+            literal: contributor.name.clone(),
+            suffix: contributor.suffix.clone(),
+            non_dropping_particle: particle("non-dropping-particle"),
+            dropping_particle: particle("dropping-particle"),
+            ..CslName::default()
+        }
+    }
+}
+
+impl From<CslName> for Contributor {
+    /// the name particles are kept in the `extra` fields
+    fn from(mut name: CslName) -> Self {
+        let particles = [
+            ("non-dropping-particle", name.non_dropping_particle),
+            ("dropping-particle", name.dropping_particle),
+        ];
+        for (key, particle) in particles.iter() {
+            if let Some(particle) = particle {
+                name.extra.insert(key.to_string(), particle.clone().into());
+            }
+        }
+        Contributor {
+            family: name.family,
+            given: name.given,
+            name: name.literal,
+            suffix: name.suffix,
+            orcid: None,
+            sequence: None,
+            affiliation: None,
+            extra: name.extra,
+        }
+    }
+}
+
+impl From<&PartialDate> for CslDate {
+    fn from(date: &PartialDate) -> Self {
+        let date_parts = (date.date_parts.0)
+            .iter()
+            .map(|parts| {
+                parts
+                    .iter()
+                    .take_while(|part| part.is_some())
+                    .filter_map(|part| part.map(|p| p as i32))
+                    .collect::<Vec<_>>()
+            })
+            .filter(|parts| !parts.is_empty())
+            .collect();
+        CslDate {
+            date_parts,
+            ..CslDate::default()
+        }
+    }
+}
+
+impl TryFrom<CslDate> for PartialDate {
+    type Error = Error;
+
+    /// a `raw` date is parsed if there are no date parts, `literal` dates can not be represented.
+    /// Fails for years before the common era, crossref only has positive date parts
+    fn try_from(date: CslDate) -> crate::Result<Self> {
+        let invalid = |date: &str| -> Error {
+            ErrorKind::InvalidDate {
+                date: date.to_string(),
+            }
+            .into()
+        };
+        let mut parts = date.date_parts;
+        if parts.is_empty() {
+            if let Some(raw) = &date.raw {
+                parts = vec![parse_raw(raw).ok_or_else(|| invalid(raw))?];
+            } else if let Some(literal) = &date.literal {
+                return Err(invalid(literal));
+            }
+        }
+        let mut date_parts = Vec::with_capacity(parts.len());
+        for date in parts {
+            if date.iter().any(|part| *part <= 0) {
+                return Err(invalid(&format!("{:?}", date)));
+            }
+            date_parts.push(date.into_iter().map(|p| Some(p as u32)).collect());
+        }
+        if date_parts.is_empty() {
+            // crossref marks unknown dates with a single `null`
+            date_parts.push(vec![None]);
+        }
+        Ok(PartialDate {
+            date_parts: DateParts(date_parts),
+            extra: date.extra,
+        })
+    }
+}
+
+/// parses `raw` dates like `2004`, `2004-01-21` or `2004/1`
+fn parse_raw(raw: &str) -> Option<Vec<i32>> {
+    let parts = raw
+        .trim()
+        .split(['-', '/'])
+        .map(|part| part.parse().ok())
+        .collect::<Option<Vec<i32>>>()?;
+    if parts.is_empty() || parts.len() > 3 {
+        None
+    } else {
+        Some(parts)
+    }
+}
+
+impl From<&Work> for CslItem {
+    fn from(work: &Work) -> Self {
+        let names = |contributors: &Option<Vec<Contributor>>| -> Vec<CslName> {
+            contributors.iter().flatten().map(CslName::from).collect()
+        };
+        let first = |values: &Option<Vec<String>>| values.as_ref()?.first().cloned();
+        let mut extra = Map::new();
+        let mut insert = |key: &str, value: Value| {
+            if !value.is_null() {
+                extra.insert(key.to_string(), value);
+            }
+        };
+        insert(
+            "translator",
+            serde_json::to_value(names(&work.translator)).unwrap_or_default(),
+        );
+        insert(
+            "chair",
+            serde_json::to_value(names(&work.chair)).unwrap_or_default(),
+        );
+        insert(
+            "container-title-short",
+            first(&work.short_container_title).into(),
+        );
+        insert("subtitle", first(&work.subtitle).into());
+        insert("number", work.article_number.clone().into());
+        insert("language", work.language.clone().into());
+        extra.retain(|_, value| value.as_array().map(|a| !a.is_empty()).unwrap_or(true));
+
+        CslItem {
+            id: Some(work.doi.clone()),
+            type_: csl_type(work.type_.as_deref().unwrap_or_default()).to_string(),
+            title: work.title.first().cloned(),
+            container_title: first(&work.container_title),
+            author: names(&work.author),
+            editor: names(&work.editor),
+            issued: work.published().map(CslDate::from),
+            page: work.page.clone(),
+            volume: work.volume.clone(),
+            issue: work.issue.clone(),
+            publisher: work.publisher.clone(),
+            doi: Some(work.doi.clone()),
+            url: work.url.clone(),
+            issn: work.issn.clone().unwrap_or_default(),
+            isbn: work.isbn.clone().unwrap_or_default(),
+            abstract_: work.abstract_.clone(),
+            extra,
+        }
+    }
+}
+
+impl TryFrom<CslItem> for Work {
+    type Error = Error;
+
+    /// best effort conversion, the item needs a DOI
+    fn try_from(mut item: CslItem) -> crate::Result<Self> {
+        let doi = match item.doi.take() {
+            Some(doi) => doi,
+            None => {
+                return Err(ErrorKind::MissingField {
+                    field: "DOI".to_string(),
+                }
+                .into())
+            }
+        };
+        let names = |names: Vec<CslName>| -> Option<Vec<Contributor>> {
+            if names.is_empty() {
+                None
+            } else {
+                Some(names.into_iter().map(Contributor::from).collect())
+            }
+        };
+        let extra = &mut item.extra;
+        let translator = take::<Vec<CslName>>(extra, "translator").and_then(names);
+        let chair = take::<Vec<CslName>>(extra, "chair").and_then(names);
+        let short_container_title = take_string(extra, "container-title-short")
+            .or_else(|| take_string(extra, "journalAbbreviation"))
+            .map(|title| vec![title]);
+        let short_title = take(extra, "short-title")
+            .or_else(|| take_string(extra, "title-short").map(|title| vec![title]));
+        let subtitle = take_string(extra, "subtitle").map(|title| vec![title]);
+        let article_number = take_string(extra, "number");
+        let language = take_string(extra, "language");
+        let is_referenced_by_count = take(extra, "is-referenced-by-count").unwrap_or_default();
+        let vec = |values: Vec<String>| {
+            if values.is_empty() {
+                None
+            } else {
+                Some(values)
+            }
+        };
+
+        let mut work = Work {
+            publisher: item.publisher,
+            title: item.title.into_iter().collect(),
+            original_title: take(extra, "original-title"),
+            short_title,
+            subtitle,
+            abstract_: item.abstract_,
+            is_referenced_by_count,
+            prefix: take_string(extra, "prefix"),
+            doi,
+            url: item.url,
+            member: take_string(extra, "member"),
+            type_: Some(crossref_type(&item.type_).to_string()),
+            published_print: take(extra, "published-print"),
+            published_online: take(extra, "published-online"),
+            posted: take(extra, "posted"),
+            accepted: take(extra, "accepted"),
+            issued: PartialDate::try_from(item.issued.unwrap_or_default())?,
+            container_title: item.container_title.map(|title| vec![title]),
+            short_container_title,
+            issue: item.issue,
+            volume: item.volume,
+            page: item.page,
+            article_number,
+            issn: vec(item.issn),
+            issn_type: take(extra, "issn-type"),
+            isbn: vec(item.isbn),
+            subject: take(extra, "subject"),
+            language,
+            license: take(extra, "license"),
+            funder: take(extra, "funder"),
+            author: names(item.author),
+            editor: names(item.editor),
+            chair,
+            translator,
+            link: take(extra, "link"),
+            reference: take(extra, "reference"),
+            relation: take(extra, "relation"),
+            extra: Map::new(),
+        };
+
+        // CSL variables with a crossref counterpart, any other CSL variable has no place in a work
+        if let Some(place) = take_string(extra, "publisher-place") {
+            work.extra
+                .insert("publisher-location".to_string(), place.into());
+        }
+        let mut event = Map::new();
+        if let Some(name) =
+            take_string(extra, "event-title").or_else(|| take_string(extra, "event"))
+        {
+            event.insert("name".to_string(), name.into());
+        }
+        if let Some(location) = take_string(extra, "event-place") {
+            event.insert("location".to_string(), location.into());
+        }
+        if !event.is_empty() {
+            work.extra.insert("event".to_string(), event.into());
+        }
+        for key in CROSSREF_FIELDS {
+            if let Some(value) = extra.remove(*key) {
+                work.extra.insert(key.to_string(), value);
+            }
+        }
+        Ok(work)
+    }
+}
+
+/// the fields of crossref works that are not modeled by `Work` but may be part of
+/// the CSL-JSON crossref returns, like `source` or `indexed`
+const CROSSREF_FIELDS: &[&str] = &[
+    "indexed",
+    "created",
+    "deposited",
+    "source",
+    "score",
+    "reference-count",
+    "references-count",
+    "content-domain",
+    "journal-issue",
+    "update-policy",
+    "update-to",
+    "archive",
+    "assertion",
+    "alternative-id",
+    "clinical-trial-number",
+    "group-title",
+    "edition-number",
+    "institution",
+    "degree",
+    "subtype",
+    "review",
+    "free-to-read",
+];
+
+/// removes the value of the `key` and decodes it, `None` if it is missing or of another type
+fn take<T: DeserializeOwned>(extra: &mut Map<String, Value>, key: &str) -> Option<T> {
+    serde_json::from_value(extra.remove(key)?).ok()
+}
+
+/// removes the value of the `key` if it is a string or number
+fn take_string(extra: &mut Map<String, Value>, key: &str) -> Option<String> {
+    match extra.remove(key)? {
+        Value::String(s) => Some(s),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// turns strings, numbers and the first element of arrays into an optional string
+fn string<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(match Value::deserialize(deserializer)? {
+        Value::String(s) => Some(s),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Array(values) => values.into_iter().find_map(|v| match v {
+            Value::String(s) => Some(s),
+            Value::Number(n) => Some(n.to_string()),
+            _ => None,
+        }),
+        _ => None,
+    })
+}
+
+/// accepts a single string or an array of strings
+fn strings<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(match Value::deserialize(deserializer)? {
+        Value::String(s) => vec![s],
+        Value::Array(values) => values
+            .into_iter()
+            .filter_map(|v| match v {
+                Value::String(s) => Some(s),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    })
+}
+
+/// date parts can be numbers or numeric strings, incomplete parts are dropped
+fn date_parts<'de, D>(deserializer: D) -> Result<Vec<Vec<i32>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let parts: Vec<Vec<Value>> = Option::deserialize(deserializer)?.unwrap_or_default();
+    let mut dates = Vec::with_capacity(parts.len());
+    for date in parts {
+        let date = date
+            .into_iter()
+            .take_while(|part| !part.is_null())
+            .map(|part| {
+                match &part {
+                    Value::Number(n) => n.as_i64().map(|n| n as i32),
+                    Value::String(s) => s.trim().parse().ok(),
+                    _ => None,
+                }
+                .ok_or_else(|| D::Error::custom(format!("invalid date part {}", part)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if !date.is_empty() {
+            dates.push(date);
+        }
+    }
+    Ok(dates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::*;
+
+    #[test]
+    fn crossref_csl_deserialize() {
+        let csl_str = r#"{"indexed":{"date-parts":[[2019,2,26]],"date-time":"2019-02-26T10:43:14Z","timestamp":1551177794515},"reference-count":105,"publisher":"American Psychological Association (APA)","issue":"1","DOI":"10.1037/0003-066x.59.1.29","type":"article-journal","page":"29-40","source":"Crossref","title":"How the Mind Hurts and Heals the Body.","prefix":"10.1037","volume":"59","author":[{"given":"Oakley","family":"Ray","sequence":"first","affiliation":[]}],"member":"15","container-title":"American Psychologist","language":"en","issued":{"date-parts":[[2004]]},"URL":"http://dx.doi.org/10.1037/0003-066x.59.1.29","ISSN":["0003-066X","1935-990X"]}"#;
+
+        let item: CslItem = from_str(csl_str).unwrap();
+
+        assert_eq!("article-journal", item.type_);
+        assert_eq!(
+            Some("American Psychologist"),
+            item.container_title.as_deref()
+        );
+        assert_eq!(Some("Ray"), item.author[0].family.as_deref());
+        assert_eq!(vec![vec![2004]], item.issued.unwrap().date_parts);
+        assert_eq!(vec!["0003-066X", "1935-990X"], item.issn);
+        assert_eq!("15", item.extra["member"]);
+    }
+
+    #[test]
+    fn work_to_csl() {
+        let work_str = r#"{"publisher":"American Psychological Association (APA)","issue":"1","DOI":"10.1037/0003-066x.59.1.29","type":"journal-article","page":"29-40","is-referenced-by-count":84,"title":["How the Mind Hurts and Heals the Body."],"volume":"59","author":[{"given":"Oakley","family":"Ray","sequence":"first","affiliation":[]},{"name":"APA Task Force"}],"container-title":["American Psychologist"],"short-container-title":["Am Psychol"],"issued":{"date-parts":[[null]]},"published-online":{"date-parts":[[2004,1]]},"ISSN":["0003-066X","1935-990X"]}"#;
+        let work: Work = from_str(work_str).unwrap();
+
+        let item = CslItem::from(&work);
+
+        assert_eq!(
+            json!({
+                "id": "10.1037/0003-066x.59.1.29",
+                "type": "article-journal",
+                "title": "How the Mind Hurts and Heals the Body.",
+                "container-title": "American Psychologist",
+                "container-title-short": "Am Psychol",
+                "author": [
+                    {"family": "Ray", "given": "Oakley"},
+                    {"literal": "APA Task Force"}
+                ],
+                "issued": {"date-parts": [[2004, 1]]},
+                "page": "29-40",
+                "volume": "59",
+                "issue": "1",
+                "publisher": "American Psychological Association (APA)",
+                "DOI": "10.1037/0003-066x.59.1.29",
+                "ISSN": ["0003-066X", "1935-990X"]
+            }),
+            to_value(&item).unwrap()
+        );
+    }
+
+    #[test]
+    fn csl_to_work() {
+        // the chapter sample of the CSL-JSON schema
+        let csl_str = r#"{"id":"ITEM-2","type":"chapter","title":"The Evolution of Language","container-title":"Handbook of Linguistics","container-title-short":"Hdb Ling","author":[{"family":"Gogh","given":"Vincent","non-dropping-particle":"van"},{"literal":"The Linguistics Group"}],"editor":[{"family":"Doe","given":"Jane","suffix":"Jr."}],"translator":[{"family":"Roe","given":"Richard"}],"issued":{"date-parts":[[2000,1,15],[2000,2]]},"page":"12-34","volume":"2","publisher":"Academic Press","publisher-place":"New York","DOI":"10.1000/xyz123","ISBN":"978-0-12-345678-9","language":"en-US"}"#;
+        let item: CslItem = from_str(csl_str).unwrap();
+
+        let work = Work::try_from(item.clone()).unwrap();
+
+        assert_eq!("10.1000/xyz123", work.doi);
+        assert_eq!(Some("book-chapter"), work.type_.as_deref());
+        assert_eq!(vec!["The Evolution of Language"], work.title);
+        assert_eq!(
+            Some(vec!["Handbook of Linguistics".to_string()]),
+            work.container_title
+        );
+        assert_eq!(
+            Some(vec!["Hdb Ling".to_string()]),
+            work.short_container_title
+        );
+        let authors = work.author.as_ref().unwrap();
+        assert_eq!(Some("Gogh"), authors[0].family.as_deref());
+        assert_eq!("van", authors[0].extra["non-dropping-particle"]);
+        assert_eq!(Some("The Linguistics Group"), authors[1].name.as_deref());
+        assert_eq!(
+            Some("Jr."),
+            work.editor.as_ref().unwrap()[0].suffix.as_deref()
+        );
+        assert_eq!(
+            Some("Roe"),
+            work.translator.as_ref().unwrap()[0].family.as_deref()
+        );
+        assert_eq!(Some((2000, Some(1), Some(15))), work.issued.ymd());
+        assert_eq!(Some("12-34"), work.page.as_deref());
+        assert_eq!(Some("en-US"), work.language.as_deref());
+        assert_eq!("New York", work.extra["publisher-location"]);
+        assert_eq!(1, work.extra.len());
+
+        // converting back keeps what CSL-JSON can express
+        let back = CslItem::from(&work);
+        assert_eq!(item.type_, back.type_);
+        assert_eq!(item.issued, back.issued);
+        assert_eq!(item.isbn, back.isbn);
+        assert_eq!(item.author, back.author);
+        assert_eq!(item.editor[0].suffix, back.editor[0].suffix);
+        assert_eq!("Roe", back.extra["translator"][0]["family"]);
+
+        let missing_doi: CslItem = from_str(r#"{"type":"book","title":"No DOI"}"#).unwrap();
+        assert_eq!(
+            "missing required field `DOI`",
+            Work::try_from(missing_doi).unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn csl_dates_to_work() {
+        let item = |issued: &str| -> CslItem {
+            from_str(&format!(
+                r#"{{"type":"book","DOI":"10.5555/1","issued":{}}}"#,
+                issued
+            ))
+            .unwrap()
+        };
+
+        let work = Work::try_from(item(r#"{"raw":"2004-01-21"}"#)).unwrap();
+        assert_eq!(Some((2004, Some(1), Some(21))), work.issued.ymd());
+        let work = Work::try_from(item(r#"{"date-parts":[]}"#)).unwrap();
+        assert_eq!(None, work.issued.ymd());
+
+        assert_eq!(
+            "invalid date `[-44, 3, 15]`",
+            Work::try_from(item(r#"{"date-parts":[[-44,3,15]]}"#))
+                .unwrap_err()
+                .to_string()
+        );
+        assert_eq!(
+            "invalid date `circa 1900`",
+            Work::try_from(item(r#"{"literal":"circa 1900"}"#))
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn crossref_csl_to_work() {
+        let csl_str = r#"{"source":"Crossref","is-referenced-by-count":84,"prefix":"10.1037","member":"15","subject":["Psychology"],"type":"article-journal","DOI":"10.1037/0003-066x.59.1.29","title":"How the Mind Hurts and Heals the Body.","issued":{"date-parts":[[2004]]},"note":"a CSL note"}"#;
+        let item: CslItem = from_str(csl_str).unwrap();
+
+        let work = Work::try_from(item).unwrap();
+
+        assert_eq!(84, work.is_referenced_by_count);
+        assert_eq!(Some("15"), work.member.as_deref());
+        assert_eq!(Some(vec!["Psychology".to_string()]), work.subject);
+        assert_eq!("Crossref", work.extra["source"]);
+        assert!(!work.extra.contains_key("note"));
+        assert!(!work.extra.contains_key("is-referenced-by-count"));
+    }
+
+    #[test]
+    fn lenient_csl_deserialize() {
+        let csl_str = r#"{"id":42,"type":"book","title":["A Title"],"volume":3,"ISBN":"978-3-16-148410-0","issued":{"date-parts":[["2001","5"],[null]]}}"#;
+
+        let item: CslItem = from_str(csl_str).unwrap();
+
+        assert_eq!(Some("42"), item.id.as_deref());
+        assert_eq!(Some("A Title"), item.title.as_deref());
+        assert_eq!(Some("3"), item.volume.as_deref());
+        assert_eq!(vec!["978-3-16-148410-0"], item.isbn);
+        assert_eq!(vec![vec![2001, 5]], item.issued.unwrap().date_parts);
+    }
+}
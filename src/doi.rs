@@ -0,0 +1,99 @@
+use url::percent_encoding::percent_decode;
+
+/// prefixes in front of the DOI, compared case insensitive
+const PREFIXES: &[&str] = &[
+    "https://doi.org/",
+    "http://doi.org/",
+    "https://dx.doi.org/",
+    "http://dx.doi.org/",
+    "doi.org/",
+    "dx.doi.org/",
+    "doi:",
+    "doi",
+];
+
+/// Normalizes a DOI as it is found in spreadsheets, references or links.
+///
+/// Surrounding whitespace, quotes and trailing punctuation are removed,
+/// as well as resolver urls like `https://doi.org/` and `doi:` prefixes.
+/// Percent encoded urls are decoded.
+/// Since DOIs are case insensitive the result is lowercase.
+///
+/// Returns `None` if the remainder is no DOI of the form `10.<registrant>/<suffix>`.
+///
+/// # Example
+///
+/// ```edition2018
+/// use crossref::doi::normalize;
+///
+/// assert_eq!(Some("10.1037/0003-066x.59.1.29".to_string()), normalize(" https://doi.org/10.1037/0003-066X.59.1.29 "));
+/// assert_eq!(Some("10.1037/0003-066x.59.1.29".to_string()), normalize("DOI: 10.1037/0003-066X.59.1.29."));
+/// assert_eq!(None, normalize("0003-066X.59.1.29"));
+/// ```
+pub fn normalize(doi: &str) -> Option<String> {
+    let mut doi = doi
+        .trim()
+        .trim_matches(|c| c == '"' || c == '\'' || c == '<' || c == '>')
+        .trim()
+        .to_lowercase();
+    for prefix in PREFIXES {
+        if doi.starts_with(prefix) {
+            doi = doi[prefix.len()..].trim_start().to_string();
+            break;
+        }
+    }
+    if doi.contains('%') {
+        doi = percent_decode(doi.as_bytes())
+            .decode_utf8()
+            .ok()?
+            .to_string();
+    }
+    let doi = doi.trim_end_matches(['.', ',', ';']);
+
+    let slash = doi.find('/')?;
+    let (registrant, suffix) = (&doi[..slash], &doi[slash + 1..]);
+    let valid_registrant = registrant.starts_with("10.")
+        && registrant[3..]
+            .split('.')
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()));
+    if valid_registrant && !suffix.is_empty() && !doi.contains(char::is_whitespace) {
+        Some(doi.to_string())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_dois() {
+        let doi = Some("10.1002/(sici)1097-4571(199806)49:8<693::aid-asi4>3.0.co;2-0".to_string());
+        assert_eq!(
+            doi,
+            normalize("10.1002/(SICI)1097-4571(199806)49:8<693::AID-ASI4>3.0.CO;2-0")
+        );
+        assert_eq!(
+            doi,
+            normalize("https://doi.org/10.1002/%28SICI%291097-4571%28199806%2949%3A8%3C693%3A%3AAID-ASI4%3E3.0.CO%3B2-0")
+        );
+        assert_eq!(
+            Some("10.5555/12345678".to_string()),
+            normalize("\"http://dx.doi.org/10.5555/12345678\"")
+        );
+        assert_eq!(
+            Some("10.5555/12345678".to_string()),
+            normalize("doi:10.5555/12345678;")
+        );
+        assert_eq!(
+            Some("10.1000.10/abc".to_string()),
+            normalize("10.1000.10/ABC")
+        );
+        assert_eq!(None, normalize(""));
+        assert_eq!(None, normalize("10.5555/"));
+        assert_eq!(None, normalize("10.abc/def"));
+        assert_eq!(None, normalize("https://example.org/10.5555/1"));
+        assert_eq!(None, normalize("10.5555/12 34"));
+    }
+}
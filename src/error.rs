@@ -1,3 +1,6 @@
+// `failure`'s derive puts the impls of `ErrorKind` into a named const
+#![allow(non_local_definitions)]
+
 use crate::query::ResourceComponent;
 use crate::response::MessageType;
 use failure::{Backtrace, Compat, Context, Fail};
@@ -29,6 +32,23 @@ impl fmt::Display for Error {
     }
 }
 
+impl Error {
+    /// Whether retrying the request may succeed.
+    ///
+    /// This is the case for timeouts, failed connections, rate limiting and server errors.
+    pub fn is_transient(&self) -> bool {
+        match self.ctx.get_context() {
+            ErrorKind::ReqWest { reqwest } => match reqwest.status() {
+                Some(status) => {
+                    status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+                }
+                None => reqwest.is_timeout() || reqwest.is_http(),
+            },
+            _ => false,
+        }
+    }
+}
+
 /// all different error types this crate uses
 #[derive(Debug, Fail)]
 pub enum ErrorKind {
@@ -36,6 +56,20 @@ pub enum ErrorKind {
     #[fail(display = "invalid type name: {}", name)]
     InvalidTypeName { name: String },
 
+    /// if a facet expression could not be parsed
+    #[fail(display = "invalid facet: {}", msg)]
+    InvalidFacet {
+        /// the notification
+        msg: String,
+    },
+
+    /// if a filter expression could not be parsed
+    #[fail(display = "invalid filter: {}", msg)]
+    InvalidFilter {
+        /// the notification
+        msg: String,
+    },
+
     /// if there is a mismatch between the expected return type of the crossref api and this rust client
     #[fail(
         display = "expected response item of type {} but got {}",
@@ -67,9 +101,30 @@ pub enum ErrorKind {
     /// When crossref could not find anything
     #[fail(display = "Nothing was found for resource `{}`", resource)]
     ResourceNotFound { resource: Box<ResourceComponent> },
+    /// When the DOI resolver could not find the DOI
+    #[fail(display = "DOI `{}` could not be resolved", doi)]
+    DoiNotFound { doi: String },
+    /// When the registration agency of a DOI can not provide the requested content type
+    #[fail(
+        display = "DOI `{}` is not available as `{}` via content negotiation",
+        doi, format
+    )]
+    NotAcceptable { doi: String, format: String },
+    /// if a required field is missing when converting between formats
+    #[fail(display = "missing required field `{}`", field)]
+    MissingField { field: String },
+    /// if a date can not be represented by crossref's date parts
+    #[fail(display = "invalid date `{}`", date)]
+    InvalidDate { date: String },
+    /// if a unixref or unixsd document could not be read
+    #[fail(display = "invalid unixref: {}", msg)]
+    Xml { msg: String },
     /// if a error in serde occurred
     #[fail(display = "invalid serde: {}", error)]
     Serde { error: serde_json::Error },
+    /// if reading or writing a file failed
+    #[fail(display = "{}", error)]
+    Io { error: std::io::Error },
 }
 
 impl From<ErrorKind> for Error {
@@ -90,6 +145,12 @@ impl From<serde_json::Error> for Error {
     }
 }
 
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Error {
+        ErrorKind::Io { error }.into()
+    }
+}
+
 impl From<reqwest::Error> for Error {
     fn from(reqwest: reqwest::Error) -> Error {
         ErrorKind::ReqWest { reqwest }.into()
@@ -0,0 +1,356 @@
+// see http://www.bibtex.org/Format/ and http://mirrors.ctan.org/macros/latex/contrib/biblatex/doc/biblatex.pdf
+
+use crate::export::{contributors, first, fold, institution, iso_date};
+use crate::response::work::{Contributor, Work};
+
+/// words that are skipped when choosing the title word of a citation key
+const STOP_WORDS: &[&str] = &[
+    "a", "an", "and", "at", "by", "das", "der", "die", "for", "from", "in", "is", "la", "le",
+    "les", "of", "on", "the", "to", "with",
+];
+
+const MONTHS: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+
+/// the two flavors of bibliography entries
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dialect {
+    BibTex,
+    BibLatex,
+}
+
+impl Work {
+    /// Exports the work as a BibTeX entry.
+    ///
+    /// The entry type is derived from the type of the work,
+    /// `journal-article` becomes `@article`, `book-chapter` becomes `@incollection` and so on.
+    ///
+    /// # Example
+    ///
+    /// ```edition2018
+    /// use crossref::Crossref;
+    /// # fn run() -> Result<(), crossref::Error> {
+    /// let client = Crossref::builder().build()?;
+    ///
+    /// let work = client.work("10.1037/0003-066x.59.1.29")?;
+    /// println!("{}", work.to_bibtex());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_bibtex(&self) -> String {
+        entry(self, Dialect::BibTex)
+    }
+
+    /// Exports the work as a BibLaTeX entry.
+    ///
+    /// Other than `to_bibtex` this uses the BibLaTeX entry types like `@thesis`, `@report`,
+    /// `@dataset` and `@online` and the `date` and `journaltitle` fields.
+    pub fn to_biblatex(&self) -> String {
+        entry(self, Dialect::BibLatex)
+    }
+
+    /// A citation key of the form `{family name}{year}{first title word}`, like `ray2004how`.
+    ///
+    /// The key only depends on the metadata of the work, works without a name fall back to their DOI.
+    pub fn citation_key(&self) -> String {
+        let name = contributors(&self.author)
+            .iter()
+            .chain(contributors(&self.editor))
+            .filter_map(|c| c.family.as_ref().or(c.name.as_ref()))
+            .map(|name| fold(name))
+            .find(|name| !name.is_empty());
+        let name = match name {
+            Some(name) => name,
+            None => {
+                return self
+                    .doi
+                    .to_lowercase()
+                    .chars()
+                    .map(|c| match c {
+                        'a'..='z' | '0'..='9' | '.' | '-' | ':' | '/' => c,
+                        _ => '_',
+                    })
+                    .collect();
+            }
+        };
+        let year = self
+            .published()
+            .and_then(|date| date.ymd())
+            .map(|(year, _, _)| year.to_string())
+            .unwrap_or_default();
+        let word = self
+            .title
+            .first()
+            .into_iter()
+            .flat_map(|title| title.split(|c: char| !c.is_alphanumeric()))
+            .map(fold)
+            .find(|word| !word.is_empty() && !STOP_WORDS.contains(&word.as_str()))
+            .unwrap_or_default();
+        format!("{}{}{}", name, year, word)
+    }
+}
+
+/// the entry type for the crossref type of the work
+fn entry_type(type_: &str, dialect: Dialect) -> &'static str {
+    match (type_, dialect) {
+        ("journal-article", _) => "article",
+        ("book-chapter", _) | ("book-section", _) | ("book-part", _) | ("book-track", _) => {
+            "incollection"
+        }
+        ("reference-entry", Dialect::BibTex) => "incollection",
+        ("reference-entry", Dialect::BibLatex) => "inreference",
+        ("proceedings-article", _) => "inproceedings",
+        ("proceedings", _) => "proceedings",
+        ("book", _)
+        | ("monograph", _)
+        | ("edited-book", _)
+        | ("reference-book", _)
+        | ("book-set", _)
+        | ("book-series", _) => "book",
+        ("dissertation", Dialect::BibTex) => "phdthesis",
+        ("dissertation", Dialect::BibLatex) => "thesis",
+        ("report", Dialect::BibTex) | ("report-series", Dialect::BibTex) => "techreport",
+        ("report", Dialect::BibLatex) | ("report-series", Dialect::BibLatex) => "report",
+        ("dataset", Dialect::BibLatex) => "dataset",
+        ("posted-content", Dialect::BibLatex) => "online",
+        ("standard", Dialect::BibLatex) => "standard",
+        _ => "misc",
+    }
+}
+
+/// the fields of an entry in the order they are rendered
+#[derive(Default)]
+struct Fields(Vec<(&'static str, String)>);
+
+impl Fields {
+    /// adds a text field, special characters are escaped
+    fn text(&mut self, name: &'static str, value: Option<&str>) {
+        if let Some(value) = value.map(str::trim).filter(|v| !v.is_empty()) {
+            self.raw(name, format!("{{{}}}", escape(value)));
+        }
+    }
+
+    /// adds a verbatim field like `doi` or `url`, which is written as is
+    fn verbatim(&mut self, name: &'static str, value: Option<&str>) {
+        if let Some(value) = value.map(str::trim).filter(|v| !v.is_empty()) {
+            self.raw(name, format!("{{{}}}", value));
+        }
+    }
+
+    /// adds a field that is already valid BibTeX
+    fn raw(&mut self, name: &'static str, value: String) {
+        self.0.push((name, value));
+    }
+}
+
+/// renders the whole entry
+fn entry(work: &Work, dialect: Dialect) -> String {
+    let type_ = work.type_.as_deref().unwrap_or_default();
+    let entry_type = entry_type(type_, dialect);
+    let publisher = work.publisher.as_deref();
+    let mut fields = Fields::default();
+
+    if let Some(authors) = names(contributors(&work.author)) {
+        fields.raw("author", authors);
+    }
+    if let Some(editors) = names(contributors(&work.editor)) {
+        fields.raw("editor", editors);
+    }
+    if dialect == Dialect::BibLatex {
+        if let Some(translators) = names(contributors(&work.translator)) {
+            fields.raw("translator", translators);
+        }
+    }
+    fields.text("title", work.title.first().map(String::as_str));
+    if dialect == Dialect::BibLatex {
+        fields.text("subtitle", first(&work.subtitle));
+    }
+    let container = first(&work.container_title);
+    match (entry_type, dialect) {
+        ("article", Dialect::BibTex) => fields.text("journal", container),
+        ("article", Dialect::BibLatex) => fields.text("journaltitle", container),
+        ("incollection", _) | ("inproceedings", _) | ("inreference", _) => {
+            fields.text("booktitle", container)
+        }
+        ("book", _) | ("proceedings", _) => fields.text("series", container),
+        ("online", _) => fields.text("organization", container),
+        _ => (),
+    }
+    match entry_type {
+        "phdthesis" => fields.text("school", institution(work).or(publisher)),
+        "thesis" => {
+            fields.text("institution", institution(work).or(publisher));
+            fields.text("type", Some("phdthesis"));
+        }
+        "techreport" => fields.text("institution", publisher),
+        "report" => {
+            fields.text("institution", publisher);
+            fields.text("type", Some("techreport"));
+        }
+        _ => fields.text("publisher", publisher),
+    }
+
+    match (work.published().and_then(|date| date.ymd()), dialect) {
+        (Some((year, month, _)), Dialect::BibTex) => {
+            fields.raw("year", format!("{{{}}}", year));
+            let month = month.and_then(|m| MONTHS.get((m as usize).checked_sub(1)?));
+            if let Some(month) = month {
+                // month macros are not enclosed in braces
+                fields.raw("month", month.to_string());
+            }
+        }
+        (Some((year, month, day)), Dialect::BibLatex) => {
+            fields.raw("date", format!("{{{}}}", iso_date(year, month, day)));
+        }
+        (None, _) => (),
+    }
+
+    fields.text("volume", work.volume.as_deref());
+    fields.text("number", work.issue.as_deref());
+    match (&work.page, &work.article_number, dialect) {
+        (Some(page), _, _) => fields.text("pages", Some(&pages(page))),
+        (None, Some(number), Dialect::BibLatex) => fields.text("eid", Some(number)),
+        (None, Some(number), Dialect::BibTex) => fields.text("pages", Some(number)),
+        _ => (),
+    }
+    fields.text("issn", first(&work.issn));
+    fields.text("isbn", first(&work.isbn));
+    fields.verbatim("doi", Some(&work.doi));
+    fields.verbatim("url", work.url.as_deref());
+    if dialect == Dialect::BibLatex {
+        fields.text("langid", work.language.as_deref());
+    }
+
+    let mut entry = format!("@{}{{{}", entry_type, work.citation_key());
+    for (name, value) in fields.0 {
+        entry.push_str(&format!(",\n  {} = {}", name, value));
+    }
+    entry.push_str("\n}\n");
+    entry
+}
+
+/// joins the names with `and`, persons as `family, given` and organizations in braces
+fn names(contributors: &[Contributor]) -> Option<String> {
+    let names: Vec<_> = contributors
+        .iter()
+        .filter_map(|c| {
+            let name = match (&c.family, &c.given, &c.suffix, &c.name) {
+                (Some(family), Some(given), Some(suffix), _) => {
+                    format!("{}, {}, {}", family, suffix, given)
+                }
+                (Some(family), Some(given), None, _) => format!("{}, {}", family, given),
+                // the given name stays empty, so the suffix is not taken for it
+                (Some(family), None, Some(suffix), _) => format!("{}, {},", family, suffix),
+                (Some(family), None, None, _) => family.clone(),
+                (None, _, _, Some(name)) => return Some(format!("{{{}}}", escape(name))),
+                (None, Some(given), _, None) => given.clone(),
+                _ => return None,
+            };
+            Some(escape(&name))
+        })
+        .collect();
+    if names.is_empty() {
+        None
+    } else {
+        Some(format!("{{{}}}", names.join(" and ")))
+    }
+}
+
+/// page ranges are separated by an en dash `--`
+fn pages(page: &str) -> String {
+    page.replace('\u{2013}', "-")
+        .replace("--", "-")
+        .replace('-', "--")
+}
+
+/// escapes LaTeX special characters and collapses whitespace
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for (i, word) in value.split_whitespace().enumerate() {
+        if i > 0 {
+            escaped.push(' ');
+        }
+        for c in word.chars() {
+            match c {
+                '\\' => escaped.push_str("\\textbackslash{}"),
+                '~' => escaped.push_str("\\textasciitilde{}"),
+                '^' => escaped.push_str("\\textasciicircum{}"),
+                '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                    escaped.push('\\');
+                    escaped.push(c);
+                }
+                c => escaped.push(c),
+            }
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::*;
+
+    #[test]
+    fn article_to_bibtex() {
+        let work_str = r#"{"publisher":"American Psychological Association (APA)","issue":"1","DOI":"10.1037/0003-066x.59.1.29","type":"journal-article","page":"29-40","is-referenced-by-count":84,"title":["How the Mind Hurts and Heals the Body."],"volume":"59","author":[{"given":"Oakley","family":"Ray","sequence":"first","affiliation":[]}],"container-title":["American Psychologist"],"issued":{"date-parts":[[2004,1]]},"URL":"http://dx.doi.org/10.1037/0003-066x.59.1.29","ISSN":["0003-066X","1935-990X"]}"#;
+        let work: Work = from_str(work_str).unwrap();
+
+        assert_eq!("ray2004how", work.citation_key());
+        assert_eq!(
+            r#"@article{ray2004how,
+  author = {Ray, Oakley},
+  title = {How the Mind Hurts and Heals the Body.},
+  journal = {American Psychologist},
+  publisher = {American Psychological Association (APA)},
+  year = {2004},
+  month = jan,
+  volume = {59},
+  number = {1},
+  pages = {29--40},
+  issn = {0003-066X},
+  doi = {10.1037/0003-066x.59.1.29},
+  url = {http://dx.doi.org/10.1037/0003-066x.59.1.29}
+}
+"#,
+            work.to_bibtex()
+        );
+        let biblatex = work.to_biblatex();
+        assert!(biblatex.contains("journaltitle = {American Psychologist}"));
+        assert!(biblatex.contains("date = {2004-01}"));
+    }
+
+    #[test]
+    fn chapter_to_bibtex() {
+        let work_str = r#"{"DOI":"10.1007/978-3-319-_x#1","type":"book-chapter","is-referenced-by-count":0,"title":["The 100% Guide to R&D in   $HOME"],"author":[{"given":"José","family":"Núñez","suffix":"Jr."},{"name":"R Core Team"}],"editor":[{"given":"Ada","family":"Lovelace"},{"family":"Doe","suffix":"III"}],"container-title":["Handbook"],"issued":{"date-parts":[[null]]},"published-online":{"date-parts":[[2019,5,3]]},"ISBN":["978-3-319-00000-0"]}"#;
+        let work: Work = from_str(work_str).unwrap();
+
+        assert_eq!("nunez2019100", work.citation_key());
+        let bibtex = work.to_bibtex();
+        assert!(bibtex.starts_with("@incollection{nunez2019100,\n"));
+        assert!(bibtex.contains("author = {Núñez, Jr., José and {R Core Team}}"));
+        assert!(bibtex.contains("editor = {Lovelace, Ada and Doe, III,}"));
+        assert!(bibtex.contains("title = {The 100\\% Guide to R\\&D in \\$HOME}"));
+        assert!(bibtex.contains("booktitle = {Handbook}"));
+        assert!(bibtex.contains("month = may"));
+        assert!(bibtex.contains("doi = {10.1007/978-3-319-_x#1}"));
+        assert!(work.to_biblatex().contains("date = {2019-05-03}"));
+    }
+
+    #[test]
+    fn entry_types() {
+        assert_eq!(
+            "inproceedings",
+            entry_type("proceedings-article", Dialect::BibTex)
+        );
+        assert_eq!("phdthesis", entry_type("dissertation", Dialect::BibTex));
+        assert_eq!("thesis", entry_type("dissertation", Dialect::BibLatex));
+        assert_eq!("misc", entry_type("dataset", Dialect::BibTex));
+        assert_eq!("dataset", entry_type("dataset", Dialect::BibLatex));
+
+        let work: Work = from_str(r#"{"DOI":"10.5555/Some (Key)","is-referenced-by-count":0,"title":[],"issued":{"date-parts":[[2001]]}}"#).unwrap();
+        assert_eq!("10.5555/some__key_", work.citation_key());
+        assert!(work.to_bibtex().starts_with("@misc{10.5555/some__key_,\n"));
+    }
+}
@@ -0,0 +1,219 @@
+// the `<xml><records>` format EndNote exports and Zotero, Mendeley and EndNote import
+
+use crate::export::{contributors, date_notes, first, institution, iso_date, name};
+use crate::response::work::Work;
+
+/// the start of an EndNote XML document, followed by the records
+pub(crate) const HEADER: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<xml>\n<records>\n";
+
+/// the end of an EndNote XML document
+pub(crate) const FOOTER: &str = "</records>\n</xml>\n";
+
+impl Work {
+    /// Exports the work as an EndNote XML document with a single record.
+    ///
+    /// Use an `ExportWriter` to export multiple works into a single document.
+    pub fn to_endnote_xml(&self) -> String {
+        format!("{}{}{}", HEADER, record(self), FOOTER)
+    }
+}
+
+/// the EndNote reference type name and number for the crossref type of the work
+pub(crate) fn reference_type(type_: &str) -> (&'static str, u32) {
+    match type_ {
+        "journal-article" => ("Journal Article", 17),
+        "book-chapter" | "book-section" | "book-part" | "book-track" => ("Book Section", 5),
+        "reference-entry" => ("Encyclopedia", 53),
+        "proceedings-article" => ("Conference Paper", 47),
+        "proceedings" => ("Conference Proceedings", 10),
+        "book" | "monograph" | "reference-book" | "book-set" | "book-series" => ("Book", 6),
+        "edited-book" => ("Edited Book", 28),
+        "dissertation" => ("Thesis", 32),
+        "report" | "report-series" => ("Report", 27),
+        "dataset" => ("Dataset", 59),
+        "posted-content" => ("Manuscript", 36),
+        "standard" | "standard-series" => ("Standard", 58),
+        _ => ("Generic", 13),
+    }
+}
+
+/// renders a single `<record>` element
+pub(crate) fn record(work: &Work) -> String {
+    let mut xml = Xml::default();
+    let type_ = work.type_.as_deref().unwrap_or_default();
+    let (type_name, type_number) = reference_type(type_);
+
+    xml.open("record");
+    xml.0.push_str(&format!(
+        "<ref-type name=\"{}\">{}</ref-type>",
+        type_name, type_number
+    ));
+
+    xml.open("contributors");
+    let roles = [
+        ("authors", &work.author),
+        ("secondary-authors", &work.editor),
+        ("tertiary-authors", &work.chair),
+        ("translated-authors", &work.translator),
+    ];
+    for (role, people) in roles.iter() {
+        let names: Vec<_> = contributors(people).iter().filter_map(name).collect();
+        if !names.is_empty() {
+            xml.open(role);
+            for name in names {
+                xml.element("author", Some(&name));
+            }
+            xml.close(role);
+        }
+    }
+    xml.close("contributors");
+
+    xml.open("titles");
+    xml.element("title", work.title.first().map(String::as_str));
+    xml.element("secondary-title", first(&work.container_title));
+    xml.element("alt-title", first(&work.short_container_title));
+    xml.element("short-title", first(&work.short_title));
+    xml.close("titles");
+    if type_ == "journal-article" {
+        xml.open("periodical");
+        xml.element("full-title", first(&work.container_title));
+        xml.element("abbr-1", first(&work.short_container_title));
+        xml.close("periodical");
+    }
+
+    xml.element(
+        "pages",
+        work.page
+            .as_ref()
+            .or(work.article_number.as_ref())
+            .map(String::as_str),
+    );
+    xml.element("volume", work.volume.as_deref());
+    xml.element("number", work.issue.as_deref());
+
+    let subjects = work.subject.as_deref().unwrap_or_default();
+    if !subjects.is_empty() {
+        xml.open("keywords");
+        for subject in subjects {
+            xml.element("keyword", Some(subject));
+        }
+        xml.close("keywords");
+    }
+
+    if let Some((year, month, day)) = work.published().and_then(|date| date.ymd()) {
+        xml.open("dates");
+        xml.element("year", Some(&year.to_string()));
+        xml.open("pub-dates");
+        xml.element("date", Some(&iso_date(year, month, day)));
+        xml.close("pub-dates");
+        xml.close("dates");
+    }
+    // a record has a single publication date, the other dates are kept as notes like in RIS
+    xml.element("notes", Some(&date_notes(work).join("\n")));
+
+    let publisher = work.publisher.as_deref();
+    match type_ {
+        "dissertation" => xml.element("publisher", institution(work).or(publisher)),
+        _ => xml.element("publisher", publisher),
+    }
+    let numbers: Vec<_> = work.issn.iter().chain(work.isbn.iter()).flatten().collect();
+    if !numbers.is_empty() {
+        let numbers: Vec<_> = numbers.iter().map(|n| n.as_str()).collect();
+        xml.element("isbn", Some(&numbers.join("; ")));
+    }
+    xml.element("abstract", work.abstract_text().as_deref());
+    xml.element("electronic-resource-num", Some(&work.doi));
+    if let Some(url) = &work.url {
+        xml.open("urls");
+        xml.open("related-urls");
+        xml.element("url", Some(url));
+        xml.close("related-urls");
+        xml.close("urls");
+    }
+    xml.element("language", work.language.as_deref());
+    xml.close("record");
+    xml.0.push('\n');
+    xml.0
+}
+
+/// builds the elements of a record
+#[derive(Default)]
+struct Xml(String);
+
+impl Xml {
+    fn open(&mut self, name: &str) {
+        self.0.push_str(&format!("<{}>", name));
+    }
+
+    fn close(&mut self, name: &str) {
+        self.0.push_str(&format!("</{}>", name));
+    }
+
+    /// adds an element with escaped text content, skipped if there is no text
+    fn element(&mut self, name: &str, text: Option<&str>) {
+        if let Some(text) = text.map(str::trim).filter(|t| !t.is_empty()) {
+            self.open(name);
+            self.0.push_str(&escape(text));
+            self.close(name);
+        }
+    }
+}
+
+/// escapes the characters that have a special meaning in XML
+/// and drops the control characters XML 1.0 does not allow
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\t' | '\n' | '\r' => escaped.push(c),
+            '\u{0}'..='\u{1f}' | '\u{fffe}' | '\u{ffff}' => (),
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::*;
+
+    #[test]
+    fn thesis_to_endnote() {
+        let work_str = r#"{"DOI":"10.5555/thesis","type":"dissertation","is-referenced-by-count":0,"title":["Effects of <i>p</i> & q"],"author":[{"given":"Grace","family":"Hopper"}],"chair":[{"given":"Howard","family":"Aiken"}],"issued":{"date-parts":[[1934,6]]},"accepted":{"date-parts":[[1934,5,2]]},"institution":[{"name":"Yale University"}],"ISBN":["978-0-00-000000-2"]}"#;
+        let work: Work = from_str(work_str).unwrap();
+
+        let xml = work.to_endnote_xml();
+
+        assert!(xml.starts_with(HEADER));
+        assert!(xml.ends_with(FOOTER));
+        assert!(xml.contains("<ref-type name=\"Thesis\">32</ref-type>"));
+        assert!(xml.contains("<authors><author>Hopper, Grace</author></authors>"));
+        assert!(xml.contains("<tertiary-authors><author>Aiken, Howard</author></tertiary-authors>"));
+        assert!(xml.contains("<title>Effects of &lt;i&gt;p&lt;/i&gt; &amp; q</title>"));
+        assert!(xml.contains("<year>1934</year><pub-dates><date>1934-06</date></pub-dates>"));
+        assert!(xml.contains("<publisher>Yale University</publisher>"));
+        assert!(xml.contains("<isbn>978-0-00-000000-2</isbn>"));
+        assert!(xml.contains("<electronic-resource-num>10.5555/thesis</electronic-resource-num>"));
+        assert!(xml.contains("<notes>Accepted: 1934-05-02</notes>"));
+    }
+
+    #[test]
+    fn dates_and_invalid_characters() {
+        let work_str = r#"{"DOI":"10.5555/12345678","type":"journal-article","is-referenced-by-count":0,"title":["Toward \u0001a\u000bn \uffffunified\ttheory"],"issued":{"date-parts":[[2008,8,13]]},"published-print":{"date-parts":[[2008,9]]},"published-online":{"date-parts":[[2008,8,13]]},"posted":{"date-parts":[[null]]}}"#;
+        let work: Work = from_str(work_str).unwrap();
+
+        let xml = work.to_endnote_xml();
+
+        assert!(xml.contains("<title>Toward an unified\ttheory</title>"));
+        assert!(xml.contains("<year>2008</year><pub-dates><date>2008-08-13</date></pub-dates>"));
+        assert!(xml
+            .contains("<notes>Published in print: 2008-09\nPublished online: 2008-08-13</notes>"));
+        assert!(!xml.contains("Posted"));
+    }
+}
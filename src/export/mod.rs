@@ -0,0 +1,267 @@
+//! Offline export of `Work`s into the formats of reference managers,
+//! without a content negotiation round-trip per DOI.
+
+use crate::error::Result;
+use crate::response::work::{Contributor, Work};
+use serde_json::Value;
+use std::borrow::Borrow;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+mod bibtex;
+mod endnote;
+mod ris;
+
+/// The formats a `Work` can be exported to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// BibTeX entries, see `Work::to_bibtex`
+    BibTex,
+    /// BibLaTeX entries, see `Work::to_biblatex`
+    BibLatex,
+    /// RIS records, see `Work::to_ris`
+    Ris,
+    /// a single EndNote XML document, see `Work::to_endnote_xml`
+    EndNoteXml,
+}
+
+/// Writes multiple `Work`s as records of a single document.
+///
+/// Records are written as soon as they are passed to the writer,
+/// so whole deep paging result sets can be exported without holding them in memory.
+///
+/// # Example
+///
+/// ```edition2018
+/// use crossref::{Crossref, WorksQuery};
+/// use crossref::export::{ExportFormat, ExportWriter};
+/// # fn run() -> Result<(), crossref::Error> {
+/// let client = Crossref::builder().build()?;
+///
+/// let mut writer = ExportWriter::create("references.ris", ExportFormat::Ris)?;
+/// writer.write_all(client.deep_page(WorksQuery::new("Machine Learning")).into_work_iter())?;
+/// writer.finish()?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct ExportWriter<W: Write> {
+    /// where the records are written to
+    writer: W,
+    /// the format of the records
+    format: ExportFormat,
+    /// the number of records written so far
+    records: usize,
+}
+
+impl ExportWriter<BufWriter<File>> {
+    /// creates the file at `path` and writes all records into it
+    pub fn create<P: AsRef<Path>>(path: P, format: ExportFormat) -> Result<Self> {
+        Ok(ExportWriter::new(
+            BufWriter::new(File::create(path)?),
+            format,
+        ))
+    }
+}
+
+impl<W: Write> ExportWriter<W> {
+    /// creates a new writer for records in the `format`
+    pub fn new(writer: W, format: ExportFormat) -> Self {
+        ExportWriter {
+            writer,
+            format,
+            records: 0,
+        }
+    }
+
+    /// the number of records written so far
+    pub fn records(&self) -> usize {
+        self.records
+    }
+
+    /// writes a single record
+    pub fn write(&mut self, work: &Work) -> Result<()> {
+        if self.records == 0 {
+            self.header()?;
+        } else if self.format != ExportFormat::EndNoteXml {
+            self.writer.write_all(b"\n")?;
+        }
+        let record = match self.format {
+            ExportFormat::BibTex => work.to_bibtex(),
+            ExportFormat::BibLatex => work.to_biblatex(),
+            ExportFormat::Ris => work.to_ris(),
+            ExportFormat::EndNoteXml => endnote::record(work),
+        };
+        self.writer.write_all(record.as_bytes())?;
+        self.records += 1;
+        Ok(())
+    }
+
+    /// writes all works as records, like the `items` of a `WorkList` or the works of a deep page iterator
+    pub fn write_all<I>(&mut self, works: I) -> Result<usize>
+    where
+        I: IntoIterator,
+        I::Item: Borrow<Work>,
+    {
+        let mut written = 0;
+        for work in works {
+            self.write(work.borrow())?;
+            written += 1;
+        }
+        Ok(written)
+    }
+
+    /// completes the document and returns the underlying writer
+    pub fn finish(mut self) -> Result<W> {
+        if self.records == 0 {
+            self.header()?;
+        }
+        if self.format == ExportFormat::EndNoteXml {
+            self.writer.write_all(endnote::FOOTER.as_bytes())?;
+        }
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    /// the start of the document
+    fn header(&mut self) -> Result<()> {
+        if self.format == ExportFormat::EndNoteXml {
+            self.writer.write_all(endnote::HEADER.as_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+/// the first entry of an optional list, like the `container-title` of a `Work`
+fn first(values: &Option<Vec<String>>) -> Option<&str> {
+    values
+        .as_ref()?
+        .iter()
+        .map(|s| s.trim())
+        .find(|s| !s.is_empty())
+}
+
+/// the contributors of an optional list, empty if there are none
+fn contributors(contributors: &Option<Vec<Contributor>>) -> &[Contributor] {
+    contributors.as_ref().map(Vec::as_slice).unwrap_or_default()
+}
+
+/// the name of the first institution of the work, like the university of a dissertation
+fn institution(work: &Work) -> Option<&str> {
+    match work.extra.get("institution")? {
+        Value::Array(institutions) => institutions.first()?.get("name")?.as_str(),
+        institution => institution.get("name")?.as_str(),
+    }
+}
+
+/// the name of a person as `family, given, suffix` or the name of an organization
+fn name(contributor: &Contributor) -> Option<String> {
+    let parts: Vec<_> = [&contributor.family, &contributor.given, &contributor.suffix]
+        .iter()
+        .filter_map(|part| part.as_ref().map(|p| p.trim()))
+        .filter(|part| !part.is_empty())
+        .collect();
+    if contributor.family.is_none() {
+        if let Some(name) = &contributor.name {
+            return Some(name.trim().to_string());
+        }
+    }
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join(", "))
+    }
+}
+
+/// the print, online, posted and accepted dates of the work as notes like `Accepted: 2004-01-21`,
+/// for formats that only have a tag for the publication date
+fn date_notes(work: &Work) -> Vec<String> {
+    let dates = [
+        ("Published in print", &work.published_print),
+        ("Published online", &work.published_online),
+        ("Posted", &work.posted),
+        ("Accepted", &work.accepted),
+    ];
+    dates
+        .iter()
+        .filter_map(|(label, date)| {
+            let (year, month, day) = date.as_ref()?.ymd()?;
+            Some(format!("{}: {}", label, iso_date(year, month, day)))
+        })
+        .collect()
+}
+
+/// a date of the form `YYYY-MM-DD`, missing parts are omitted
+fn iso_date(year: i32, month: Option<u32>, day: Option<u32>) -> String {
+    let mut date = format!("{:04}", year);
+    for part in month.iter().chain(day.iter()) {
+        date.push_str(&format!("-{:02}", part));
+    }
+    date
+}
+
+/// lowercase ascii version of `s`, common diacritics are replaced by their base letter
+fn fold(s: &str) -> String {
+    let mut folded = String::with_capacity(s.len());
+    for c in s.chars().flat_map(char::to_lowercase) {
+        let replacement = match c {
+            'a'..='z' | '0'..='9' => {
+                folded.push(c);
+                continue;
+            }
+            'à'..='å' | 'ā' | 'ă' | 'ą' => "a",
+            'æ' => "ae",
+            'ç' | 'ć' | 'č' => "c",
+            'ď' | 'đ' | 'ð' => "d",
+            'è'..='ë' | 'ē' | 'ė' | 'ę' | 'ě' => "e",
+            'ğ' => "g",
+            'ì'..='ï' | 'ī' | 'ı' => "i",
+            'ł' | 'ľ' => "l",
+            'ñ' | 'ń' | 'ň' => "n",
+            'ò'..='ö' | 'ø' | 'ő' | 'ō' => "o",
+            'œ' => "oe",
+            'ř' => "r",
+            'ś' | 'š' | 'ş' => "s",
+            'ß' => "ss",
+            'ť' | 'ţ' => "t",
+            'þ' => "th",
+            'ù'..='ü' | 'ů' | 'ű' | 'ū' => "u",
+            'ý' | 'ÿ' => "y",
+            'ź' | 'ż' | 'ž' => "z",
+            _ => "",
+        };
+        folded.push_str(replacement);
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::*;
+
+    #[test]
+    fn export_writer() {
+        let works: Vec<Work> = from_str(r#"[{"DOI":"10.5555/1","type":"dataset","is-referenced-by-count":0,"title":["One"],"issued":{"date-parts":[[2019]]}},{"DOI":"10.5555/2","type":"book","is-referenced-by-count":0,"title":["Two"],"issued":{"date-parts":[[2020]]}}]"#).unwrap();
+
+        let mut writer = ExportWriter::new(Vec::new(), ExportFormat::Ris);
+        assert_eq!(2, writer.write_all(&works).unwrap());
+        let ris = String::from_utf8(writer.finish().unwrap()).unwrap();
+        assert_eq!(format!("{}\n{}", works[0].to_ris(), works[1].to_ris()), ris);
+        assert!(ris.starts_with("TY  - DATA\n"));
+
+        let mut writer = ExportWriter::new(Vec::new(), ExportFormat::EndNoteXml);
+        writer.write_all(works.iter()).unwrap();
+        assert_eq!(2, writer.records());
+        let xml = String::from_utf8(writer.finish().unwrap()).unwrap();
+        assert_eq!(2, xml.matches("<record>").count());
+        assert_eq!(1, xml.matches("<records>").count());
+        assert!(xml.ends_with("</records>\n</xml>\n"));
+
+        let empty = ExportWriter::new(Vec::new(), ExportFormat::EndNoteXml);
+        assert_eq!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<xml>\n<records>\n</records>\n</xml>\n",
+            String::from_utf8(empty.finish().unwrap()).unwrap()
+        );
+    }
+}
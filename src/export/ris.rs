@@ -0,0 +1,183 @@
+// see https://en.wikipedia.org/wiki/RIS_(file_format)
+
+use crate::export::{contributors, date_notes, first, institution, name};
+use crate::response::work::Work;
+
+impl Work {
+    /// Exports the work as a single [RIS](https://en.wikipedia.org/wiki/RIS_(file_format)) record.
+    ///
+    /// The reference type is derived from the type of the work,
+    /// `journal-article` becomes `JOUR`, `book-chapter` becomes `CHAP` and so on.
+    ///
+    /// # Example
+    ///
+    /// ```edition2018
+    /// use crossref::Crossref;
+    /// # fn run() -> Result<(), crossref::Error> {
+    /// let client = Crossref::builder().build()?;
+    ///
+    /// let work = client.work("10.1037/0003-066x.59.1.29")?;
+    /// print!("{}", work.to_ris());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_ris(&self) -> String {
+        let mut record = Record::default();
+        let type_ = self.type_.as_deref().unwrap_or_default();
+        record.tag("TY", Some(reference_type(type_)));
+
+        let roles = [
+            ("AU", &self.author),
+            ("A2", &self.editor),
+            ("A3", &self.chair),
+            ("A4", &self.translator),
+        ];
+        for (tag, role) in roles.iter() {
+            for contributor in contributors(role) {
+                record.tag(tag, name(contributor).as_deref());
+            }
+        }
+
+        // RIS has no subtitle tag, `ST` is the short title
+        let title = match (self.title.first(), first(&self.subtitle)) {
+            (Some(title), Some(subtitle)) => {
+                Some(format!("{}: {}", title.trim_end_matches('.'), subtitle))
+            }
+            (title, _) => title.cloned(),
+        };
+        record.tag("TI", title.as_deref());
+        record.tag("ST", first(&self.short_title));
+        record.tag("T2", first(&self.container_title));
+        record.tag("J2", first(&self.short_container_title));
+
+        if let Some((year, month, day)) = self.published().and_then(|date| date.ymd()) {
+            record.tag("PY", Some(&year.to_string()));
+            record.tag("DA", Some(&date(year, month, day)));
+        }
+        // RIS has no tags for the other dates, they are kept as notes
+        for note in date_notes(self) {
+            record.tag("N1", Some(&note));
+        }
+
+        record.tag("VL", self.volume.as_deref());
+        record.tag("IS", self.issue.as_deref());
+        if let Some(page) = &self.page {
+            let mut range = page.splitn(2, ['-', '\u{2013}']);
+            record.tag("SP", range.next());
+            record.tag("EP", range.next().map(|end| end.trim_start_matches('-')));
+        } else {
+            record.tag("SP", self.article_number.as_deref());
+        }
+        for number in self.issn.iter().chain(self.isbn.iter()).flatten() {
+            record.tag("SN", Some(number));
+        }
+
+        let publisher = self.publisher.as_deref();
+        match type_ {
+            "dissertation" => record.tag("PB", institution(self).or(publisher)),
+            _ => record.tag("PB", publisher),
+        }
+        record.tag("DO", Some(&self.doi));
+        record.tag("UR", self.url.as_deref());
+        record.tag("AB", self.abstract_text().as_deref());
+        record.tag("LA", self.language.as_deref());
+        for subject in self.subject.iter().flatten() {
+            record.tag("KW", Some(subject));
+        }
+        record.tag("ER", Some(""));
+        record.0
+    }
+}
+
+/// the RIS reference type for the crossref type of the work
+pub(crate) fn reference_type(type_: &str) -> &'static str {
+    match type_ {
+        "journal-article" => "JOUR",
+        "journal" | "journal-issue" | "journal-volume" => "JFULL",
+        "book-chapter" | "book-section" | "book-part" | "book-track" => "CHAP",
+        "reference-entry" => "ENCYC",
+        "proceedings-article" | "proceedings" => "CONF",
+        "book" | "monograph" | "reference-book" | "book-set" | "book-series" => "BOOK",
+        "edited-book" => "EDBOOK",
+        "dissertation" => "THES",
+        "report" | "report-series" => "RPRT",
+        "dataset" => "DATA",
+        "posted-content" => "UNPB",
+        "standard" | "standard-series" => "STAND",
+        _ => "GEN",
+    }
+}
+
+/// a date of the form `YYYY/MM/DD/`, missing parts are left empty
+fn date(year: i32, month: Option<u32>, day: Option<u32>) -> String {
+    let part = |p: Option<u32>| p.map(|p| format!("{:02}", p)).unwrap_or_default();
+    format!("{:04}/{}/{}/", year, part(month), part(day))
+}
+
+/// the lines of a record
+#[derive(Default)]
+struct Record(String);
+
+impl Record {
+    /// adds a `TAG  - value` line, values are joined into a single line
+    fn tag(&mut self, tag: &str, value: Option<&str>) {
+        let value = match value {
+            Some(value) => value.split_whitespace().collect::<Vec<_>>().join(" "),
+            None => return,
+        };
+        if value.is_empty() && tag != "ER" {
+            return;
+        }
+        self.0.push_str(tag);
+        self.0.push_str("  - ");
+        self.0.push_str(&value);
+        self.0.push('\n');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::*;
+
+    #[test]
+    fn article_to_ris() {
+        let work_str = r#"{"publisher":"American Psychological Association (APA)","issue":"1","DOI":"10.1037/0003-066x.59.1.29","type":"journal-article","page":"29-40","is-referenced-by-count":84,"title":["How the Mind Hurts and Heals the Body."],"subtitle":["A Review"],"short-title":["Mind and Body"],"volume":"59","author":[{"given":"Oakley","family":"Ray"},{"name":"APA Task Force"}],"editor":[{"given":"Ada","family":"Lovelace","suffix":"Jr."}],"container-title":["American Psychologist"],"issued":{"date-parts":[[2004,1]]},"published-online":{"date-parts":[[2004,1,21]]},"URL":"http://dx.doi.org/10.1037/0003-066x.59.1.29","ISSN":["0003-066X","1935-990X"],"abstract":"<jats:p>Mind\n and <jats:italic>body</jats:italic>.</jats:p>","subject":["Psychology"]}"#;
+        let work: Work = from_str(work_str).unwrap();
+
+        assert_eq!(
+            "TY  - JOUR
+AU  - Ray, Oakley
+AU  - APA Task Force
+A2  - Lovelace, Ada, Jr.
+TI  - How the Mind Hurts and Heals the Body: A Review
+ST  - Mind and Body
+T2  - American Psychologist
+PY  - 2004
+DA  - 2004/01//
+N1  - Published online: 2004-01-21
+VL  - 59
+IS  - 1
+SP  - 29
+EP  - 40
+SN  - 0003-066X
+SN  - 1935-990X
+PB  - American Psychological Association (APA)
+DO  - 10.1037/0003-066x.59.1.29
+UR  - http://dx.doi.org/10.1037/0003-066x.59.1.29
+AB  - Mind and body.
+KW  - Psychology
+ER  - \n",
+            work.to_ris()
+        );
+    }
+
+    #[test]
+    fn reference_types() {
+        assert_eq!("CHAP", reference_type("book-chapter"));
+        assert_eq!("CONF", reference_type("proceedings-article"));
+        assert_eq!("THES", reference_type("dissertation"));
+        assert_eq!("DATA", reference_type("dataset"));
+        assert_eq!("GEN", reference_type("peer-review"));
+    }
+}
@@ -0,0 +1,813 @@
+// see https://jats.nlm.nih.gov/archiving/tag-library/1.2/ for the elements of JATS abstracts
+
+use crate::response::work::Work;
+
+/// The formats a JATS abstract can be rendered to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// plain text, paragraphs are separated by blank lines
+    Text,
+    /// CommonMark, sub- and superscripts are kept as inline html
+    Markdown,
+    /// html that only contains a small set of harmless elements
+    Html,
+}
+
+/// Renders JATS markup as plain text.
+///
+/// # Example
+///
+/// ```edition2018
+/// use crossref::jats;
+///
+/// let text = jats::to_text("<jats:p>H<jats:sub>2</jats:sub>O is <jats:italic>wet</jats:italic>.</jats:p>");
+/// assert_eq!("H₂O is wet.", text);
+/// ```
+pub fn to_text(markup: &str) -> String {
+    render(markup, Format::Text)
+}
+
+/// Renders JATS markup as Markdown.
+pub fn to_markdown(markup: &str) -> String {
+    render(markup, Format::Markdown)
+}
+
+/// Renders JATS markup as sanitized html.
+///
+/// The output only contains paragraphs, headings, lists, emphasis, sub- and superscripts,
+/// code, line breaks, math spans and links with `http`, `https` or `mailto` targets.
+pub fn to_html(markup: &str) -> String {
+    render(markup, Format::Html)
+}
+
+/// Renders JATS markup in the `format`.
+///
+/// Malformed markup is rendered on a best effort basis, unclosed elements are closed at the end
+/// and stray closing tags are ignored.
+pub fn render(markup: &str, format: Format) -> String {
+    let nodes = parse(markup);
+    let mut renderer = Renderer {
+        format,
+        out: String::with_capacity(markup.len()),
+        sections: 0,
+        lists: Vec::new(),
+        in_item: 0,
+    };
+    renderer.nodes(&nodes);
+    renderer.finish()
+}
+
+impl Work {
+    /// the abstract as plain text
+    pub fn abstract_text(&self) -> Option<String> {
+        self.abstract_.as_ref().map(|a| to_text(a))
+    }
+
+    /// the abstract as Markdown
+    pub fn abstract_markdown(&self) -> Option<String> {
+        self.abstract_.as_ref().map(|a| to_markdown(a))
+    }
+
+    /// the abstract as sanitized html
+    pub fn abstract_html(&self) -> Option<String> {
+        self.abstract_.as_ref().map(|a| to_html(a))
+    }
+}
+
+/// a parsed piece of markup
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Element {
+        /// the name without namespace prefix
+        name: String,
+        attributes: Vec<(String, String)>,
+        children: Vec<Node>,
+    },
+    Text(String),
+}
+
+impl Node {
+    fn attribute(&self, key: &str) -> Option<&str> {
+        match self {
+            Node::Element { attributes, .. } => attributes
+                .iter()
+                .find(|(k, _)| k == key || k.rsplit(':').next() == Some(key))
+                .map(|(_, v)| v.as_str()),
+            Node::Text(_) => None,
+        }
+    }
+
+    /// the text of the node and all its descendants
+    fn text(&self) -> String {
+        match self {
+            Node::Text(text) => text.clone(),
+            Node::Element { children, .. } => children.iter().map(Node::text).collect(),
+        }
+    }
+}
+
+/// how deep elements can be nested, deeper elements are dropped and only their text is kept
+///
+/// The nodes are rendered recursively, this keeps deeply nested markup from overflowing the stack.
+const MAX_DEPTH: usize = 64;
+
+/// an element that is not closed yet, with its name, attributes and the children so far
+type OpenElement = (String, Vec<(String, String)>, Vec<Node>);
+
+/// builds the tree of nodes, never fails
+fn parse(markup: &str) -> Vec<Node> {
+    // elements that are currently open, with their children so far
+    let mut stack: Vec<OpenElement> = Vec::new();
+    let mut root = Vec::new();
+    // elements opened beyond `MAX_DEPTH` that are not closed yet
+    let mut dropped = 0;
+
+    fn close(stack: &mut Vec<OpenElement>, root: &mut Vec<Node>) {
+        if let Some((name, attributes, children)) = stack.pop() {
+            let node = Node::Element {
+                name,
+                attributes,
+                children,
+            };
+            match stack.last_mut() {
+                Some((_, _, parent)) => parent.push(node),
+                None => root.push(node),
+            }
+        }
+    }
+
+    let mut rest = markup;
+    while !rest.is_empty() {
+        if rest.starts_with("<![CDATA[") {
+            let end = rest.find("]]>").unwrap_or(rest.len());
+            let cdata = &rest[9..end.max(9)];
+            let nodes = match stack.last_mut() {
+                Some((_, _, children)) => children,
+                None => &mut root,
+            };
+            nodes.push(Node::Text(cdata.to_string()));
+            rest = &rest[(end + 3).min(rest.len())..];
+            continue;
+        }
+        let tag = match rest.find('<') {
+            Some(0) => Tag::parse(rest),
+            Some(start) => {
+                push_text(&mut stack, &mut root, &rest[..start]);
+                rest = &rest[start..];
+                continue;
+            }
+            None => {
+                push_text(&mut stack, &mut root, rest);
+                break;
+            }
+        };
+        let (tag, len) = match tag {
+            Some(tag) => tag,
+            None => {
+                // a `<` that does not start a tag
+                push_text(&mut stack, &mut root, "<");
+                rest = &rest[1..];
+                continue;
+            }
+        };
+        rest = &rest[len..];
+        match tag {
+            Tag::Open {
+                name,
+                attributes,
+                self_closing,
+            } => {
+                if stack.len() >= MAX_DEPTH {
+                    if !self_closing {
+                        dropped += 1;
+                    }
+                    continue;
+                }
+                stack.push((name, attributes, Vec::new()));
+                if self_closing {
+                    close(&mut stack, &mut root);
+                }
+            }
+            Tag::Close(_) if dropped > 0 => dropped -= 1,
+            Tag::Close(name) => {
+                // stray closing tags are ignored, unclosed children are closed
+                if let Some(open) = stack.iter().rposition(|(n, _, _)| *n == name) {
+                    while stack.len() > open {
+                        close(&mut stack, &mut root);
+                    }
+                }
+            }
+            Tag::Other => (),
+        }
+    }
+    while !stack.is_empty() {
+        close(&mut stack, &mut root);
+    }
+    root
+}
+
+/// adds decoded text to the innermost open element
+fn push_text(stack: &mut [OpenElement], root: &mut Vec<Node>, text: &str) {
+    let text = decode(text);
+    let nodes = match stack.last_mut() {
+        Some((_, _, children)) => children,
+        None => root,
+    };
+    match nodes.last_mut() {
+        Some(Node::Text(previous)) => previous.push_str(&text),
+        _ => nodes.push(Node::Text(text)),
+    }
+}
+
+/// a single tag of the markup
+enum Tag {
+    Open {
+        name: String,
+        attributes: Vec<(String, String)>,
+        self_closing: bool,
+    },
+    Close(String),
+    /// comments, processing instructions and doctypes
+    Other,
+}
+
+impl Tag {
+    /// parses the tag at the start of `s`, returns the tag and its length in bytes
+    fn parse(s: &str) -> Option<(Tag, usize)> {
+        for (start, end) in &[("<!--", "-->"), ("<?", "?>"), ("<!", ">")] {
+            if s.starts_with(start) {
+                let len = s.find(end).map(|i| i + end.len()).unwrap_or(s.len());
+                return Some((Tag::Other, len));
+            }
+        }
+        let end = s.find('>')?;
+        let inner = &s[1..end];
+        let closing = inner.starts_with('/');
+        let inner = inner.trim_start_matches('/');
+        let self_closing = inner.ends_with('/');
+        let inner = inner.trim_end_matches('/');
+        let name_end = inner
+            .find(|c: char| c.is_whitespace())
+            .unwrap_or(inner.len());
+        let name = &inner[..name_end];
+        let valid_name = name
+            .chars()
+            .next()
+            .map(|c| c.is_alphabetic())
+            .unwrap_or_default()
+            && name
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == ':' || c == '-' || c == '_' || c == '.');
+        if !valid_name {
+            return None;
+        }
+        // drop the namespace prefix like `jats:` or `mml:`
+        let name = name.rsplit(':').next().unwrap_or(name).to_lowercase();
+        let tag = if closing {
+            Tag::Close(name)
+        } else {
+            Tag::Open {
+                name,
+                attributes: attributes(&inner[name_end..]),
+                self_closing,
+            }
+        };
+        Some((tag, end + 1))
+    }
+}
+
+/// parses `key="value"` pairs, values without quotes are accepted
+fn attributes(s: &str) -> Vec<(String, String)> {
+    let mut attributes = Vec::new();
+    let mut rest = s.trim_start();
+    while let Some(eq) = rest.find('=') {
+        let key = rest[..eq].trim().to_string();
+        let value = rest[eq + 1..].trim_start();
+        let (value, remaining) = match value.chars().next() {
+            Some(quote) if quote == '"' || quote == '\'' => match value[1..].find(quote) {
+                Some(end) => (&value[1..=end], &value[end + 2..]),
+                None => (&value[1..], ""),
+            },
+            _ => {
+                let end = value.find(char::is_whitespace).unwrap_or(value.len());
+                (&value[..end], &value[end..])
+            }
+        };
+        attributes.push((key, decode(value)));
+        rest = remaining.trim_start();
+    }
+    attributes
+}
+
+/// decodes the predefined and numeric character references, unknown references are kept
+fn decode(s: &str) -> String {
+    let mut decoded = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let end = match rest.find(';') {
+            Some(end) if end <= 10 => end,
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+                continue;
+            }
+        };
+        let entity = &rest[1..end];
+        let c = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some('\u{a0}'),
+            _ if entity.starts_with("#x") || entity.starts_with("#X") => {
+                u32::from_str_radix(&entity[2..], 16)
+                    .ok()
+                    .and_then(std::char::from_u32)
+            }
+            _ if entity.starts_with('#') => entity[1..].parse().ok().and_then(std::char::from_u32),
+            _ => None,
+        };
+        match c {
+            Some(c) => {
+                decoded.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+/// writes the nodes in a `Format`
+struct Renderer {
+    format: Format,
+    out: String,
+    /// how deep the current section is nested
+    sections: usize,
+    /// the open lists, `Some(n)` for ordered lists at item `n`
+    lists: Vec<Option<usize>>,
+    /// how many list items are open
+    in_item: usize,
+}
+
+impl Renderer {
+    fn nodes(&mut self, nodes: &[Node]) {
+        for node in nodes {
+            self.node(node);
+        }
+    }
+
+    fn node(&mut self, node: &Node) {
+        let (name, children) = match node {
+            Node::Text(text) => return self.text(text),
+            Node::Element { name, children, .. } => (name.as_str(), children),
+        };
+        match name {
+            "p" if self.in_item > 0 => {
+                self.space();
+                self.nodes(children);
+            }
+            "p" => self.block("p", children),
+            "sec" | "abstract" | "trans-abstract" => {
+                if self.format == Format::Html && name == "sec" {
+                    self.block_break();
+                    self.out.push_str("<section>");
+                }
+                self.sections += 1;
+                self.nodes(children);
+                self.sections -= 1;
+                if self.format == Format::Html && name == "sec" {
+                    self.out.push_str("</section>");
+                }
+            }
+            "title" => {
+                let level = (self.sections + 1).clamp(2, 6);
+                match self.format {
+                    Format::Markdown => {
+                        self.block_break();
+                        self.out.push_str(&"#".repeat(level));
+                        self.out.push(' ');
+                        self.nodes(children);
+                        self.block_break();
+                    }
+                    _ => self.block(&format!("h{}", level), children),
+                }
+            }
+            "list" => self.list(node, children),
+            "list-item" | "li" => self.list_item(children),
+            "italic" | "i" | "em" => self.inline(children, "*", "em"),
+            "bold" | "b" | "strong" => self.inline(children, "**", "strong"),
+            "monospace" | "code" | "tt" => self.inline(children, "`", "code"),
+            "sub" => self.script(children, "sub"),
+            "sup" => self.script(children, "sup"),
+            "break" | "br" => match self.format {
+                Format::Text => self.out.push('\n'),
+                Format::Markdown => self.out.push_str("  \n"),
+                Format::Html => self.out.push_str("<br>"),
+            },
+            "alternatives" => {
+                // prefer the TeX version of a formula, then MathML
+                let preferred = ["tex-math", "math"].iter().find_map(|preferred| {
+                    children.iter().find(|child| match child {
+                        Node::Element { name, .. } => name == preferred,
+                        Node::Text(_) => false,
+                    })
+                });
+                match preferred {
+                    Some(child) => self.node(child),
+                    None => self.nodes(children),
+                }
+            }
+            "tex-math" => self.math(&node.text(), true),
+            "math" => self.math(&node.text(), false),
+            "disp-formula" => {
+                self.block_break();
+                self.nodes(children);
+                self.block_break();
+            }
+            "ext-link" | "uri" | "a" => self.link(node, children),
+            "script" | "style" => (),
+            _ => self.nodes(children),
+        }
+    }
+
+    /// appends text with collapsed whitespace
+    fn text(&mut self, text: &str) {
+        let mut chars = text.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c.is_whitespace() {
+                while chars.peek().map(|c| c.is_whitespace()).unwrap_or_default() {
+                    chars.next();
+                }
+                self.space();
+                continue;
+            }
+            match (self.format, c) {
+                (Format::Html, '&') => self.out.push_str("&amp;"),
+                (Format::Html, '<') => self.out.push_str("&lt;"),
+                (Format::Html, '>') => self.out.push_str("&gt;"),
+                (Format::Html, '"') => self.out.push_str("&quot;"),
+                (Format::Markdown, '\\')
+                | (Format::Markdown, '*')
+                | (Format::Markdown, '_')
+                | (Format::Markdown, '`')
+                | (Format::Markdown, '[')
+                | (Format::Markdown, ']')
+                | (Format::Markdown, '<') => {
+                    self.out.push('\\');
+                    self.out.push(c);
+                }
+                (_, c) => self.out.push(c),
+            }
+        }
+    }
+
+    /// a single space, unless at the start of a line
+    fn space(&mut self) {
+        match self.out.chars().last() {
+            None | Some(' ') | Some('\n') => (),
+            Some('>') if self.format == Format::Html && self.at_block_start() => (),
+            _ => self.out.push(' '),
+        }
+    }
+
+    /// whether the html output is right after the opening tag of a block
+    fn at_block_start(&self) -> bool {
+        let last_tag = match self.out.rsplit('<').next() {
+            Some(tag) if self.out.ends_with('>') => {
+                tag.trim_start_matches('/').trim_end_matches('>')
+            }
+            _ => return false,
+        };
+        [
+            "p", "li", "section", "ul", "ol", "h2", "h3", "h4", "h5", "h6",
+        ]
+        .contains(&last_tag)
+    }
+
+    /// ends the current line of text and inserts an empty line
+    fn block_break(&mut self) {
+        if self.format == Format::Html {
+            return;
+        }
+        while self.out.ends_with(' ') {
+            self.out.pop();
+        }
+        if self.out.is_empty() {
+            return;
+        }
+        while !self.out.ends_with("\n\n") {
+            self.out.push('\n');
+        }
+    }
+
+    /// a paragraph or heading
+    fn block(&mut self, tag: &str, children: &[Node]) {
+        self.block_break();
+        if self.format == Format::Html {
+            self.out.push_str(&format!("<{}>", tag));
+        }
+        self.nodes(children);
+        if self.format == Format::Html {
+            self.trim_end();
+            self.out.push_str(&format!("</{}>", tag));
+        }
+        self.block_break();
+    }
+
+    fn list(&mut self, node: &Node, children: &[Node]) {
+        let ordered = node.attribute("list-type") == Some("order")
+            || node.attribute("list-type") == Some("ordered");
+        if self.in_item > 0 {
+            self.line_break();
+        } else {
+            self.block_break();
+        }
+        if self.format == Format::Html {
+            self.out.push_str(if ordered { "<ol>" } else { "<ul>" });
+        }
+        self.lists.push(if ordered { Some(0) } else { None });
+        self.nodes(children);
+        self.lists.pop();
+        if self.format == Format::Html {
+            self.out.push_str(if ordered { "</ol>" } else { "</ul>" });
+        } else if self.in_item == 0 {
+            self.block_break();
+        }
+    }
+
+    fn list_item(&mut self, children: &[Node]) {
+        if self.format == Format::Html {
+            self.out.push_str("<li>");
+        } else {
+            self.line_break();
+            let depth = self.lists.len().max(1) - 1;
+            self.out.push_str(&"  ".repeat(depth));
+            match self.lists.last_mut() {
+                Some(Some(n)) => {
+                    *n += 1;
+                    let marker = format!("{}. ", n);
+                    self.out.push_str(&marker);
+                }
+                _ => self.out.push_str("- "),
+            }
+        }
+        self.in_item += 1;
+        self.nodes(children);
+        self.in_item -= 1;
+        if self.format == Format::Html {
+            self.trim_end();
+            self.out.push_str("</li>");
+        }
+    }
+
+    /// starts a new line unless already at the start of one
+    fn line_break(&mut self) {
+        self.trim_end();
+        if !self.out.is_empty() && !self.out.ends_with('\n') {
+            self.out.push('\n');
+        }
+    }
+
+    fn trim_end(&mut self) {
+        while self.out.ends_with(' ') {
+            self.out.pop();
+        }
+    }
+
+    /// emphasis, strong and code
+    fn inline(&mut self, children: &[Node], markdown: &str, html: &str) {
+        match self.format {
+            Format::Text => self.nodes(children),
+            Format::Markdown => {
+                self.out.push_str(markdown);
+                self.nodes(children);
+                self.trim_end();
+                self.out.push_str(markdown);
+            }
+            Format::Html => {
+                self.out.push_str(&format!("<{}>", html));
+                self.nodes(children);
+                self.out.push_str(&format!("</{}>", html));
+            }
+        }
+    }
+
+    /// sub- and superscripts, plain text uses the unicode forms if available
+    fn script(&mut self, children: &[Node], tag: &str) {
+        if self.format != Format::Text {
+            self.out.push_str(&format!("<{}>", tag));
+            self.nodes(children);
+            self.out.push_str(&format!("</{}>", tag));
+            return;
+        }
+        let text: String = children.iter().map(Node::text).collect();
+        let text = text.trim();
+        let mapped: Option<String> = text
+            .chars()
+            .map(|c| {
+                if tag == "sub" {
+                    subscript(c)
+                } else {
+                    superscript(c)
+                }
+            })
+            .collect();
+        match mapped {
+            Some(mapped) => self.out.push_str(&mapped),
+            None => {
+                self.out.push(if tag == "sub" { '_' } else { '^' });
+                if text.chars().count() == 1 {
+                    self.out.push_str(text);
+                } else {
+                    self.out.push_str(&format!("({})", text));
+                }
+            }
+        }
+    }
+
+    fn math(&mut self, math: &str, tex: bool) {
+        let math = math
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(if tex { " " } else { "" });
+        match self.format {
+            Format::Text => self.out.push_str(&math),
+            Format::Markdown if tex => self.out.push_str(&format!("${}$", math)),
+            Format::Markdown => self.out.push_str(&math),
+            Format::Html => {
+                let class = if tex { "tex-math" } else { "math" };
+                let (open, close) = if tex { ("\\(", "\\)") } else { ("", "") };
+                self.out
+                    .push_str(&format!("<span class=\"{}\">{}", class, open));
+                self.text(&math);
+                self.out.push_str(&format!("{}</span>", close));
+            }
+        }
+    }
+
+    fn link(&mut self, node: &Node, children: &[Node]) {
+        let href = node.attribute("href").map(str::trim).filter(|href| {
+            let lower = href.to_lowercase();
+            ["http://", "https://", "mailto:"]
+                .iter()
+                .any(|scheme| lower.starts_with(scheme))
+        });
+        let href = match href {
+            Some(href) => href.to_string(),
+            None => return self.nodes(children),
+        };
+        match self.format {
+            Format::Text => {
+                self.nodes(children);
+                if node.text().trim() != href {
+                    self.space();
+                    self.out.push_str(&format!("({})", href));
+                }
+            }
+            Format::Markdown => {
+                self.out.push('[');
+                self.nodes(children);
+                self.trim_end();
+                self.out
+                    .push_str(&format!("]({})", href.replace(')', "%29")));
+            }
+            Format::Html => {
+                self.out.push_str("<a href=\"");
+                self.out
+                    .push_str(&href.replace('&', "&amp;").replace('"', "&quot;"));
+                self.out.push_str("\">");
+                self.nodes(children);
+                self.out.push_str("</a>");
+            }
+        }
+    }
+
+    fn finish(mut self) -> String {
+        let trimmed = self.out.trim_end().len();
+        self.out.truncate(trimmed);
+        self.out
+    }
+}
+
+fn subscript(c: char) -> Option<char> {
+    Some(match c {
+        '0'..='9' => std::char::from_u32('₀' as u32 + c.to_digit(10)?)?,
+        '+' => '₊',
+        '-' | '−' => '₋',
+        '=' => '₌',
+        '(' => '₍',
+        ')' => '₎',
+        'a' => 'ₐ',
+        'e' => 'ₑ',
+        'i' => 'ᵢ',
+        'n' => 'ₙ',
+        'x' => 'ₓ',
+        _ => return None,
+    })
+}
+
+fn superscript(c: char) -> Option<char> {
+    Some(match c {
+        '0' => '⁰',
+        '1' => '¹',
+        '2' => '²',
+        '3' => '³',
+        '4'..='9' => std::char::from_u32('⁴' as u32 + c.to_digit(10)? - 4)?,
+        '+' => '⁺',
+        '-' | '−' => '⁻',
+        '=' => '⁼',
+        '(' => '⁽',
+        ')' => '⁾',
+        'i' => 'ⁱ',
+        'n' => 'ⁿ',
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ABSTRACT: &str = r#"<jats:sec>
+    <jats:title>Background</jats:title>
+    <jats:p>CO<jats:sub>2</jats:sub> levels rose by 10<jats:sup>3</jats:sup> ppm in <jats:italic>vitro</jats:italic> &amp; <jats:bold>vivo</jats:bold>.</jats:p>
+</jats:sec>
+<jats:sec>
+    <jats:title>Results</jats:title>
+    <jats:list list-type="order">
+        <jats:list-item><jats:p>first</jats:p></jats:list-item>
+        <jats:list-item><jats:p>second</jats:p></jats:list-item>
+    </jats:list>
+    <jats:p>With <jats:inline-formula><jats:alternatives><mml:math><mml:mi>x</mml:mi></mml:math><jats:tex-math>x^2</jats:tex-math></jats:alternatives></jats:inline-formula> see <jats:ext-link xlink:href="https://example.org">here</jats:ext-link>.</jats:p>
+</jats:sec>"#;
+
+    #[test]
+    fn abstract_to_text() {
+        assert_eq!(
+            "Background\n\nCO₂ levels rose by 10³ ppm in vitro & vivo.\n\nResults\n\n1. first\n2. second\n\nWith x^2 see here (https://example.org).",
+            to_text(ABSTRACT)
+        );
+        assert_eq!("H_(2b)O", to_text("H<sub>2b</sub>O"));
+    }
+
+    #[test]
+    fn abstract_to_markdown() {
+        assert_eq!(
+            "## Background\n\nCO<sub>2</sub> levels rose by 10<sup>3</sup> ppm in *vitro* & **vivo**.\n\n## Results\n\n1. first\n2. second\n\nWith $x^2$ see [here](https://example.org).",
+            to_markdown(ABSTRACT)
+        );
+    }
+
+    #[test]
+    fn abstract_to_html() {
+        assert_eq!(
+            "<section><h2>Background</h2><p>CO<sub>2</sub> levels rose by 10<sup>3</sup> ppm in <em>vitro</em> &amp; <strong>vivo</strong>.</p></section><section><h2>Results</h2><ol><li>first</li><li>second</li></ol><p>With <span class=\"tex-math\">\\(x^2\\)</span> see <a href=\"https://example.org\">here</a>.</p></section>",
+            to_html(ABSTRACT)
+        );
+        assert_eq!(
+            "<p>a &lt;b&gt; c</p>",
+            to_html("<p onclick=\"x\">a &lt;b&gt;<script>alert(1)</script> <a href=\"javascript:x\">c</a></p>")
+        );
+    }
+
+    #[test]
+    fn malformed_markup() {
+        assert_eq!("a < b", to_text("a < b"));
+        assert_eq!("unclosed bold", to_text("<p>unclosed <bold>bold"));
+        assert_eq!("stray &bogus; end", to_text("stray &bogus;</i> end"));
+        assert_eq!("x <\n\ny", to_text("<p>x <</p><![CDATA[y]]>"));
+        for markup in &[
+            "<",
+            "</",
+            "<p",
+            "&#xZZ;",
+            "<a href='",
+            "<list><list-item>",
+            "&#99999999;",
+        ] {
+            to_text(markup);
+            to_markdown(markup);
+            to_html(markup);
+        }
+    }
+
+    #[test]
+    fn deeply_nested_markup() {
+        let markup = format!("{}deep", "<jats:italic>".repeat(10000));
+        assert_eq!("deep", to_text(&markup));
+        let html = to_html(&format!("<p>{}</p>", markup));
+        assert_eq!(MAX_DEPTH - 1, html.matches("<em>").count());
+        assert_eq!(MAX_DEPTH - 1, html.matches("</em>").count());
+        assert!(html.contains("<em>deep</em>"));
+        let closed = format!("{}{}after", markup, "</jats:italic>".repeat(10000));
+        assert_eq!("deepafter", to_text(&closed));
+    }
+}
@@ -253,9 +253,26 @@ pub mod response;
 // TODO extract to optional feature?
 /// content negotiation
 pub mod cn;
+/// provides the CSL-JSON item type and its conversion from and to `Work`
+pub mod csl;
+/// normalizes DOIs as they are found in spreadsheets and references
+pub mod doi;
+/// offline export into reference manager formats
+pub mod export;
+/// renders JATS markup of abstracts as plain text, Markdown or html
+pub mod jats;
 /// textual data mining
 pub mod tdm;
+#[cfg(test)]
+mod test_util;
+/// reads unixref and unixsd xml records
+#[cfg(feature = "unixref")]
+pub mod unixref;
 
+#[doc(inline)]
+pub use self::cn::{CitationStyle, CnFormat};
+#[doc(inline)]
+pub use self::csl::CslItem;
 #[doc(inline)]
 pub use self::error::{Error, Result};
 
@@ -268,37 +285,36 @@ pub use self::query::works::{
 pub use self::query::{Component, CrossrefQuery, CrossrefRoute, Order, Sort};
 pub use self::query::{Funders, Journals, Members, Prefixes, Type, Types};
 pub use self::response::{
-    CrossrefType, Funder, FunderList, Journal, JournalList, Member, MemberList, TypeList, Work,
-    WorkAgency, WorkList,
+    CrossrefType, Funder, FunderList, FunderTree, Journal, JournalList, Member, MemberList,
+    TypeList, Work, WorkAgency, WorkList, WorkStream,
 };
 
 pub(crate) use self::response::{Message, Response};
 
 use crate::error::ErrorKind;
 use crate::query::{FundersQuery, MembersQuery, ResourceComponent};
-use crate::response::{MessageType, Prefix};
+use crate::response::{MessageItem, MessageType, Prefix};
 use reqwest::{self, Client};
+use serde_json::Value;
 use std::iter::FlatMap;
 use std::rc::Rc;
 
 macro_rules! get_item {
-    ($ident:ident, $value:expr, $got:expr) => {
-        if let Some(msg) = $value {
-            match msg {
-                Message::$ident(item) => Ok(item),
-                _ => Err(ErrorKind::UnexpectedItem {
-                    expected: MessageType::$ident,
-                    got: $got,
-                }
-                .into()),
+    ($ident:ident, $resp:expr) => {{
+        let resp = $resp;
+        match resp.message {
+            Some(item) => Ok(item),
+            None if resp.message_type != MessageType::$ident => Err(ErrorKind::UnexpectedItem {
+                expected: MessageType::$ident,
+                got: resp.message_type,
             }
-        } else {
-            Err(ErrorKind::MissingMessage {
+            .into()),
+            None => Err(ErrorKind::MissingMessage {
                 expected: MessageType::$ident,
             }
-            .into())
+            .into()),
         }
-    };
+    }};
 }
 
 macro_rules! impl_combined_works_query {
@@ -307,8 +323,7 @@ macro_rules! impl_combined_works_query {
         /// Return one page of the components's `Work` that match the query
         ///
         pub fn $name(&self, ident: WorksIdentQuery) -> Result<WorkList> {
-            let resp = self.get_response(&$component::Works(ident))?;
-            get_item!(WorkList, resp.message, resp.message_type)
+            get_item!(WorkList, self.get_response(&$component::Works(ident))?)
         })+
     };
 }
@@ -318,12 +333,17 @@ macro_rules! impl_combined_works_query {
 pub struct Crossref {
     /// use another base url than `api.crossref.org`
     pub base_url: String,
+    /// the DOI resolver used for content negotiation, `https://doi.org` by default
+    pub resolver_url: String,
     /// the reqwest client that handles the requests
     pub client: Rc<Client>,
+    /// the reqwest client for the DOI resolver, it never sends the Plus token
+    pub resolver_client: Rc<Client>,
 }
 
 impl Crossref {
     const BASE_URL: &'static str = "https://api.crossref.org";
+    const RESOLVER_URL: &'static str = "https://doi.org";
 
     /// Constructs a new `CrossrefBuilder`.
     ///
@@ -343,19 +363,82 @@ impl Crossref {
     /// If it was a bad url, the server will return `Resource not found` a `ResourceNotFound` error will be returned in this case
     /// Also fails if the json response body could be parsed into `Response`
     /// Fails if there was an error in reqwest executing the request [::reqwest::RequestBuilder::send]
-    fn get_response<T: CrossrefQuery>(&self, query: &T) -> Result<Response> {
-        let resp = self
-            .client
-            .get(&query.to_url(&self.base_url)?)
-            .send()?
-            .text()?;
+    fn get_response<T: CrossrefQuery, M: MessageItem>(&self, query: &T) -> Result<Response<M>> {
+        Ok(serde_json::from_str(&self.get_body(query)?)?)
+    }
+
+    /// Executes the request for the `CrossrefQuery` and returns the response body
+    ///
+    /// # Errors
+    ///
+    /// If it was a bad url, the server will return `Resource not found` a `ResourceNotFound` error will be returned in this case
+    /// Fails if there was an error in reqwest executing the request [::reqwest::RequestBuilder::send]
+    fn get_body<T: CrossrefQuery>(&self, query: &T) -> Result<String> {
+        let mut resp = self.client.get(&query.to_url(&self.base_url)?).send()?;
+        let status = resp.status();
+        if status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            resp.error_for_status_ref()?;
+        }
+        let resp = resp.text()?;
         if resp.starts_with("Resource not found") {
             Err(ErrorKind::ResourceNotFound {
                 resource: Box::new(query.clone().resource_component()),
             }
             .into())
         } else {
-            Ok(serde_json::from_str(&resp)?)
+            Ok(resp)
+        }
+    }
+
+    /// Return the whole response of any `CrossrefQuery` as raw json.
+    ///
+    /// This includes all fields crossref sends, regardless whether they are modeled by the typed responses.
+    ///
+    /// # Example
+    ///
+    /// ```edition2018
+    /// use crossref::{Crossref, Works};
+    /// # fn run() -> Result<(), crossref::Error> {
+    /// let client = Crossref::builder().build()?;
+    ///
+    /// let resp = client.raw(Works::doi("10.1037/0003-066X.59.1.29"))?;
+    /// let publisher = &resp["message"]["publisher"];
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the query expands to a bad route `ResourceNotFound`
+    /// Also fails if the response body is no valid json
+    pub fn raw<T: CrossrefQuery>(&self, query: T) -> Result<Value> {
+        Ok(serde_json::from_str(&self.get_body(&query)?)?)
+    }
+
+    /// Deep page through all `Work` items that match the query and return them as raw json.
+    ///
+    /// Works like [Crossref::deep_page], but yields the `items` of each page as they were sent by crossref.
+    /// A failed request is yielded as error and ends the iteration.
+    ///
+    /// # Example
+    ///
+    /// ```edition2018
+    /// use crossref::{Crossref, WorksQuery};
+    /// # fn run() -> Result<(), crossref::Error> {
+    /// let client = Crossref::builder().build()?;
+    ///
+    /// for work in client.works_raw(WorksQuery::new("Machine Learning")) {
+    ///     println!("{}", work?["DOI"]);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn works_raw<T: Into<WorkListQuery>>(&self, query: T) -> RawWorkIterator<'_> {
+        RawWorkIterator {
+            query: query.into(),
+            client: self,
+            items: Vec::new().into_iter(),
+            finish_next_iteration: false,
         }
     }
 
@@ -388,8 +471,49 @@ impl Crossref {
     /// Fails if the response body doesn't have `message` field `MissingMessage`.
     /// Fails if anything else than a `WorkList` is returned as message `UnexpectedItem`
     pub fn works<T: Into<WorkListQuery>>(&self, query: T) -> Result<WorkList> {
-        let resp = self.get_response(&query.into())?;
-        get_item!(WorkList, resp.message, resp.message_type)
+        get_item!(WorkList, self.get_response(&query.into())?)
+    }
+
+    /// Return the `Work` items that match a certain query as a `WorkStream`.
+    ///
+    /// Other than [Crossref::works], the response body is decoded incrementally
+    /// and only a single `Work` is held in memory at a time.
+    /// This is useful for large pages, like `rows=1000`.
+    ///
+    /// # Example
+    ///
+    /// ```edition2018
+    /// use crossref::{Crossref, WorksQuery};
+    /// # fn run() -> Result<(), crossref::Error> {
+    /// let client = Crossref::builder().build()?;
+    ///
+    /// for work in client.works_stream(WorksQuery::new("Machine Learning"))? {
+    ///     println!("{}", work?.doi);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the `works` element expands to a bad route `ResourceNotFound`
+    /// or if crossref responds with any other http error status `ReqWest`
+    /// Each item of the stream fails if the body could not be decoded or
+    /// if anything else than a `WorkList` is returned as message `UnexpectedItem`
+    pub fn works_stream<T: Into<WorkListQuery>>(
+        &self,
+        query: T,
+    ) -> Result<WorkStream<reqwest::Response>> {
+        let query = query.into();
+        let resp = self.client.get(&query.to_url(&self.base_url)?).send()?;
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            Err(ErrorKind::ResourceNotFound {
+                resource: Box::new(query.resource_component()),
+            }
+            .into())
+        } else {
+            Ok(WorkStream::new(resp.error_for_status()?))
+        }
     }
 
     /// Return the `Work` that is identified by  the `doi`.
@@ -398,8 +522,10 @@ impl Crossref {
     /// This method fails if the doi could not identified `ResourceNotFound`
     ///
     pub fn work(&self, doi: &str) -> Result<Work> {
-        let resp = self.get_response(&Works::Identifier(doi.to_string()))?;
-        get_item!(Work, resp.message, resp.message_type).map(|x| *x)
+        get_item!(
+            Work,
+            self.get_response(&Works::Identifier(doi.to_string()))?
+        )
     }
 
     /// [Deep paging results](https://github.com/CrossRef/rest-api-doc#deep-paging-with-cursors)
@@ -494,55 +620,101 @@ impl Crossref {
     /// This method fails if the doi could not identified `ResourceNotFound`
     ///
     pub fn work_agency(&self, doi: &str) -> Result<WorkAgency> {
-        let resp = self.get_response(&Works::Agency(doi.to_string()))?;
-        get_item!(WorkAgency, resp.message, resp.message_type)
+        get_item!(
+            WorkAgency,
+            self.get_response(&Works::Agency(doi.to_string()))?
+        )
     }
 
     /// Return the matching `Funders` items.
     pub fn funders(&self, funders: FundersQuery) -> Result<FunderList> {
-        let resp = self.get_response(&Funders::Query(funders))?;
-        get_item!(FunderList, resp.message, resp.message_type)
+        get_item!(FunderList, self.get_response(&Funders::Query(funders))?)
     }
 
     /// Return the `Funder` for the `id`
     pub fn funder(&self, id: &str) -> Result<Funder> {
-        let resp = self.get_response(&Funders::Identifier(id.to_string()))?;
-        get_item!(Funder, resp.message, resp.message_type).map(|x| *x)
+        get_item!(
+            Funder,
+            self.get_response(&Funders::Identifier(id.to_string()))?
+        )
+    }
+
+    /// Return all descendants of the funder with the `id` in the funder registry.
+    ///
+    /// The funder is requested first, then each funder below it in its [`FunderTree`],
+    /// in depth first order, to include their names and work counts.
+    /// Descendants of funders that are marked with `more` in the tree are not included.
+    ///
+    /// Each descendant is returned with its id and the result of its own request,
+    /// a single descendant that could not be requested does not fail the others.
+    ///
+    /// # Example
+    ///
+    /// ```edition2018
+    /// use crossref::Crossref;
+    /// # fn run() -> Result<(), crossref::Error> {
+    /// # let client = Crossref::builder().build()?;
+    /// for (id, funder) in client.funder_descendants("100000002")? {
+    ///     match funder {
+    ///         Ok(funder) => println!("{}: {:?}", funder.name, funder.work_count),
+    ///         Err(err) => eprintln!("{}: {}", id, err),
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// This method fails if the funder with the `id` could not be found `ResourceNotFound`
+    pub fn funder_descendants(&self, id: &str) -> Result<Vec<(String, Result<Funder>)>> {
+        let funder = self.funder(id)?;
+        Ok(funder
+            .tree()
+            .descendants(&funder.id)
+            .into_iter()
+            .map(|node| (node.id.clone(), self.funder(&node.id)))
+            .collect())
     }
 
     /// Return the matching `Members` items.
     pub fn members(&self, members: MembersQuery) -> Result<MemberList> {
-        let resp = self.get_response(&Members::Query(members))?;
-        get_item!(MemberList, resp.message, resp.message_type)
+        get_item!(MemberList, self.get_response(&Members::Query(members))?)
     }
 
     /// Return the `Member` for the `id`
     pub fn member(&self, member_id: &str) -> Result<Member> {
-        let resp = self.get_response(&Members::Identifier(member_id.to_string()))?;
-        get_item!(Member, resp.message, resp.message_type).map(|x| *x)
+        get_item!(
+            Member,
+            self.get_response(&Members::Identifier(member_id.to_string()))?
+        )
     }
 
     /// Return the `Prefix` for the `id`
     pub fn prefix(&self, id: &str) -> Result<Prefix> {
-        let resp = self.get_response(&Prefixes::Identifier(id.to_string()))?;
-        get_item!(Prefix, resp.message, resp.message_type)
+        get_item!(
+            Prefix,
+            self.get_response(&Prefixes::Identifier(id.to_string()))?
+        )
     }
     /// Return a specific `Journal`
     pub fn journal(&self, id: &str) -> Result<Journal> {
-        let resp = self.get_response(&Journals::Identifier(id.to_string()))?;
-        get_item!(Journal, resp.message, resp.message_type).map(|x| *x)
+        get_item!(
+            Journal,
+            self.get_response(&Journals::Identifier(id.to_string()))?
+        )
     }
 
     /// Return all available `Type`
     pub fn types(&self) -> Result<TypeList> {
-        let resp = self.get_response(&Types::All)?;
-        get_item!(TypeList, resp.message, resp.message_type)
+        get_item!(TypeList, self.get_response(&Types::All)?)
     }
 
     /// Return the `Type` for the `id`
     pub fn type_(&self, id: &Type) -> Result<CrossrefType> {
-        let resp = self.get_response(&Types::Identifier(id.id().to_string()))?;
-        get_item!(Type, resp.message, resp.message_type)
+        get_item!(
+            Type,
+            self.get_response(&Types::Identifier(id.id().to_string()))?
+        )
     }
 
     /// Get a random set of DOIs
@@ -592,6 +764,8 @@ pub struct CrossrefBuilder {
     plus_token: Option<String>,
     /// use a different base url than `Crossref::BASE_URL` https://api.crossref.org
     base_url: Option<String>,
+    /// use a different DOI resolver than `Crossref::RESOLVER_URL` https://doi.org
+    resolver_url: Option<String>,
     proxy: Option<String>,
 }
 
@@ -622,6 +796,18 @@ impl CrossrefBuilder {
         self
     }
 
+    /// use a different base url than `https://api.crossref.org`, like a mirror or a local stand-in
+    pub fn base_url(mut self, base_url: &str) -> Self {
+        self.base_url = Some(base_url.trim_end_matches('/').to_string());
+        self
+    }
+
+    /// set the DOI resolver used for content negotiation
+    pub fn resolver_url(mut self, resolver_url: &str) -> Self {
+        self.resolver_url = Some(resolver_url.trim_end_matches('/').to_string());
+        self
+    }
+
     pub fn proxy(mut self, proxy: &str) -> Self {
         self.proxy = Some(proxy.to_string());
         self
@@ -642,6 +828,23 @@ impl CrossrefBuilder {
                 })?,
             );
         }
+        let build_client = |headers: header::HeaderMap| -> Result<Client> {
+            let mut client_builder = reqwest::Client::builder();
+            if let Some(proxy) = &self.proxy {
+                client_builder = client_builder.proxy(reqwest::Proxy::all(proxy)?);
+            };
+            client_builder
+                .default_headers(headers)
+                .build()
+                .map_err(|_| {
+                    ErrorKind::Config {
+                        msg: "failed to initialize TLS backend".to_string(),
+                    }
+                    .into()
+                })
+        };
+        // the token is only meant for the api, not for the resolver or the publishers behind it
+        let resolver_client = build_client(headers.clone())?;
         if let Some(token) = &self.plus_token {
             headers.insert(
                 header::AUTHORIZATION,
@@ -650,24 +853,17 @@ impl CrossrefBuilder {
                 })?,
             );
         }
-        let mut client_builder = reqwest::Client::builder();
-
-        if let Some(proxy) = self.proxy {
-            client_builder = client_builder.proxy(reqwest::Proxy::all(&proxy)?);
-        };
-
-        let client = client_builder
-            .default_headers(headers)
-            .build()
-            .map_err(|_| ErrorKind::Config {
-                msg: "failed to initialize TLS backend".to_string(),
-            })?;
+        let client = build_client(headers)?;
 
         Ok(Crossref {
             base_url: self
                 .base_url
                 .unwrap_or_else(|| Crossref::BASE_URL.to_string()),
+            resolver_url: self
+                .resolver_url
+                .unwrap_or_else(|| Crossref::RESOLVER_URL.to_string()),
             client: Rc::new(client),
+            resolver_client: Rc::new(resolver_client),
         })
     }
 }
@@ -688,58 +884,235 @@ impl<'a> WorkListIterator<'a> {
     pub fn into_work_iter(self) -> impl Iterator<Item = Work> + 'a {
         self.flat_map(|x| x.items)
     }
+
+    /// Iterates over all `Work` items and yields the error of a failed request.
+    ///
+    /// Unlike `into_work_iter`, which silently stops at the first failed request,
+    /// the error is yielded as the last item.
+    ///
+    /// # Example
+    ///
+    /// ```edition2018
+    /// use crossref::{Crossref, Work};
+    /// # fn run() -> Result<(), crossref::Error> {
+    /// let client = Crossref::builder().build()?;
+    ///
+    /// let all_works: Vec<Work> = client.deep_page("Machine Learning")
+    ///     .into_work_results()
+    ///     .collect::<Result<_, _>>()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn into_work_results(mut self) -> impl Iterator<Item = Result<Work>> + 'a {
+        let mut failed = false;
+        std::iter::from_fn(move || {
+            if failed {
+                return None;
+            }
+            match self.next_page() {
+                Ok(page) => page.map(|list| list.items.into_iter().map(Ok).collect()),
+                Err(err) => {
+                    failed = true;
+                    Some(vec![Err(err)])
+                }
+            }
+        })
+        .flat_map(|items: Vec<Result<Work>>| items)
+    }
+
+    /// requests the next page, `None` once all pages were retrieved
+    fn next_page(&mut self) -> Result<Option<WorkList>> {
+        if self.finish_next_iteration {
+            return Ok(None);
+        }
+
+        prepare_deep_page(&mut self.query);
+
+        let resp = self.client.get_response(&self.query)?;
+        let worklist: Result<WorkList> = get_item!(WorkList, resp);
+        let worklist = worklist?;
+        self.finish_next_iteration =
+            !set_next_cursor(&mut self.query, worklist.next_cursor.as_ref());
+
+        if worklist.items.is_empty() {
+            self.finish_next_iteration = true;
+            Ok(None)
+        } else {
+            Ok(Some(worklist))
+        }
+    }
 }
 
 impl<'a> Iterator for WorkListIterator<'a> {
     type Item = WorkList;
 
+    /// stops at the first request that fails, see `into_work_results` to get the error
     fn next(&mut self) -> Option<Self::Item> {
+        self.next_page().unwrap_or_else(|_| {
+            self.finish_next_iteration = true;
+            None
+        })
+    }
+}
+
+/// if no result control is set, set a new cursor to deep page through the results
+fn prepare_deep_page(query: &mut WorkListQuery) {
+    let control = &mut query.query_mut().result_control;
+    if control.is_none() {
+        *control = Some(WorkResultControl::new_cursor());
+    }
+}
+
+/// use the received cursor token in the next request.
+/// Returns `false` if there are no more pages to request.
+fn set_next_cursor(query: &mut WorkListQuery, next_cursor: Option<&String>) -> bool {
+    match (next_cursor, &mut query.query_mut().result_control) {
+        (Some(cursor), Some(WorkResultControl::Cursor { token, .. })) => {
+            *token = Some(cursor.clone());
+            true
+        }
+        // standard result control was set, don't deep page
+        (Some(_), Some(WorkResultControl::Standard(_))) => false,
+        (Some(_), None) => true,
+        // no cursor received
+        (None, _) => false,
+    }
+}
+
+/// Allows iterating over the raw json of all `Work` items of a deep page work request
+pub struct RawWorkIterator<'a> {
+    /// the query
+    query: WorkListQuery,
+    /// performs each request
+    client: &'a Crossref,
+    /// the remaining items of the current page
+    items: std::vec::IntoIter<Value>,
+    /// whether the iterator should finish next iteration
+    finish_next_iteration: bool,
+}
+
+impl<'a> RawWorkIterator<'a> {
+    /// the next item, requests the next page once the current page is exhausted
+    fn next_item(&mut self) -> Result<Option<Value>> {
+        if let Some(item) = self.items.next() {
+            return Ok(Some(item));
+        }
         if self.finish_next_iteration {
-            return None;
+            return Ok(None);
         }
 
-        {
-            let control = &mut self.query.query_mut().result_control;
+        prepare_deep_page(&mut self.query);
 
-            // if no result control is set, set a new cursor
-            if control.is_none() {
-                *control = Some(WorkResultControl::new_cursor());
-            }
+        let mut resp = self.client.raw(self.query.clone())?;
+        let missing = || ErrorKind::MissingMessage {
+            expected: MessageType::WorkList,
+        };
+        let message = resp.get_mut("message").ok_or_else(missing)?;
+        let next_cursor = message
+            .get("next-cursor")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        self.finish_next_iteration = !set_next_cursor(&mut self.query, next_cursor.as_ref());
+
+        match message.get_mut("items").map(Value::take) {
+            Some(Value::Array(items)) => self.items = items.into_iter(),
+            // not the items of a `WorkList`
+            _ => return Err(missing().into()),
         }
+        Ok(self.items.next())
+    }
+}
 
-        let resp = self.client.get_response(&self.query);
-        if let Ok(resp) = resp {
-            let worklist: Result<WorkList> = get_item!(WorkList, resp.message, resp.message_type);
-            if let Ok(worklist) = worklist {
-                if let Some(cursor) = &worklist.next_cursor {
-                    match &mut self.query.query_mut().result_control {
-                        Some(WorkResultControl::Cursor { token, .. }) => {
-                            // use the received cursor token in next iteration
-                            *token = Some(cursor.clone())
-                        }
-                        Some(WorkResultControl::Standard(_)) => {
-                            // standard result control was set, don't deep page and return next iteration
-                            self.finish_next_iteration = true;
-                        }
-                        _ => (),
-                    }
-                } else {
-                    // no cursor received, end next iteration
-                    self.finish_next_iteration = true;
-                }
+impl<'a> Iterator for RawWorkIterator<'a> {
+    type Item = Result<Value>;
 
-                if worklist.items.is_empty() {
-                    None
-                } else {
-                    Some(worklist)
-                }
-            } else {
-                // failed to deserialize response into `WorkList`
-                None
+    /// yields the error of a failed request as the last item
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_item() {
+            Ok(item) => item.map(Ok),
+            Err(err) => {
+                self.finish_next_iteration = true;
+                Some(Err(err))
             }
-        } else {
-            // no response received
-            None
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::serve;
+
+    #[test]
+    fn deep_page_yields_errors() {
+        let (url, server) = serve(vec![
+            (
+                "200 OK",
+                "Content-Type: application/json\r\n",
+                r#"{"status":"ok","message-type":"work-list","message-version":"1.0.0","message":{"items-per-page":1,"query":{"start-index":0,"search-terms":null},"total-results":2,"next-cursor":"next","items":[{"DOI":"10.5555/1","is-referenced-by-count":0,"title":["First"],"issued":{"date-parts":[[2019]]}}]}}"#,
+            ),
+            ("503 Service Unavailable", "", ""),
+        ]);
+        let client = Crossref::builder().base_url(&url).build().unwrap();
+
+        let works: Vec<_> = client
+            .deep_page(WorksQuery::new("q"))
+            .into_work_results()
+            .collect();
+        let requests = server.join().unwrap();
+
+        assert_eq!(2, works.len());
+        assert_eq!("10.5555/1", works[0].as_ref().unwrap().doi);
+        assert!(works[1].as_ref().unwrap_err().to_string().contains("503"));
+        assert!(requests[1].contains("cursor=next"));
+    }
+
+    #[test]
+    fn works_raw_yields_errors() {
+        let (url, server) = serve(vec![
+            (
+                "200 OK",
+                "Content-Type: application/json\r\n",
+                r#"{"status":"ok","message-type":"work-list","message-version":"1.0.0","message":{"total-results":2,"next-cursor":"next","items":[{"DOI":"10.5555/1","source":"Crossref"}]}}"#,
+            ),
+            ("503 Service Unavailable", "", ""),
+        ]);
+        let client = Crossref::builder().base_url(&url).build().unwrap();
+
+        let works: Vec<_> = client.works_raw(WorksQuery::new("q")).collect();
+        let requests = server.join().unwrap();
+
+        assert_eq!(2, works.len());
+        assert_eq!("Crossref", works[0].as_ref().unwrap()["source"]);
+        assert!(works[1].as_ref().unwrap_err().to_string().contains("503"));
+        assert!(requests[1].contains("cursor=next"));
+    }
+
+    #[test]
+    fn funder_descendants_reports_each_failure() {
+        let (url, server) = serve(vec![
+            (
+                "200 OK",
+                "",
+                r#"{"status":"ok","message-type":"funder","message-version":"1.0.0","message":{"id":"100000002","name":"National Institutes of Health","hierarchy":{"100000016":{"100000002":{"100000025":{},"100000026":{}}}}}}"#,
+            ),
+            (
+                "200 OK",
+                "",
+                r#"{"status":"ok","message-type":"funder","message-version":"1.0.0","message":{"id":"100000025","name":"National Institute of Mental Health","work-count":7}}"#,
+            ),
+            ("404 Not Found", "", "Resource not found."),
+        ]);
+        let client = Crossref::builder().base_url(&url).build().unwrap();
+
+        let descendants = client.funder_descendants("100000002").unwrap();
+        let requests = server.join().unwrap();
+
+        assert_eq!(2, descendants.len());
+        assert_eq!("100000025", descendants[0].0);
+        assert_eq!(Some(7), descendants[0].1.as_ref().unwrap().work_count);
+        assert_eq!("100000026", descendants[1].0);
+        assert!(descendants[1].1.is_err());
+        assert!(requests[2].starts_with("GET /funders/100000026 "));
+    }
+}
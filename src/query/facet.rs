@@ -1,5 +1,7 @@
+use crate::error::{Error, ErrorKind, Result};
 use crate::query::{CrossrefQueryParam, ParamFragment};
 use std::borrow::Cow;
+use std::str::FromStr;
 
 /// all available facets that can be set as filter in a query
 #[derive(Debug, Clone)]
@@ -51,8 +53,28 @@ impl Facet {
         }
     }
 
+    /// the names of all facets
+    pub const NAMES: &'static [&'static str] = &[
+        "affiliation",
+        "funder-name",
+        "funder-doi",
+        "orcid",
+        "container-title",
+        "assertion",
+        "archive",
+        "update-type",
+        "issn",
+        "published",
+        "type-name",
+        "license",
+        "category-name",
+        "relation-type",
+        "assertion-group",
+        "publisher-name",
+    ];
+
     /// the key name for the query fragment
-    fn as_str(&self) -> &str {
+    pub fn as_str(&self) -> &str {
         match self {
             Facet::Affiliation => "affiliation",
             Facet::FunderName => "funder-name",
@@ -74,6 +96,39 @@ impl Facet {
     }
 }
 
+impl FromStr for Facet {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim() {
+            "affiliation" => Ok(Facet::Affiliation),
+            "funder-name" => Ok(Facet::FunderName),
+            "funder-doi" => Ok(Facet::FunderDoi),
+            "orcid" => Ok(Facet::ORCID),
+            "container-title" => Ok(Facet::ContainerTitle),
+            "assertion" => Ok(Facet::Assertion),
+            "archive" => Ok(Facet::Archive),
+            "update-type" => Ok(Facet::UpdateType),
+            "issn" => Ok(Facet::ISSN),
+            "published" => Ok(Facet::Published),
+            "type-name" => Ok(Facet::TypeName),
+            "license" => Ok(Facet::License),
+            "category-name" => Ok(Facet::CategoryName),
+            "relation-type" => Ok(Facet::RelationType),
+            "assertion-group" => Ok(Facet::AssertionGroup),
+            "publisher-name" => Ok(Facet::PublisherName),
+            name => Err(ErrorKind::InvalidFacet {
+                msg: format!(
+                    "unknown facet `{}`, valid facets are: {}",
+                    name,
+                    Facet::NAMES.join(", ")
+                ),
+            }
+            .into()),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 #[allow(missing_docs)]
 pub struct FacetCount {
@@ -101,6 +156,27 @@ impl FacetCount {
     }
 }
 
+impl FromStr for FacetCount {
+    type Err = Error;
+
+    /// parses `name` or `name:count`, `*` requests all values
+    fn from_str(s: &str) -> Result<Self> {
+        let mut parts = s.splitn(2, ':');
+        let facet = parts.next().unwrap_or_default().parse()?;
+        let count = match parts.next().map(str::trim) {
+            None | Some("*") => None,
+            Some(count) => Some(count.parse().map_err(|_| ErrorKind::InvalidFacet {
+                msg: format!(
+                    "invalid count `{}` for facet `{}`, expected a number or `*`",
+                    count,
+                    s.trim()
+                ),
+            })?),
+        };
+        Ok(FacetCount { facet, count })
+    }
+}
+
 impl ParamFragment for FacetCount {
     fn key(&self) -> Cow<str> {
         Cow::Borrowed(self.facet.as_str())
@@ -124,3 +200,24 @@ impl CrossrefQueryParam for Vec<FacetCount> {
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_facet_count() {
+        let facet: FacetCount = "type-name".parse().unwrap();
+        assert_eq!("type-name:*", facet.fragment());
+        let facet: FacetCount = "orcid:500".parse().unwrap();
+        assert_eq!("orcid:100", facet.fragment());
+        let facet: FacetCount = "published:10".parse().unwrap();
+        assert_eq!("published:10", facet.fragment());
+
+        let err = "types".parse::<FacetCount>().unwrap_err().to_string();
+        assert!(
+            err.starts_with("invalid facet: unknown facet `types`, valid facets are: affiliation,")
+        );
+        assert!("published:many".parse::<FacetCount>().is_err());
+    }
+}
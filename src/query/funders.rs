@@ -1,8 +1,9 @@
-use crate::error::Result;
+use crate::error::{Error, Result};
 use crate::query::facet::FacetCount;
 use crate::query::works::{WorksCombiner, WorksFilter, WorksIdentQuery, WorksQuery};
 use crate::query::*;
 use std::borrow::Cow;
+use std::str::FromStr;
 
 /// filters supported for the /funders route
 #[derive(Debug, Clone)]
@@ -18,6 +19,22 @@ impl FundersFilter {
             FundersFilter::Location(_) => "location",
         }
     }
+
+    /// the names of all filters of the `/funders` route
+    pub const NAMES: &'static [&'static str] = &["location"];
+}
+
+impl FromStr for FundersFilter {
+    type Err = Error;
+
+    /// parses a `name:value` filter expression
+    fn from_str(s: &str) -> Result<Self> {
+        let (name, value) = split_filter(s);
+        match name {
+            "location" => filter_value(name, value).map(|v| FundersFilter::Location(v.to_string())),
+            name => Err(unknown_filter(name, "/funders", FundersFilter::NAMES)),
+        }
+    }
 }
 
 impl ParamFragment for FundersFilter {
@@ -1,7 +1,8 @@
-use crate::error::Result;
+use crate::error::{Error, Result};
 use crate::query::works::{WorksCombiner, WorksFilter, WorksIdentQuery, WorksQuery};
 use crate::query::*;
 use std::borrow::Cow;
+use std::str::FromStr;
 
 /// filters supported for the `/members` route
 #[derive(Debug, Clone)]
@@ -22,10 +23,40 @@ impl MembersFilter {
         match self {
             MembersFilter::HasPublicReferences => "has-public-references",
             MembersFilter::ReferenceVisibility(_) => "reference-visibility",
-            MembersFilter::BlackfileDoiCount(_) => "blackfile-doi-count",
+            MembersFilter::BlackfileDoiCount(_) => "backfile-doi-count",
             MembersFilter::CurrentDoiCount(_) => "current-doi-count",
         }
     }
+
+    /// the names of all filters of the `/members` route
+    pub const NAMES: &'static [&'static str] = &[
+        "has-public-references",
+        "reference-visibility",
+        "backfile-doi-count",
+        "current-doi-count",
+    ];
+}
+
+impl FromStr for MembersFilter {
+    type Err = Error;
+
+    /// parses a `name:value` filter expression
+    fn from_str(s: &str) -> Result<Self> {
+        let (name, value) = split_filter(s);
+        match name {
+            "has-public-references" => {
+                filter_flag(name, value).map(|_| MembersFilter::HasPublicReferences)
+            }
+            "reference-visibility" => {
+                filter_visibility(name, value).map(MembersFilter::ReferenceVisibility)
+            }
+            "backfile-doi-count" => {
+                filter_number(name, value).map(MembersFilter::BlackfileDoiCount)
+            }
+            "current-doi-count" => filter_number(name, value).map(MembersFilter::CurrentDoiCount),
+            name => Err(unknown_filter(name, "/members", MembersFilter::NAMES)),
+        }
+    }
 }
 
 impl ParamFragment for MembersFilter {
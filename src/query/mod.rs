@@ -1,4 +1,4 @@
-use crate::error::{Error, Result};
+use crate::error::{Error, ErrorKind, Result};
 use crate::query::facet::FacetCount;
 pub use crate::query::funders::{Funders, FundersQuery};
 pub use crate::query::journals::Journals;
@@ -517,6 +517,147 @@ impl CrossrefQuery for ResourceComponent {
 /// Helper trait to mark filters in the query string
 pub trait Filter: ParamFragment {}
 
+/// Parses a comma separated list of `key:value` filters like `type:journal-article,from-pub-date:2020`.
+///
+/// A comma only separates two filters if it is followed by one of the `names`,
+/// so values may contain commas.
+///
+/// # Example
+///
+/// ```edition2018
+/// use crossref::query::parse_filters;
+/// use crossref::WorksFilter;
+///
+/// let filters: Vec<WorksFilter> =
+///     parse_filters("type:journal-article,from-pub-date:2020", WorksFilter::NAMES).unwrap();
+/// assert_eq!(2, filters.len());
+/// ```
+pub fn parse_filters<F>(s: &str, names: &[&str]) -> Result<Vec<F>>
+where
+    F: FromStr<Err = Error>,
+{
+    let mut expressions: Vec<String> = Vec::new();
+    for part in s.split(',') {
+        let (name, _) = split_filter(part);
+        match expressions.last_mut() {
+            Some(previous) if !names.contains(&name.trim()) => {
+                previous.push(',');
+                previous.push_str(part);
+            }
+            _ => expressions.push(part.to_string()),
+        }
+    }
+    expressions.iter().map(|e| e.parse()).collect()
+}
+
+/// splits a filter expression into the name and the value, `has-funder` has no value
+pub(crate) fn split_filter(s: &str) -> (&str, Option<&str>) {
+    let mut parts = s.trim().splitn(2, ':');
+    let name = parts.next().unwrap_or_default();
+    (name, parts.next())
+}
+
+/// the error for a filter name that is not supported by the route
+pub(crate) fn unknown_filter(name: &str, route: &str, names: &[&str]) -> Error {
+    ErrorKind::InvalidFilter {
+        msg: format!(
+            "unknown filter `{}` for the `{}` route, valid filters are: {}",
+            name,
+            route,
+            names.join(", ")
+        ),
+    }
+    .into()
+}
+
+/// the error for a filter of the route whose value the typed filter can not hold
+pub(crate) fn unsupported_filter(name: &str, route: &str) -> Error {
+    ErrorKind::InvalidFilter {
+        msg: format!(
+            "filter `{}` of the `{}` route takes a value that can not be passed on yet",
+            name, route
+        ),
+    }
+    .into()
+}
+
+/// the error for a filter value that can not be parsed
+pub(crate) fn invalid_filter_value(name: &str, value: Option<&str>, expected: &str) -> Error {
+    let msg = match value {
+        Some(value) => format!(
+            "invalid value `{}` for filter `{}`, expected {}",
+            value, name, expected
+        ),
+        None => format!("missing value for filter `{}`, expected {}", name, expected),
+    };
+    ErrorKind::InvalidFilter { msg }.into()
+}
+
+/// the value of a filter that requires one
+pub(crate) fn filter_value<'a>(name: &str, value: Option<&'a str>) -> Result<&'a str> {
+    value
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .ok_or_else(|| invalid_filter_value(name, value, "a value like `name:value`"))
+}
+
+/// checks the value of a filter without value, only `true` is accepted
+pub(crate) fn filter_flag(name: &str, value: Option<&str>) -> Result<()> {
+    match value.map(str::trim) {
+        None | Some("true") => Ok(()),
+        other => Err(invalid_filter_value(name, other, "no value or `true`")),
+    }
+}
+
+/// parses a numeric filter value
+pub(crate) fn filter_number<T: FromStr>(name: &str, value: Option<&str>) -> Result<T> {
+    filter_value(name, value)?
+        .parse()
+        .map_err(|_| invalid_filter_value(name, value, "a number"))
+}
+
+/// parses a `YYYY`, `YYYY-MM` or `YYYY-MM-DD` date, incomplete `until` dates refer to the end of the period
+pub(crate) fn filter_date(name: &str, value: Option<&str>) -> Result<NaiveDate> {
+    let invalid =
+        || invalid_filter_value(name, value, "a date like `2020`, `2020-04` or `2020-04-28`");
+    let date = filter_value(name, value)?;
+    let parts = date
+        .split('-')
+        .map(|p| p.parse::<u32>().map_err(|_| invalid()))
+        .collect::<Result<Vec<_>>>()?;
+    let until = name.starts_with("until-");
+    let date = match parts.as_slice() {
+        [year] if until => NaiveDate::from_ymd_opt(*year as i32, 12, 31),
+        [year] => NaiveDate::from_ymd_opt(*year as i32, 1, 1),
+        [year, month] if until => {
+            let (year, month) = if *month == 12 {
+                (year + 1, 1)
+            } else {
+                (*year, month + 1)
+            };
+            NaiveDate::from_ymd_opt(year as i32, month, 1).and_then(|d| d.pred_opt())
+        }
+        [year, month] => NaiveDate::from_ymd_opt(*year as i32, *month, 1),
+        [year, month, day] => NaiveDate::from_ymd_opt(*year as i32, *month, *day),
+        _ => None,
+    };
+    date.ok_or_else(invalid)
+}
+
+/// parses a `open`, `limited` or `closed` visibility
+pub(crate) fn filter_visibility(name: &str, value: Option<&str>) -> Result<Visibility> {
+    match filter_value(name, value)? {
+        "open" => Ok(Visibility::Open),
+        "limited" => Ok(Visibility::Limited),
+        "closed" => Ok(Visibility::Closed),
+        _ => Err(invalid_filter_value(
+            name,
+            value,
+            "one of `open`, `limited` or `closed`",
+        )),
+    }
+}
+
 impl<T: Filter> CrossrefQueryParam for Vec<T> {
     /// always use `filter` as the key
     fn param_key(&self) -> Cow<str> {
@@ -7,8 +7,10 @@ use serde::Serializer as SerdeSerializer;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::borrow::Cow;
+use std::str::FromStr;
 #[cfg(feature = "cli")]
 use structopt::StructOpt;
+use url::form_urlencoded::byte_serialize;
 
 /// Filters allow you to narrow queries. All filter results are lists
 #[derive(Debug, Clone)]
@@ -219,7 +221,7 @@ impl WorksFilter {
             WorksFilter::AlternativeId => "alternative-id",
             WorksFilter::ArticleNumber => "article-number",
             WorksFilter::HasAbstract => "has-abstract",
-            WorksFilter::HasClinicalTrialNumber => "has-clinical-trial-number	",
+            WorksFilter::HasClinicalTrialNumber => "has-clinical-trial-number",
             WorksFilter::ContentDomain(_) => "content-domain",
             WorksFilter::HasContentDomain => "has-content-domain",
             WorksFilter::HasDomainRestriction => "has-domain-restriction",
@@ -229,6 +231,186 @@ impl WorksFilter {
             WorksFilter::RelationObjectType(_) => "relation.object-type",
         }
     }
+
+    /// the names of the filters of the `/works` route that can be parsed
+    pub const NAMES: &'static [&'static str] = &[
+        "has-funder",
+        "funder",
+        "location",
+        "prefix",
+        "member",
+        "from-index-date",
+        "until-index-date",
+        "from-deposit-date",
+        "until-deposit-date",
+        "from-update-date",
+        "until-update-date",
+        "from-created-date",
+        "until-created-date",
+        "from-pub-date",
+        "until-pub-date",
+        "from-online-pub-date",
+        "until-online-pub-date",
+        "from-print-pub-date",
+        "until-print-pub-date",
+        "from-posted-date",
+        "until-posted-date",
+        "from-accepted-date",
+        "until-accepted-date",
+        "has-license",
+        "license.url",
+        "license.version",
+        "license.delay",
+        "has-full-text",
+        "full-text.version",
+        "full-text.type",
+        "full-text.application",
+        "has-references",
+        "reference-visibility",
+        "has-archive",
+        "archive",
+        "has-orcid",
+        "has-authenticated-orcid",
+        "orcid",
+        "issn",
+        "isbn",
+        "type",
+        "directory",
+        "doi",
+        "updates",
+        "is-update",
+        "has-update-policy",
+        "container-title",
+        "category-name",
+        "type-name",
+        "award.number",
+        "award.funder",
+        "has-assertion",
+        "assertion-group",
+        "assertion",
+        "has-affiliation",
+        "has-abstract",
+        "has-clinical-trial-number",
+        "content-domain",
+        "has-content-domain",
+        "has-domain-restriction",
+        "has-relation",
+        "relation.object-type",
+    ];
+}
+
+impl FromStr for WorksFilter {
+    type Err = Error;
+
+    /// parses a `name:value` filter expression, filters without value like `has-funder` need no `:value`
+    fn from_str(s: &str) -> Result<Self> {
+        let (name, value) = split_filter(s);
+        match name {
+            "has-funder" => filter_flag(name, value).map(|_| WorksFilter::HasFunder),
+            "funder" => filter_value(name, value).map(|v| WorksFilter::Funder(v.to_string())),
+            "location" => filter_value(name, value).map(|v| WorksFilter::Location(v.to_string())),
+            "prefix" => filter_value(name, value).map(|v| WorksFilter::Prefix(v.to_string())),
+            "member" => filter_value(name, value).map(|v| WorksFilter::Member(v.to_string())),
+            "from-index-date" => filter_date(name, value).map(WorksFilter::FromIndexDate),
+            "until-index-date" => filter_date(name, value).map(WorksFilter::UntilIndexDate),
+            "from-deposit-date" => filter_date(name, value).map(WorksFilter::FromDepositDate),
+            "until-deposit-date" => filter_date(name, value).map(WorksFilter::UntilDepositDate),
+            "from-update-date" => filter_date(name, value).map(WorksFilter::FromUpdateDate),
+            "until-update-date" => filter_date(name, value).map(WorksFilter::UntilUpdateDate),
+            "from-created-date" => filter_date(name, value).map(WorksFilter::FromCreatedDate),
+            "until-created-date" => filter_date(name, value).map(WorksFilter::UntilCreatedDate),
+            "from-pub-date" => filter_date(name, value).map(WorksFilter::FromPubDate),
+            "until-pub-date" => filter_date(name, value).map(WorksFilter::UntilPubDate),
+            "from-online-pub-date" => filter_date(name, value).map(WorksFilter::FromOnlinePubDate),
+            "until-online-pub-date" => {
+                filter_date(name, value).map(WorksFilter::UntilOnlinePubDate)
+            }
+            "from-print-pub-date" => filter_date(name, value).map(WorksFilter::FromPrintPubDate),
+            "until-print-pub-date" => filter_date(name, value).map(WorksFilter::UntilPrintPubDate),
+            "from-posted-date" => filter_date(name, value).map(WorksFilter::FromPostedDate),
+            "until-posted-date" => filter_date(name, value).map(WorksFilter::UntilPostedDate),
+            "from-accepted-date" => filter_date(name, value).map(WorksFilter::FromAcceptedDate),
+            "until-accepted-date" => filter_date(name, value).map(WorksFilter::UntilAcceptedDate),
+            "has-license" => filter_flag(name, value).map(|_| WorksFilter::HasLicense),
+            "license.url" => {
+                filter_value(name, value).map(|v| WorksFilter::LicenseUrl(v.to_string()))
+            }
+            "license.version" => {
+                filter_value(name, value).map(|v| WorksFilter::LicenseVersion(v.to_string()))
+            }
+            "license.delay" => filter_number(name, value).map(WorksFilter::LicenseDelay),
+            "has-full-text" => filter_flag(name, value).map(|_| WorksFilter::HasFullText),
+            "full-text.version" => {
+                filter_value(name, value).map(|v| WorksFilter::FullTextVersion(v.to_string()))
+            }
+            "full-text.type" => {
+                filter_value(name, value).map(|v| WorksFilter::FullTextType(v.to_string()))
+            }
+            "full-text.application" => {
+                filter_value(name, value).map(|v| WorksFilter::FullTextApplication(v.to_string()))
+            }
+            "has-references" => filter_flag(name, value).map(|_| WorksFilter::HasReferences),
+            "reference-visibility" => {
+                filter_visibility(name, value).map(WorksFilter::ReferenceVisibility)
+            }
+            "has-archive" => filter_flag(name, value).map(|_| WorksFilter::HasArchive),
+            "archive" => filter_value(name, value).map(|v| WorksFilter::Archive(v.to_string())),
+            "has-orcid" => filter_flag(name, value).map(|_| WorksFilter::HasOrcid),
+            "has-authenticated-orcid" => {
+                filter_flag(name, value).map(|_| WorksFilter::HasAuthenticatedOrcid)
+            }
+            "orcid" => filter_value(name, value).map(|v| WorksFilter::Orcid(v.to_string())),
+            "issn" => filter_value(name, value).map(|v| WorksFilter::Issn(v.to_string())),
+            "isbn" => filter_value(name, value).map(|v| WorksFilter::Isbn(v.to_string())),
+            "type" => filter_value(name, value)?
+                .parse()
+                .map(WorksFilter::Type)
+                .map_err(|_| invalid_filter_value(name, value, "a type id like `journal-article`")),
+            "directory" => filter_value(name, value).map(|v| WorksFilter::Directory(v.to_string())),
+            "doi" => filter_value(name, value).map(|v| WorksFilter::Doi(v.to_string())),
+            "updates" => filter_value(name, value).map(|v| WorksFilter::Updates(v.to_string())),
+            "is-update" => filter_flag(name, value).map(|_| WorksFilter::IsUpdate),
+            "has-update-policy" => filter_flag(name, value).map(|_| WorksFilter::HasUpdatePolicy),
+            "container-title" => {
+                filter_value(name, value).map(|v| WorksFilter::ContainerTitle(v.to_string()))
+            }
+            "category-name" => {
+                filter_value(name, value).map(|v| WorksFilter::CategoryName(v.to_string()))
+            }
+            "type-name" => filter_value(name, value).map(|v| WorksFilter::TypeName(v.to_string())),
+            "award.number" => {
+                filter_value(name, value).map(|v| WorksFilter::AwardNumber(v.to_string()))
+            }
+            "award.funder" => {
+                filter_value(name, value).map(|v| WorksFilter::AwardFunder(v.to_string()))
+            }
+            "has-assertion" => filter_flag(name, value).map(|_| WorksFilter::HasAssertion),
+            "assertion-group" => {
+                filter_value(name, value).map(|v| WorksFilter::AssertionGroup(v.to_string()))
+            }
+            "assertion" => filter_value(name, value).map(|v| WorksFilter::Assertion(v.to_string())),
+            "has-affiliation" => filter_flag(name, value).map(|_| WorksFilter::HasAffiliation),
+            "has-abstract" => filter_flag(name, value).map(|_| WorksFilter::HasAbstract),
+            "has-clinical-trial-number" => {
+                filter_flag(name, value).map(|_| WorksFilter::HasClinicalTrialNumber)
+            }
+            "content-domain" => {
+                filter_value(name, value).map(|v| WorksFilter::ContentDomain(v.to_string()))
+            }
+            "has-content-domain" => filter_flag(name, value).map(|_| WorksFilter::HasContentDomain),
+            "has-domain-restriction" => {
+                filter_flag(name, value).map(|_| WorksFilter::HasDomainRestriction)
+            }
+            "has-relation" => filter_flag(name, value).map(|_| WorksFilter::HasRelation),
+            "relation.object-type" => {
+                filter_value(name, value).map(|v| WorksFilter::RelationObjectType(v.to_string()))
+            }
+            "alternative-id" | "article-number" | "relation.type" | "relation.object" => {
+                Err(unsupported_filter(name, "/works"))
+            }
+            name => Err(unknown_filter(name, "/works", WorksFilter::NAMES)),
+        }
+    }
 }
 
 impl ParamFragment for WorksFilter {
@@ -372,7 +554,7 @@ impl FieldQuery {
 
 impl CrossrefQueryParam for FieldQuery {
     fn param_key(&self) -> Cow<str> {
-        Cow::Borrowed(&self.name)
+        Cow::Owned(format!("query.{}", self.name))
     }
     fn param_value(&self) -> Option<Cow<str>> {
         Some(Cow::Owned(format_query(&self.value)))
@@ -421,20 +603,32 @@ impl CrossrefQueryParam for WorkResultControl {
     fn param_key(&self) -> Cow<str> {
         match self {
             WorkResultControl::Standard(s) => s.param_key(),
-            WorkResultControl::Cursor { token, .. } => Cow::Owned(format!(
-                "cursor={}",
-                token.as_ref().map(String::as_str).unwrap_or("*")
-            )),
+            WorkResultControl::Cursor { .. } => Cow::Borrowed("cursor"),
         }
     }
 
     fn param_value(&self) -> Option<Cow<str>> {
         match self {
             WorkResultControl::Standard(s) => s.param_value(),
-            WorkResultControl::Cursor { rows, .. } => match rows {
-                Some(r) => Some(Cow::Owned(format!("rows={}", r))),
-                _ => None,
-            },
+            // tokens may contain `+`, `/` and `=`
+            WorkResultControl::Cursor { token, .. } => Some(match token {
+                Some(token) => Cow::Owned(byte_serialize(token.as_bytes()).collect()),
+                None => Cow::Borrowed("*"),
+            }),
+        }
+    }
+
+    /// the cursor is followed by the number of `rows`, if any
+    fn param(&self) -> Cow<'_, str> {
+        match (self, self.param_value()) {
+            (
+                WorkResultControl::Cursor {
+                    rows: Some(rows), ..
+                },
+                Some(cursor),
+            ) => Cow::Owned(format!("cursor={}&rows={}", cursor, rows)),
+            (_, Some(value)) => Cow::Owned(format!("{}={}", self.param_key(), value)),
+            (_, None) => self.param_key(),
         }
     }
 }
@@ -839,10 +1033,6 @@ impl CrossrefRoute for WorksQuery {
     fn route(&self) -> Result<String> {
         let mut params = Vec::new();
 
-        if let Some(sample) = self.sample {
-            return Ok(format!("sample={}", sample));
-        }
-
         if !self.free_form_queries.is_empty() {
             params.push(Cow::Owned(format!(
                 "query={}",
@@ -867,6 +1057,9 @@ impl CrossrefRoute for WorksQuery {
         if let Some(rc) = &self.result_control {
             params.push(rc.param());
         }
+        if let Some(sample) = self.sample {
+            params.push(Cow::Owned(format!("sample={}", sample)));
+        }
 
         Ok(format!(
             "{}?{}",
@@ -913,4 +1106,84 @@ mod tests {
 
         assert_eq!("/works/10.1037/0003-066X.59.1.29", &works.route().unwrap())
     }
+
+    #[test]
+    fn serialize_sample() {
+        let mut works = WorksQuery::random(10);
+        assert_eq!("/works?sample=10", &works.route().unwrap());
+
+        works.filter = vec![WorksFilter::Type(Type::Book)];
+        assert_eq!("/works?filter=type:book&sample=10", &works.route().unwrap());
+    }
+
+    #[test]
+    fn serialize_cursor() {
+        let mut works = WorksQuery::new("Machine Learning");
+        works.result_control = Some(WorkResultControl::new_cursor());
+        assert_eq!(
+            "/works?query=Machine+Learning&cursor=*",
+            &works.route().unwrap()
+        );
+
+        works.result_control = Some(WorkResultControl::Cursor {
+            token: Some("AoJ/x+y=".to_string()),
+            rows: Some(1000),
+        });
+        assert_eq!(
+            "/works?query=Machine+Learning&cursor=AoJ%2Fx%2By%3D&rows=1000",
+            &works.route().unwrap()
+        );
+    }
+
+    #[test]
+    fn serialize_field_queries() {
+        let works = WorksQuery::new("Machine Learning")
+            .field_query(FieldQuery::author("Richard Feynman"))
+            .field_query(FieldQuery::container_title("Physical Review"));
+
+        assert_eq!(
+            "/works?query=Machine+Learning&query.author=Richard+Feynman&query.container-title=Physical+Review",
+            &works.route().unwrap()
+        )
+    }
+
+    #[test]
+    fn parse_filters() {
+        let filters: Vec<WorksFilter> = crate::query::parse_filters(
+            "type:journal-article,from-pub-date:2020,until-pub-date:2020-02,has-funder,container-title:Nature, Physics",
+            WorksFilter::NAMES,
+        )
+        .unwrap();
+        let fragments: Vec<_> = filters.iter().map(ParamFragment::fragment).collect();
+        assert_eq!(
+            vec![
+                "type:journal-article",
+                "from-pub-date:2020-01-01",
+                "until-pub-date:2020-02-29",
+                "has-funder:true",
+                "container-title:Nature, Physics"
+            ],
+            fragments
+        );
+
+        let err = "publisher:x"
+            .parse::<WorksFilter>()
+            .unwrap_err()
+            .to_string();
+        assert!(err.starts_with("invalid filter: unknown filter `publisher` for the `/works` route, valid filters are: has-funder, funder,"));
+        let err = "from-pub-date:yesterday"
+            .parse::<WorksFilter>()
+            .unwrap_err()
+            .to_string();
+        assert_eq!("invalid filter: invalid value `yesterday` for filter `from-pub-date`, expected a date like `2020`, `2020-04` or `2020-04-28`", err);
+        assert!("type:article".parse::<WorksFilter>().is_err());
+        assert!("has-funder:maybe".parse::<WorksFilter>().is_err());
+        assert!("member".parse::<WorksFilter>().is_err());
+        let err = "relation.type:is-preprint-of"
+            .parse::<WorksFilter>()
+            .unwrap_err()
+            .to_string();
+        assert_eq!("invalid filter: filter `relation.type` of the `/works` route takes a value that can not be passed on yet", err);
+        assert!(!WorksFilter::NAMES.contains(&"alternative-id"));
+    }
 }
@@ -0,0 +1,295 @@
+//! Collects the fields of a response object that are not explicitly modeled.
+//!
+//! `#[serde(flatten)]` would do the same, but makes serde buffer the whole object
+//! before it is decoded, which for a page of works costs several times the time and memory
+//! of decoding it directly. Instead the derived visitor of the struct is handed a map
+//! that passes on the known keys and stores every other key in the `extra` map on the way.
+
+use serde::de::{self, DeserializeSeed, Deserializer, IntoDeserializer, MapAccess, Visitor};
+use serde_json::{Map, Value};
+use std::fmt;
+
+/// Declares a struct with an `extra` map that collects all fields not explicitly modeled.
+///
+/// The struct is written without the serde derives, the macro derives `Serialize` and
+/// implements `Deserialize` by decoding a private copy of the struct through [`ExtraFields`].
+/// The `extra` field has to be marked `#[serde(flatten, skip_deserializing)]`,
+/// it is flattened when serializing and filled by the `ExtraFields` when deserializing.
+macro_rules! extra_fields {
+    ($(#[$meta:meta])* $vis:vis struct $name:ident {
+        $($(#[$fmeta:meta])* $fvis:vis $field:ident: $ty:ty,)*
+    }) => {
+        #[derive(Serialize)]
+        $(#[$meta])*
+        $vis struct $name {
+            $($(#[$fmeta])* $fvis $field: $ty,)*
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                #[derive(Deserialize)]
+                $(#[$meta])*
+                struct Fields {
+                    $($(#[$fmeta])* $field: $ty,)*
+                }
+
+                let mut extra = serde_json::Map::new();
+                let fields = Fields::deserialize($crate::response::extra::ExtraFields::new(
+                    deserializer,
+                    &mut extra,
+                ))?;
+                let mut value = $name {
+                    $($field: fields.$field,)*
+                };
+                value.extra = extra;
+                Ok(value)
+            }
+        }
+    };
+}
+
+/// Deserializes a struct from the wrapped deserializer and stores all keys
+/// that are not one of the fields of the struct in `extra`.
+pub(crate) struct ExtraFields<'a, D> {
+    inner: D,
+    extra: &'a mut Map<String, Value>,
+}
+
+impl<'a, D> ExtraFields<'a, D> {
+    /// wraps `inner`, unknown keys end up in `extra`
+    pub(crate) fn new(inner: D, extra: &'a mut Map<String, Value>) -> Self {
+        ExtraFields { inner, extra }
+    }
+}
+
+impl<'de, 'a, D> Deserializer<'de> for ExtraFields<'a, D>
+where
+    D: Deserializer<'de>,
+{
+    type Error = D::Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.inner.deserialize_any(visitor)
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.inner.deserialize_struct(
+            name,
+            fields,
+            ExtraVisitor {
+                visitor,
+                fields,
+                extra: self.extra,
+            },
+        )
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+/// hands the derived visitor an `ExtraMap` instead of the map of the object
+struct ExtraVisitor<'a, V> {
+    visitor: V,
+    fields: &'static [&'static str],
+    extra: &'a mut Map<String, Value>,
+}
+
+impl<'de, 'a, V> Visitor<'de> for ExtraVisitor<'a, V>
+where
+    V: Visitor<'de>,
+{
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.visitor.expecting(formatter)
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        self.visitor.visit_seq(seq)
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        self.visitor.visit_map(ExtraMap {
+            inner: map,
+            fields: self.fields,
+            extra: self.extra,
+        })
+    }
+}
+
+/// only yields the keys that are fields of the struct, the values of all other keys go to `extra`
+struct ExtraMap<'a, A> {
+    inner: A,
+    fields: &'static [&'static str],
+    extra: &'a mut Map<String, Value>,
+}
+
+impl<'de, 'a, A> MapAccess<'de> for ExtraMap<'a, A>
+where
+    A: MapAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        loop {
+            match self.inner.next_key_seed(KeySeed(self.fields))? {
+                Some(Key::Field(field)) => {
+                    return seed.deserialize(field.into_deserializer()).map(Some);
+                }
+                Some(Key::Other(key)) => {
+                    let value = self.inner.next_value()?;
+                    self.extra.insert(key, value);
+                }
+                None => return Ok(None),
+            }
+        }
+    }
+
+    fn next_value_seed<T>(&mut self, seed: T) -> Result<T::Value, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        self.inner.next_value_seed(seed)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.inner.size_hint()
+    }
+}
+
+/// a key of the object, fields are matched without allocating
+enum Key {
+    Field(&'static str),
+    Other(String),
+}
+
+struct KeySeed(&'static [&'static str]);
+
+impl<'de> DeserializeSeed<'de> for KeySeed {
+    type Value = Key;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(self)
+    }
+}
+
+impl<'de> Visitor<'de> for KeySeed {
+    type Value = Key;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a field name")
+    }
+
+    fn visit_str<E>(self, key: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(match self.0.iter().find(|field| **field == key) {
+            Some(field) => Key::Field(field),
+            None => Key::Other(key.to_string()),
+        })
+    }
+
+    fn visit_string<E>(self, key: String) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(match self.0.iter().find(|field| **field == key) {
+            Some(field) => Key::Field(field),
+            None => Key::Other(key),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::response::{Work, WorkList};
+    use serde::de::DeserializeOwned;
+
+    /// only lets a type decode itself as a struct, a flattened type asks for a map instead
+    struct StructOnly<D>(D);
+
+    impl<'de, D> Deserializer<'de> for StructOnly<D>
+    where
+        D: Deserializer<'de>,
+    {
+        type Error = D::Error;
+
+        fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+        {
+            Err(de::Error::custom("not decoded as a struct"))
+        }
+
+        fn deserialize_struct<V>(
+            self,
+            name: &'static str,
+            fields: &'static [&'static str],
+            visitor: V,
+        ) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+        {
+            self.0.deserialize_struct(name, fields, visitor)
+        }
+
+        serde::forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+            bytes byte_buf option unit unit_struct newtype_struct seq tuple
+            tuple_struct map enum identifier ignored_any
+        }
+    }
+
+    fn decode<T: DeserializeOwned>(json: &str) -> T {
+        let mut deserializer = serde_json::Deserializer::from_str(json);
+        T::deserialize(StructOnly(&mut deserializer)).unwrap()
+    }
+
+    #[test]
+    fn decodes_without_buffering() {
+        let work: Work = decode(
+            r#"{"title":[],"is-referenced-by-count":1,"DOI":"10.1037/0003-066x.59.1.29",
+            "issued":{"date-parts":[[2004]]},"score":1,"source":"Crossref"}"#,
+        );
+        assert_eq!("10.1037/0003-066x.59.1.29", work.doi);
+        assert_eq!(1, work.extra["score"]);
+        assert_eq!("Crossref", work.extra["source"]);
+
+        let list: WorkList =
+            decode(r#"{"total-results":0,"items":[],"next-cursor":"AoJ/","x":[]}"#);
+        assert_eq!(Some("AoJ/"), list.next_cursor.as_deref());
+        assert_eq!(vec!["x"], list.extra.keys().collect::<Vec<_>>());
+    }
+}
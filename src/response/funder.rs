@@ -0,0 +1,262 @@
+use crate::response::Funder;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A single level of the `hierarchy` of a `Funder` as returned by the `/funders/{id}` route.
+///
+/// Every key is the id of a funder, mapped to the level of its own children.
+/// The registry truncates deep hierarchies, in that case the `more` flag is set.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct HierarchyNode {
+    /// whether the funder has more children than included in the response
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub more: bool,
+    /// the children of the funder, identified by their id
+    #[serde(flatten)]
+    pub children: HashMap<String, HierarchyNode>,
+}
+
+/// A funder inside a `FunderTree`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunderNode {
+    /// the id of the funder in the funder registry
+    pub id: String,
+    /// the name of the funder, if included in the `hierarchy-names`
+    pub name: Option<String>,
+    /// the id of the parent funder, `None` for a root
+    pub parent: Option<String>,
+    /// the ids of all direct children, sorted
+    pub children: Vec<String>,
+    /// distance to the root of the tree, a root has depth `0`
+    pub depth: usize,
+    /// whether the funder has more children than included in the tree
+    pub more: bool,
+}
+
+/// The [Funder Registry](https://github.com/Crossref/open-funder-registry) hierarchy of a `Funder`.
+///
+/// # Example
+///
+/// ```edition2018
+/// use crossref::Crossref;
+/// # fn run() -> Result<(), crossref::Error> {
+/// let client = Crossref::builder().build()?;
+///
+/// let tree = client.funder("100000015")?.tree();
+/// for root in tree.roots() {
+///     for child in tree.children(&root.id) {
+///         println!("{} -> {:?}", child.id, child.name);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FunderTree {
+    /// ids of the funders at the top of the hierarchy
+    roots: Vec<String>,
+    /// all funders of the tree, by id
+    nodes: HashMap<String, FunderNode>,
+}
+
+impl FunderTree {
+    /// builds the tree from the `hierarchy` and `hierarchy-names` of a `Funder`
+    pub fn new(funder: &Funder) -> Self {
+        let mut tree = FunderTree::default();
+        let mut roots: Vec<_> = funder.hierarchy.keys().collect();
+        roots.sort();
+        for id in roots {
+            tree.insert(id, &funder.hierarchy[id], None, 0, &funder.hierarchy_names);
+            tree.roots.push(id.clone());
+        }
+        tree
+    }
+
+    /// recursively inserts the `node` and all its children
+    fn insert(
+        &mut self,
+        id: &str,
+        node: &HierarchyNode,
+        parent: Option<&str>,
+        depth: usize,
+        names: &HashMap<String, Option<String>>,
+    ) {
+        let mut children: Vec<_> = node.children.keys().cloned().collect();
+        children.sort();
+        for child in &children {
+            self.insert(child, &node.children[child], Some(id), depth + 1, names);
+        }
+        self.nodes.insert(
+            id.to_string(),
+            FunderNode {
+                id: id.to_string(),
+                name: names.get(id).cloned().unwrap_or_default(),
+                parent: parent.map(str::to_string),
+                children,
+                depth,
+                more: node.more,
+            },
+        );
+    }
+
+    /// the funders at the top of the hierarchy
+    pub fn roots(&self) -> impl Iterator<Item = &FunderNode> {
+        self.roots.iter().filter_map(move |id| self.nodes.get(id))
+    }
+
+    /// the funder with the `id`, if part of the tree
+    pub fn get(&self, id: &str) -> Option<&FunderNode> {
+        self.nodes.get(id)
+    }
+
+    /// whether the funder with the `id` is part of the tree
+    pub fn contains(&self, id: &str) -> bool {
+        self.nodes.contains_key(id)
+    }
+
+    /// the name of the funder with the `id`
+    pub fn name(&self, id: &str) -> Option<&str> {
+        self.nodes.get(id)?.name.as_deref()
+    }
+
+    /// the depth of the funder with the `id`, roots have depth `0`
+    pub fn depth(&self, id: &str) -> Option<usize> {
+        self.nodes.get(id).map(|node| node.depth)
+    }
+
+    /// the parent of the funder with the `id`
+    pub fn parent(&self, id: &str) -> Option<&FunderNode> {
+        self.nodes.get(self.nodes.get(id)?.parent.as_ref()?)
+    }
+
+    /// the direct children of the funder with the `id`
+    pub fn children(&self, id: &str) -> Vec<&FunderNode> {
+        self.nodes
+            .get(id)
+            .map(|node| {
+                node.children
+                    .iter()
+                    .filter_map(|child| self.nodes.get(child))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// all ancestors of the funder with the `id`, starting with its parent
+    pub fn ancestors(&self, id: &str) -> Vec<&FunderNode> {
+        let mut ancestors = Vec::new();
+        let mut current = self.parent(id);
+        while let Some(node) = current {
+            ancestors.push(node);
+            current = self.parent(&node.id);
+        }
+        ancestors
+    }
+
+    /// all descendants of the funder with the `id` in depth first order
+    pub fn descendants(&self, id: &str) -> Vec<&FunderNode> {
+        let mut descendants = Vec::new();
+        let mut stack: Vec<_> = self.children(id).into_iter().rev().collect();
+        while let Some(node) = stack.pop() {
+            stack.extend(self.children(&node.id).into_iter().rev());
+            descendants.push(node);
+        }
+        descendants
+    }
+
+    /// all funders whose name matches `name`, ignoring case
+    pub fn find_by_name(&self, name: &str) -> Vec<&FunderNode> {
+        let name = name.to_lowercase();
+        let mut found: Vec<_> = self
+            .nodes
+            .values()
+            .filter(|node| {
+                node.name
+                    .as_ref()
+                    .map(|n| n.to_lowercase() == name)
+                    .unwrap_or_default()
+            })
+            .collect();
+        found.sort_by(|a, b| a.id.cmp(&b.id));
+        found
+    }
+
+    /// iterate over all funders of the tree in depth first order
+    pub fn iter(&self) -> impl Iterator<Item = &FunderNode> {
+        self.roots()
+            .flat_map(move |root| std::iter::once(root).chain(self.descendants(&root.id)))
+    }
+
+    /// the number of funders in the tree
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// whether the tree holds no funders
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+}
+
+impl Funder {
+    /// the `FunderTree` of this funder's `hierarchy`
+    pub fn tree(&self) -> FunderTree {
+        FunderTree::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::*;
+
+    #[test]
+    fn funder_tree() {
+        let funder_str = r#"{"hierarchy-names":{"100000016":"U.S. Department of Health and Human Services","100000002":"National Institutes of Health","100000025":"National Institute of Mental Health","100000026":"National Institute on Drug Abuse","100006545":"NIH Office of the Director"},"work-count":236402,"name":"National Institutes of Health","descendants":["100000025","100000026","100006545"],"descendant-work-count":502147,"id":"100000002","tokens":["national"],"replaces":[],"uri":"http:\/\/dx.doi.org\/10.13039\/100000002","hierarchy":{"100000016":{"100000002":{"100000025":{},"100000026":{"more":true},"100006545":{}}}},"alt-names":["NIH"],"location":"United States","replaced-by":[]}"#;
+
+        let funder: Funder = from_str(funder_str).unwrap();
+        let tree = funder.tree();
+
+        assert_eq!(5, tree.len());
+        let roots: Vec<_> = tree.roots().map(|n| n.id.as_str()).collect();
+        assert_eq!(vec!["100000016"], roots);
+        assert_eq!(Some(1), tree.depth("100000002"));
+        assert_eq!(Some(2), tree.depth("100000026"));
+        assert!(tree.get("100000026").unwrap().more);
+        assert_eq!(
+            Some("National Institutes of Health"),
+            tree.name("100000002")
+        );
+        assert_eq!("100000002", tree.parent("100000025").unwrap().id);
+        let children: Vec<_> = tree
+            .children("100000002")
+            .into_iter()
+            .map(|n| n.id.as_str())
+            .collect();
+        assert_eq!(vec!["100000025", "100000026", "100006545"], children);
+        let ancestors: Vec<_> = tree
+            .ancestors("100006545")
+            .into_iter()
+            .map(|n| n.id.as_str())
+            .collect();
+        assert_eq!(vec!["100000002", "100000016"], ancestors);
+        assert_eq!(4, tree.descendants("100000016").len());
+        assert_eq!(5, tree.iter().count());
+        assert_eq!(
+            "100000025",
+            tree.find_by_name("national institute of mental health")[0].id
+        );
+    }
+
+    #[test]
+    fn hierarchy_more_roundtrip() {
+        let hierarchy = r#"{"100000015":{"100006130":{"more":true},"100013165":{}}}"#;
+        let parsed: HashMap<String, HierarchyNode> = from_str(hierarchy).unwrap();
+
+        assert!(parsed["100000015"].children["100006130"].more);
+        assert_eq!(
+            parsed,
+            from_str::<HashMap<String, HierarchyNode>>(&to_string(&parsed).unwrap()).unwrap()
+        );
+    }
+}
@@ -2,21 +2,36 @@ use crate::query::facet::Facet;
 use crate::query::facet::FacetCount;
 use crate::query::Visibility;
 use crate::response::work::*;
-use serde::de::Deserializer;
+use serde::de::{self, DeserializeSeed, Deserializer, IgnoredAny, MapAccess, Visitor};
 use serde::{Deserialize, Serialize};
-use serde_json::{from_value, Value};
+use serde_json::{from_value, Map, Value};
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fmt;
-
+use std::marker::PhantomData;
+
+/// collects the fields of a response that are not explicitly modeled
+#[macro_use]
+mod extra;
+/// provides the types for a funder hierarchy
+pub mod funder;
+/// provides incremental decoding of large work lists
+pub mod stream;
 /// provides the types for a work response
 pub mod work;
 
+pub use crate::response::funder::{FunderNode, FunderTree, HierarchyNode};
+pub use crate::response::stream::WorkStream;
 pub use crate::response::work::{Work, WorkList};
 
 /// Represents the whole crossref response for a any request.
+///
+/// The type of the `message` is chosen by the caller: a `Response<WorkList>` decodes the
+/// message directly into a `WorkList` in a single pass, while the default `Response<Message>`
+/// decodes whatever variant is announced by the `message-type` of the response.
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "kebab-case")]
-pub struct Response {
+pub struct Response<T = Message> {
     /// the status of the request
     pub status: String,
     /// the type of the response message holds
@@ -24,8 +39,10 @@ pub struct Response {
     /// the version of the service created this message
     #[serde(default = "default_msg_version")]
     pub message_version: String,
-    /// the actual message of the response
-    pub message: Option<Message>,
+    /// the actual message of the response.
+    ///
+    /// For typed responses this is `None` if the `message_type` does not match `T`.
+    pub message: Option<T>,
 }
 
 /// at some routes the `msg_version` is missing, this returns the default version for a crossref response
@@ -49,7 +66,7 @@ macro_rules! impl_msg_helper {
     };
 }
 
-impl Response {
+impl Response<Message> {
     impl_msg_helper!(single:
         is_work_ageny -> WorkAgency,
         is_funder -> Funder,
@@ -75,116 +92,204 @@ impl Response {
     }
 }
 
-impl<'de> Deserialize<'de> for Response {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+/// A type that can be decoded from the `message` of a `Response`.
+///
+/// The `message-type` of the response is known before the `message` is decoded,
+/// so implementors can pick the target type without buffering the message.
+pub trait MessageItem: Sized {
+    /// decodes the message announced as `message_type`.
+    ///
+    /// Returns `None` if the message is `null` or if `message_type` can not be represented by `Self`,
+    /// in which case the message is skipped.
+    fn deserialize_message<'de, D>(
+        message_type: &MessageType,
+        deserializer: D,
+    ) -> Result<Option<Self>, D::Error>
     where
-        D: Deserializer<'de>,
-    {
-        #[derive(Deserialize)]
-        #[serde(rename_all = "kebab-case")]
-        struct ResponseFragment {
-            status: String,
-            message_type: MessageType,
-            #[serde(default = "default_msg_version")]
-            message_version: String,
-            message: Option<Value>,
-        }
+        D: Deserializer<'de>;
+}
 
-        #[derive(Deserialize)]
-        #[serde(rename_all = "kebab-case")]
-        struct ListResp {
-            #[serde(default)]
-            facets: FacetMap,
-            next_cursor: Option<String>,
-            total_results: usize,
-            items_per_page: Option<usize>,
-            query: Option<QueryResponse>,
-            items: Value,
+/// implements `MessageItem` for the types that represent exactly one `MessageType`
+macro_rules! impl_message_item {
+    ($($ty:ty => $ident:ident,)*) => {
+    $(
+        impl MessageItem for $ty {
+            fn deserialize_message<'de, D>(
+                message_type: &MessageType,
+                deserializer: D,
+            ) -> Result<Option<Self>, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                if *message_type == MessageType::$ident {
+                    Option::<$ty>::deserialize(deserializer)
+                } else {
+                    IgnoredAny::deserialize(deserializer)?;
+                    Ok(None)
+                }
+            }
         }
+    )+
+    };
+}
 
-        let fragment = ResponseFragment::deserialize(deserializer)?;
+impl_message_item!(
+    Vec<Failure> => ValidationFailure,
+    WorkAgency => WorkAgency,
+    Prefix => Prefix,
+    CrossrefType => Type,
+    TypeList => TypeList,
+    Work => Work,
+    WorkList => WorkList,
+    Member => Member,
+    MemberList => MemberList,
+    Journal => Journal,
+    JournalList => JournalList,
+    Funder => Funder,
+    FunderList => FunderList,
+);
 
+impl MessageItem for Message {
+    fn deserialize_message<'de, D>(
+        message_type: &MessageType,
+        deserializer: D,
+    ) -> Result<Option<Self>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
         macro_rules! msg_arm {
-            ($ident:ident, $value:expr) => {{
-                Message::$ident(
-                    ::serde_json::from_value($value).map_err(::serde::de::Error::custom)?,
-                )
-            }};
-            ($ident:ident, $value:expr, $ty:ty) => {{
-                let list_resp: ListResp =
-                    ::serde_json::from_value($value).map_err(::serde::de::Error::custom)?;
-                let items: Vec<$ty> = ::serde_json::from_value(list_resp.items)
-                    .map_err(::serde::de::Error::custom)?;
-                Message::$ident($ident {
-                    facets: list_resp.facets,
-                    total_results: list_resp.total_results,
-                    items_per_page: list_resp.items_per_page,
-                    query: list_resp.query,
-                    items,
-                })
-            }};
+            ($ident:ident) => {
+                Option::<_>::deserialize(deserializer)?.map(Message::$ident)
+            };
         }
 
-        fn work_list(msg: Value) -> Result<Message, serde_json::Error> {
-            let list_resp: ListResp = ::serde_json::from_value(msg)?;
-            let items: Vec<Work> = ::serde_json::from_value(list_resp.items)?;
-
-            Ok(Message::WorkList(WorkList {
-                facets: list_resp.facets,
-                total_results: list_resp.total_results,
-                items_per_page: list_resp.items_per_page,
-                query: list_resp.query,
-                items,
-                next_cursor: list_resp.next_cursor,
-            }))
+        Ok(match message_type {
+            MessageType::ValidationFailure => msg_arm!(ValidationFailure),
+            MessageType::WorkAgency => msg_arm!(WorkAgency),
+            MessageType::Prefix => msg_arm!(Prefix),
+            MessageType::Type => msg_arm!(Type),
+            MessageType::TypeList => msg_arm!(TypeList),
+            MessageType::Work => msg_arm!(Work),
+            MessageType::WorkList => msg_arm!(WorkList),
+            MessageType::Member => msg_arm!(Member),
+            MessageType::MemberList => msg_arm!(MemberList),
+            MessageType::Journal => msg_arm!(Journal),
+            MessageType::JournalList => msg_arm!(JournalList),
+            MessageType::Funder => msg_arm!(Funder),
+            MessageType::FunderList => msg_arm!(FunderList),
+            MessageType::RouteNotFound => {
+                Option::<IgnoredAny>::deserialize(deserializer)?.map(|_| Message::RouteNotFound)
+            }
+        })
+    }
+}
+
+/// decodes the `message` of a response once the `message-type` is known
+struct MessageSeed<'a, T> {
+    message_type: &'a MessageType,
+    item: PhantomData<T>,
+}
+
+impl<'de, 'a, T: MessageItem> DeserializeSeed<'de> for MessageSeed<'a, T> {
+    type Value = Option<T>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        T::deserialize_message(self.message_type, deserializer)
+    }
+}
+
+impl<'de, T: MessageItem> Deserialize<'de> for Response<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ResponseVisitor<T>(PhantomData<T>);
+
+        impl<'de, T: MessageItem> Visitor<'de> for ResponseVisitor<T> {
+            type Value = Response<T>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a crossref response")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut status = None;
+                let mut message_type = None;
+                let mut message_version = None;
+                let mut message = None;
+                // only used if the `message` precedes the `message-type`
+                let mut buffered: Option<Value> = None;
+
+                while let Some(key) = map.next_key::<Cow<str>>()? {
+                    match key.as_ref() {
+                        "status" => status = Some(map.next_value()?),
+                        "message-type" => message_type = Some(map.next_value()?),
+                        "message-version" => message_version = Some(map.next_value()?),
+                        "message" => match &message_type {
+                            Some(message_type) => {
+                                message = map.next_value_seed(MessageSeed {
+                                    message_type,
+                                    item: PhantomData,
+                                })?
+                            }
+                            None => buffered = Some(map.next_value()?),
+                        },
+                        _ => {
+                            map.next_value::<IgnoredAny>()?;
+                        }
+                    }
+                }
+
+                let status = status.ok_or_else(|| de::Error::missing_field("status"))?;
+                let message_type: MessageType =
+                    message_type.ok_or_else(|| de::Error::missing_field("message-type"))?;
+                if let Some(value) = buffered {
+                    message =
+                        T::deserialize_message(&message_type, value).map_err(de::Error::custom)?;
+                }
+
+                Ok(Response {
+                    status,
+                    message_type,
+                    message_version: message_version.unwrap_or_else(default_msg_version),
+                    message,
+                })
+            }
         }
 
-        let message = match fragment.message {
-            Some(msg) => Some(match &fragment.message_type {
-                MessageType::ValidationFailure => msg_arm!(ValidationFailure, msg),
-                MessageType::WorkAgency => msg_arm!(WorkAgency, msg),
-                MessageType::Prefix => msg_arm!(Prefix, msg),
-                MessageType::Type => msg_arm!(Type, msg),
-                MessageType::TypeList => msg_arm!(TypeList, msg, CrossrefType),
-                MessageType::Work => msg_arm!(Work, msg),
-                MessageType::WorkList => work_list(msg).map_err(::serde::de::Error::custom)?,
-                MessageType::Member => msg_arm!(Member, msg),
-                MessageType::MemberList => msg_arm!(MemberList, msg, Member),
-                MessageType::Journal => msg_arm!(Journal, msg),
-                MessageType::JournalList => msg_arm!(JournalList, msg, Journal),
-                MessageType::Funder => msg_arm!(Funder, msg),
-                MessageType::FunderList => msg_arm!(FunderList, msg, Funder),
-                MessageType::RouteNotFound => Message::RouteNotFound,
-            }),
-            _ => None,
-        };
-        Ok(Response {
-            status: fragment.status,
-            message_type: fragment.message_type,
-            message_version: fragment.message_version,
-            message,
-        })
+        deserializer.deserialize_map(ResponseVisitor(PhantomData))
     }
 }
 
 macro_rules! impl_list_response {
     ($($name:ident<$ty:ty>,)*) => {
     $(
-        #[derive(Debug, Clone, Deserialize, Serialize)]
-        #[serde(rename_all = "kebab-case")]
-        #[allow(missing_docs)]
-        pub struct $name {
-             /// if facets where part in the request they are also included in the response
-            #[serde(default)]
-            pub facets: FacetMap,
-            /// the number of items that match the response
-            pub total_results: usize,
-            /// crossref responses for large number of items are divided in pages, number of elements to expect in `items`
-            pub items_per_page: Option<usize>,
-            /// if a query was set in the request, this will also be part in the response
-            pub query: Option<QueryResponse>,
-            /// all actual message items of the response
-            pub items: Vec<$ty>,
+        extra_fields! {
+            #[derive(Debug, Clone)]
+            #[serde(rename_all = "kebab-case")]
+            #[allow(missing_docs)]
+            pub struct $name {
+                 /// if facets where part in the request they are also included in the response
+                #[serde(default)]
+                pub facets: FacetMap,
+                /// the number of items that match the response
+                pub total_results: usize,
+                /// crossref responses for large number of items are divided in pages, number of elements to expect in `items`
+                pub items_per_page: Option<usize>,
+                /// if a query was set in the request, this will also be part in the response
+                pub query: Option<QueryResponse>,
+                /// all actual message items of the response
+                pub items: Vec<$ty>,
+                /// fields of the list message that are not explicitly modeled
+                #[serde(flatten, skip_deserializing)]
+                pub extra: Map<String, Value>,
+            }
         }
     )+
     };
@@ -197,6 +302,10 @@ impl_list_response!(
 );
 
 /// the different payloads of a response
+///
+/// Which variant a message holds is determined by the `message-type` of the `Response`,
+/// see [`MessageItem`].
+/// A `Message` deserialized on its own has no `message-type` to go by and takes the first variant that matches.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(untagged)]
 pub enum Message {
@@ -230,12 +339,17 @@ pub enum Message {
     FunderList(FunderList),
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
-#[allow(missing_docs)]
-pub struct CrossrefType {
-    pub id: String,
-    /// Name of work's publisher
-    pub label: String,
+extra_fields! {
+    #[derive(Debug, Clone)]
+    #[allow(missing_docs)]
+    pub struct CrossrefType {
+        pub id: String,
+        /// Name of work's publisher
+        pub label: String,
+        /// fields of the type that are not explicitly modeled
+        #[serde(flatten, skip_deserializing)]
+        pub extra: Map<String, Value>,
+    }
 }
 
 impl Into<CrossrefType> for crate::query::types::Type {
@@ -243,27 +357,50 @@ impl Into<CrossrefType> for crate::query::types::Type {
         CrossrefType {
             id: self.id().to_string(),
             label: self.label().to_string(),
+            extra: Map::new(),
         }
     }
 }
 
-/// response item for the `/works/{id}/agency` route
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct WorkAgency {
-    /// the DOI fo the work that belongs to the `agency`
-    #[serde(rename = "DOI")]
-    doi: String,
-    /// the agency that owns the work with `doi`
-    agency: Agency,
+extra_fields! {
+    /// response item for the `/works/{id}/agency` route
+    #[derive(Debug, Clone)]
+    pub struct WorkAgency {
+        /// the DOI fo the work that belongs to the `agency`
+        #[serde(rename = "DOI")]
+        doi: String,
+        /// the agency that owns the work with `doi`
+        agency: Agency,
+        /// fields of the agency lookup that are not explicitly modeled
+        #[serde(flatten, skip_deserializing)]
+        pub extra: Map<String, Value>,
+    }
 }
 
-/// response item for the `/prefix/{id}/` route
-#[derive(Debug, Clone, Deserialize, Serialize)]
-#[allow(missing_docs)]
-pub struct Prefix {
-    pub member: String,
-    pub name: String,
-    pub prefix: String,
+impl WorkAgency {
+    /// the DOI of the work
+    pub fn doi(&self) -> &str {
+        &self.doi
+    }
+
+    /// the agency the DOI is registered with
+    pub fn agency(&self) -> &Agency {
+        &self.agency
+    }
+}
+
+extra_fields! {
+    /// response item for the `/prefix/{id}/` route
+    #[derive(Debug, Clone)]
+    #[allow(missing_docs)]
+    pub struct Prefix {
+        pub member: String,
+        pub name: String,
+        pub prefix: String,
+        /// fields of the prefix that are not explicitly modeled
+        #[serde(flatten, skip_deserializing)]
+        pub extra: Map<String, Value>,
+    }
 }
 
 /// all possible `message-type` of a response
@@ -315,14 +452,19 @@ impl fmt::Display for MessageType {
     }
 }
 
-/// if a query was set in the request then it is also part of the result
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
-#[serde(rename_all = "kebab-case")]
-pub struct QueryResponse {
-    /// from which the returned items start
-    pub start_index: usize,
-    /// the terms that were initially set in the request query
-    pub search_terms: Option<String>,
+extra_fields! {
+    /// if a query was set in the request then it is also part of the result
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[serde(rename_all = "kebab-case")]
+    pub struct QueryResponse {
+        /// from which the returned items start
+        pub start_index: usize,
+        /// the terms that were initially set in the request query
+        pub search_terms: Option<String>,
+        /// all fields of the message that are not explicitly modeled
+        #[serde(flatten, skip_deserializing)]
+        pub extra: Map<String, Value>,
+    }
 }
 
 // TODO impl CrossrefRoute for QueryResponse
@@ -330,142 +472,289 @@ pub struct QueryResponse {
 /// facets are returned as map
 pub type FacetMap = HashMap<String, FacetItem>;
 
-/// if a `facet` was set in a request `FacetMap` will be  in a `List` response as additional field of the message
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
-#[serde(rename_all = "kebab-case")]
-pub struct FacetItem {
-    /// represents the length of `values`
-    pub value_count: usize,
-    /// contains the
-    pub values: HashMap<String, usize>,
+extra_fields! {
+    /// if a `facet` was set in a request `FacetMap` will be  in a `List` response as additional field of the message
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[serde(rename_all = "kebab-case")]
+    pub struct FacetItem {
+        /// represents the length of `values`
+        pub value_count: usize,
+        /// contains the
+        pub values: HashMap<String, usize>,
+        /// all fields of the message that are not explicitly modeled
+        #[serde(flatten, skip_deserializing)]
+        pub extra: Map<String, Value>,
+    }
 }
 
-/// response item if a request could be processed
-#[derive(Debug, Clone, Deserialize, Serialize)]
-#[serde(rename_all = "kebab-case")]
-pub struct Failure {
-    /// identifier for a failue like `parameter-not-found`
-    #[serde(rename = "type")]
-    type_: String,
-    /// value that caused the failure
-    value: String,
-    /// the message from the server
-    message: String,
-}
-
-/// response item for the `/funder/{id}` route
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
-#[serde(rename_all = "kebab-case", default)]
-#[allow(missing_docs)]
-pub struct Funder {
-    pub hierarchy_names: HashMap<String, Option<String>>,
-    pub hierarchy: HashMap<String, HashMap<String, HashMap<String, bool>>>,
-    pub id: String,
-    pub location: String,
-    pub work_count: Option<usize>,
-    pub descendant_work_count: Option<usize>,
-    pub descendants: Vec<String>,
-    pub name: String,
-    pub alt_names: Vec<String>,
-    pub uri: String,
-    pub replaces: Vec<String>,
-    pub replaced_by: Vec<String>,
-    pub tokens: Vec<String>,
-}
-
-/// response item for the `/member/{id}` route
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
-#[serde(rename_all = "kebab-case", default)]
-#[allow(missing_docs)]
-pub struct Member {
-    pub primary_name: String,
-    pub last_status_check_time: usize,
-    pub counts: Counts,
-    pub breakdowns: Breakdowns,
-    pub prefixes: Vec<String>,
-    pub coverage: Coverage,
-    pub prefix: Vec<RefPrefix>,
-    pub id: usize,
-    pub tokens: Vec<String>,
-    pub counts_type: HashMap<String, HashMap<String, usize>>,
-    pub coverage_type: Value,
-    pub flags: HashMap<String, bool>,
-    pub location: String,
-    pub names: Vec<String>,
-}
-
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
-#[serde(rename_all = "kebab-case", default)]
-#[allow(missing_docs)]
-pub struct Counts {
-    pub total_dois: usize,
-    pub current_dois: usize,
-    pub backfile_dois: usize,
+impl FacetItem {
+    /// the values with their counts, the most frequent first and ties sorted by value
+    pub fn sorted_values(&self) -> Vec<(&str, usize)> {
+        let mut values: Vec<_> = self
+            .values
+            .iter()
+            .map(|(value, count)| (value.as_str(), *count))
+            .collect();
+        values.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        values
+    }
 }
 
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
-#[serde(rename_all = "kebab-case", default)]
-#[allow(missing_docs)]
-pub struct Breakdowns {
-    pub dois_by_issued_year: Vec<Vec<u32>>,
+extra_fields! {
+    /// response item if a request could be processed
+    #[derive(Debug, Clone)]
+    #[serde(rename_all = "kebab-case")]
+    pub struct Failure {
+        /// identifier for a failue like `parameter-not-found`
+        #[serde(rename = "type")]
+        type_: String,
+        /// value that caused the failure
+        value: String,
+        /// the message from the server
+        message: String,
+        /// all fields of the message that are not explicitly modeled
+        #[serde(flatten, skip_deserializing)]
+        pub extra: Map<String, Value>,
+    }
 }
 
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
-#[serde(rename_all = "kebab-case", default)]
-#[allow(missing_docs)]
-pub struct Coverage {
-    pub affiliations_current: f32,
-    pub similarity_checking_current: f32,
-    pub funders_backfile: f32,
-    pub licenses_backfile: f32,
-    pub funders_current: f32,
-    pub affiliations_backfile: f32,
-    pub resource_links_backfile: f32,
-    pub orcids_backfile: f32,
-    pub update_policies_current: f32,
-    pub open_references_backfile: f32,
-    pub orcids_current: f32,
-    pub similarity_checking_backfile: f32,
-    pub references_backfile: f32,
-    pub award_numbers_backfile: f32,
-    pub update_policies_backfile: f32,
-    pub licenses_current: f32,
-    pub award_numbers_current: f32,
-    pub abstracts_backfile: f32,
-    pub resource_links_current: f32,
-    pub abstracts_current: f32,
-    pub open_references_current: f32,
-    pub references_current: f32,
-}
-
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
-#[serde(rename_all = "kebab-case", default)]
-#[allow(missing_docs)]
-pub struct RefPrefix {
-    pub value: String,
-    pub name: String,
-    pub public_references: bool,
-    pub reference_visibility: Option<Visibility>,
+extra_fields! {
+    /// response item for the `/funder/{id}` route
+    #[derive(Debug, Clone, Default)]
+    #[serde(rename_all = "kebab-case", default)]
+    #[allow(missing_docs)]
+    pub struct Funder {
+        pub hierarchy_names: HashMap<String, Option<String>>,
+        pub hierarchy: HashMap<String, HierarchyNode>,
+        pub id: String,
+        pub location: String,
+        pub work_count: Option<usize>,
+        pub descendant_work_count: Option<usize>,
+        pub descendants: Vec<String>,
+        pub name: String,
+        pub alt_names: Vec<String>,
+        pub uri: String,
+        pub replaces: Vec<String>,
+        pub replaced_by: Vec<String>,
+        pub tokens: Vec<String>,
+        /// fields of the funder that are not explicitly modeled
+        #[serde(flatten, skip_deserializing)]
+        pub extra: Map<String, Value>,
+    }
 }
 
-/// response item for the `/journal/{id}` route
-#[derive(Debug, Clone, Deserialize, Serialize)]
-#[serde(rename_all = "kebab-case")]
-#[allow(missing_docs)]
-pub struct Journal {
-    /// could not determine type, possible PartialDateParts
-    pub last_status_check_time: Option<Value>,
-    pub counts: Option<usize>,
-    pub breakdowns: Option<Value>,
-    pub publisher: Option<String>,
-    pub coverage: Option<Value>,
-    pub title: Option<String>,
-    pub subjects: Vec<Value>,
-    pub coverage_type: Option<Value>,
-    pub flags: Option<Value>,
-    #[serde(rename = "ISSN")]
-    pub issn: Vec<String>,
-    pub issn_type: Vec<String>,
+extra_fields! {
+    /// response item for the `/member/{id}` route
+    #[derive(Debug, Clone, Default)]
+    #[serde(rename_all = "kebab-case", default)]
+    #[allow(missing_docs)]
+    pub struct Member {
+        pub primary_name: String,
+        pub last_status_check_time: usize,
+        pub counts: Counts,
+        pub breakdowns: Breakdowns,
+        pub prefixes: Vec<String>,
+        pub coverage: Coverage,
+        pub prefix: Vec<RefPrefix>,
+        pub id: usize,
+        pub tokens: Vec<String>,
+        pub counts_type: HashMap<String, HashMap<String, usize>>,
+        pub coverage_type: Value,
+        pub flags: HashMap<String, bool>,
+        pub location: String,
+        pub names: Vec<String>,
+        /// fields of the member record that are not explicitly modeled
+        #[serde(flatten, skip_deserializing)]
+        pub extra: Map<String, Value>,
+    }
+}
+
+extra_fields! {
+    #[derive(Debug, Clone, Default)]
+    #[serde(rename_all = "kebab-case", default)]
+    #[allow(missing_docs)]
+    pub struct Counts {
+        pub total_dois: usize,
+        pub current_dois: usize,
+        pub backfile_dois: usize,
+        /// all fields of the message that are not explicitly modeled
+        #[serde(flatten, skip_deserializing)]
+        pub extra: Map<String, Value>,
+    }
+}
+
+extra_fields! {
+    #[derive(Debug, Clone, Default)]
+    #[serde(rename_all = "kebab-case", default)]
+    #[allow(missing_docs)]
+    pub struct Breakdowns {
+        pub dois_by_issued_year: Vec<Vec<u32>>,
+        /// all fields of the message that are not explicitly modeled
+        #[serde(flatten, skip_deserializing)]
+        pub extra: Map<String, Value>,
+    }
+}
+
+extra_fields! {
+    #[derive(Debug, Clone, Default)]
+    #[serde(rename_all = "kebab-case", default)]
+    #[allow(missing_docs)]
+    pub struct Coverage {
+        pub affiliations_current: f32,
+        pub similarity_checking_current: f32,
+        pub funders_backfile: f32,
+        pub licenses_backfile: f32,
+        pub funders_current: f32,
+        pub affiliations_backfile: f32,
+        pub resource_links_backfile: f32,
+        pub orcids_backfile: f32,
+        pub update_policies_current: f32,
+        pub open_references_backfile: f32,
+        pub orcids_current: f32,
+        pub similarity_checking_backfile: f32,
+        pub references_backfile: f32,
+        pub award_numbers_backfile: f32,
+        pub update_policies_backfile: f32,
+        pub licenses_current: f32,
+        pub award_numbers_current: f32,
+        pub abstracts_backfile: f32,
+        pub resource_links_current: f32,
+        pub abstracts_current: f32,
+        pub open_references_current: f32,
+        pub references_current: f32,
+        /// all fields of the message that are not explicitly modeled
+        #[serde(flatten, skip_deserializing)]
+        pub extra: Map<String, Value>,
+    }
+}
+
+extra_fields! {
+    #[derive(Debug, Clone, Default)]
+    #[serde(rename_all = "kebab-case", default)]
+    #[allow(missing_docs)]
+    pub struct RefPrefix {
+        pub value: String,
+        pub name: String,
+        pub public_references: bool,
+        pub reference_visibility: Option<Visibility>,
+        /// all fields of the message that are not explicitly modeled
+        #[serde(flatten, skip_deserializing)]
+        pub extra: Map<String, Value>,
+    }
+}
+
+extra_fields! {
+    /// response item for the `/journal/{id}` route
+    #[derive(Debug, Clone, Default)]
+    #[serde(rename_all = "kebab-case", default)]
+    #[allow(missing_docs)]
+    pub struct Journal {
+        pub last_status_check_time: Option<usize>,
+        pub counts: Option<Counts>,
+        pub breakdowns: Option<Breakdowns>,
+        pub publisher: Option<String>,
+        pub coverage: Option<Coverage>,
+        pub title: Option<String>,
+        pub subjects: Vec<Subject>,
+        pub coverage_type: Option<CoverageType>,
+        pub flags: Option<JournalFlags>,
+        #[serde(rename = "ISSN")]
+        pub issn: Vec<String>,
+        pub issn_type: Vec<ISSN>,
+        /// fields of the journal record that are not explicitly modeled
+        #[serde(flatten, skip_deserializing)]
+        pub extra: Map<String, Value>,
+    }
+}
+
+extra_fields! {
+    /// a subject area of a `Journal`
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Subject {
+        /// the display name of the subject
+        pub name: String,
+        /// the [ASJC](https://service.elsevier.com/app/answers/detail/a_id/15181/supporthub/scopus/) code of the subject
+        #[serde(rename = "ASJC")]
+        pub asjc: Option<u32>,
+        /// all fields of the message that are not explicitly modeled
+        #[serde(flatten, skip_deserializing)]
+        pub extra: Map<String, Value>,
+    }
+}
+
+extra_fields! {
+    /// coverage ratios of a `Journal`, split by the time period of the content
+    #[derive(Debug, Clone, Default)]
+    #[serde(default)]
+    pub struct CoverageType {
+        /// coverage across all content
+        pub all: Option<CoverageRatios>,
+        /// coverage of material published more than two years ago
+        pub backfile: Option<CoverageRatios>,
+        /// coverage of material published within the last two years
+        pub current: Option<CoverageRatios>,
+        /// all fields of the message that are not explicitly modeled
+        #[serde(flatten, skip_deserializing)]
+        pub extra: Map<String, Value>,
+    }
+}
+
+extra_fields! {
+    /// ratio of items that carry a certain kind of metadata, between `0.0` and `1.0`
+    #[derive(Debug, Clone, Default)]
+    #[serde(rename_all = "kebab-case", default)]
+    #[allow(missing_docs)]
+    pub struct CoverageRatios {
+        pub affiliations: f32,
+        pub abstracts: f32,
+        pub orcids: f32,
+        pub licenses: f32,
+        pub references: f32,
+        pub funders: f32,
+        pub similarity_checking: f32,
+        pub award_numbers: f32,
+        pub update_policies: f32,
+        pub resource_links: f32,
+        /// all fields of the message that are not explicitly modeled
+        #[serde(flatten, skip_deserializing)]
+        pub extra: Map<String, Value>,
+    }
+}
+
+extra_fields! {
+    /// which kind of metadata the publisher deposits for a `Journal`
+    #[derive(Debug, Clone, Default)]
+    #[serde(rename_all = "kebab-case", default)]
+    #[allow(missing_docs)]
+    pub struct JournalFlags {
+        pub deposits: bool,
+        pub deposits_articles: bool,
+        pub deposits_abstracts_current: bool,
+        pub deposits_abstracts_backfile: bool,
+        pub deposits_orcids_current: bool,
+        pub deposits_orcids_backfile: bool,
+        pub deposits_affiliations_current: bool,
+        pub deposits_affiliations_backfile: bool,
+        pub deposits_update_policies_current: bool,
+        pub deposits_update_policies_backfile: bool,
+        pub deposits_similarity_checking_current: bool,
+        pub deposits_similarity_checking_backfile: bool,
+        pub deposits_award_numbers_current: bool,
+        pub deposits_award_numbers_backfile: bool,
+        pub deposits_resource_links_current: bool,
+        pub deposits_resource_links_backfile: bool,
+        pub deposits_references_current: bool,
+        pub deposits_references_backfile: bool,
+        pub deposits_open_references_current: bool,
+        pub deposits_open_references_backfile: bool,
+        pub deposits_funders_current: bool,
+        pub deposits_funders_backfile: bool,
+        pub deposits_licenses_current: bool,
+        pub deposits_licenses_backfile: bool,
+        /// all fields of the message that are not explicitly modeled
+        #[serde(flatten, skip_deserializing)]
+        pub extra: Map<String, Value>,
+    }
 }
 
 #[cfg(test)]
@@ -494,7 +783,17 @@ mod tests {
       }
     }"#;
 
-        let _: FacetMap = from_str(facets).unwrap();
+        let facets: FacetMap = from_str(facets).unwrap();
+        assert_eq!(
+            vec![
+                ("of", 177247),
+                ("university", 147649),
+                ("department", 128741),
+                ("and", 102652),
+                ("medicine", 96232)
+            ],
+            facets["affiliation"].sorted_values()
+        );
     }
 
     #[test]
@@ -611,6 +910,83 @@ mod tests {
         assert!(journal.is_journal());
     }
 
+    #[test]
+    fn journal_typed_msg_deserialize() {
+        let journal_str = r#"{"status":"ok","message-type":"journal","message-version":"1.0.0","message":{"last-status-check-time":1551766727771,"counts":{"current-dois":12,"backfile-dois":30,"total-dois":42},"breakdowns":{"dois-by-issued-year":[[2018,12],[2017,30]]},"publisher":"American Psychological Association (APA)","coverage":{"affiliations-current":0.5,"abstracts-current":0.25,"references-current":1.0},"title":"American Psychologist","subjects":[{"ASJC":3200,"name":"General Psychology"}],"coverage-type":{"all":{"last-status-check-time":1551766727771,"affiliations":0.1,"abstracts":0.2,"orcids":0.3,"licenses":1.0,"references":0.9,"funders":0.0,"similarity-checking":1.0,"award-numbers":0.0,"ror-ids":0.0,"update-policies":0.0,"resource-links":1.0,"descriptions":0.0},"backfile":null,"current":{"references":1.0}},"flags":{"deposits-abstracts-current":true,"deposits":true,"deposits-articles":true,"deposits-orcids-backfile":false},"ISSN":["0003-066X","1935-990X"],"issn-type":[{"value":"0003-066X","type":"print"},{"value":"1935-990X","type":"electronic"}]}}"#;
+
+        let resp: Response = from_str(journal_str).unwrap();
+        let journal = match resp.message {
+            Some(Message::Journal(journal)) => journal,
+            _ => panic!("expected a journal message"),
+        };
+
+        let counts = journal.counts.unwrap();
+        assert_eq!(42, counts.total_dois);
+        assert_eq!(12, counts.current_dois);
+        assert_eq!(30, counts.backfile_dois);
+        assert_eq!(
+            vec![vec![2018, 12], vec![2017, 30]],
+            journal.breakdowns.unwrap().dois_by_issued_year
+        );
+        assert_eq!(0.5, journal.coverage.unwrap().affiliations_current);
+        assert_eq!(Some(3200), journal.subjects[0].asjc);
+        let coverage_type = journal.coverage_type.unwrap();
+        assert_eq!(0.9, coverage_type.all.unwrap().references);
+        assert!(coverage_type.backfile.is_none());
+        let flags = journal.flags.unwrap();
+        assert!(flags.deposits_abstracts_current);
+        assert!(!flags.deposits_orcids_backfile);
+        assert_eq!("print", journal.issn_type[0].type_);
+    }
+
+    #[test]
+    fn typed_msg_deserialize() {
+        let type_list_str = r#"{"status":"ok","message-type":"type-list","message-version":"1.0.0","message":{"total-results":27,"items":[{"id":"book-section","label":"Book Section"},{"id":"monograph","label":"Monograph"}]}}"#;
+
+        let type_list: Response<TypeList> = from_str(type_list_str).unwrap();
+        assert_eq!(2, type_list.message.unwrap().items.len());
+
+        let mismatch: Response<WorkList> = from_str(type_list_str).unwrap();
+        assert_eq!(MessageType::TypeList, mismatch.message_type);
+        assert!(mismatch.message.is_none());
+    }
+
+    #[test]
+    fn msg_before_msg_type_deserialize() {
+        let type_str = r#"{"message":{"id":"book-section","label":"Book Section"},"status":"ok","message-type":"type"}"#;
+
+        let type_: Response = from_str(type_str).unwrap();
+        assert!(type_.is_type());
+        assert_eq!("1.0.0", type_.message_version);
+
+        let typed: Response<CrossrefType> = from_str(type_str).unwrap();
+        assert_eq!("book-section", typed.message.unwrap().id);
+    }
+
+    #[test]
+    fn msg_roundtrip() {
+        let type_list_str = r#"{"status":"ok","message-type":"type-list","message-version":"1.0.0","message":{"total-results":1,"items":[{"id":"book-section","label":"Book Section"}]}}"#;
+
+        let type_list: Response = from_str(type_list_str).unwrap();
+        let roundtrip: Response = from_str(&to_string(&type_list).unwrap()).unwrap();
+        assert!(roundtrip.is_type_list());
+
+        let route_not_found: Response =
+            from_str(r#"{"status":"error","message-type":"route-not-found","message":null}"#)
+                .unwrap();
+        assert!(route_not_found.message.is_none());
+    }
+
+    #[test]
+    fn untyped_msg_deserialize() {
+        let prefix_str = r#"{"member":"http:\/\/id.crossref.org\/member\/78","name":"Elsevier BV","prefix":"http:\/\/id.crossref.org\/prefix\/10.1016"}"#;
+
+        match from_str(prefix_str).unwrap() {
+            Message::Prefix(prefix) => assert_eq!("Elsevier BV", prefix.name),
+            msg => panic!("expected a prefix message, got {:?}", msg),
+        }
+    }
+
     #[test]
     fn type_list_msg_deserialize() {
         let type_list_str = r#"{"status":"ok","message-type":"type-list","message-version":"1.0.0","message":{"total-results":27,"items":[{"id":"book-section","label":"Book Section"},{"id":"monograph","label":"Monograph"}]}}"#;
@@ -0,0 +1,393 @@
+use crate::error::{ErrorKind, Result};
+use crate::response::{MessageType, Work};
+use serde::de::{DeserializeOwned, Error as DeError};
+use std::io::{self, BufRead, BufReader, Read};
+
+/// Streams the `Work` items of a `work-list` response one at a time.
+///
+/// Instead of reading the whole response into memory, only a single item is buffered at a time.
+/// The other fields of the message are recorded as soon as they were read,
+/// crossref usually sends `next-cursor` and `total-results` ahead of the `items`.
+///
+/// # Example
+///
+/// ```edition2018
+/// use crossref::{Crossref, WorksQuery};
+/// # fn run() -> Result<(), crossref::Error> {
+/// let client = Crossref::builder().build()?;
+///
+/// let mut stream = client.works_stream(WorksQuery::new("Machine Learning").new_cursor())?;
+/// while let Some(work) = stream.next() {
+///     println!("{}", work?.doi);
+/// }
+/// println!("{:?} {:?}", stream.total_results(), stream.next_cursor());
+/// # Ok(())
+/// # }
+/// ```
+pub struct WorkStream<R> {
+    /// the scanner over the response body
+    scanner: Scanner<BufReader<R>>,
+    /// where the stream currently is
+    state: State,
+    /// buffer for the raw bytes of a single value
+    buf: Vec<u8>,
+    /// the number of items that match the query
+    total_results: Option<usize>,
+    /// number of elements to expect in the page
+    items_per_page: Option<usize>,
+    /// deep page through `/works` result sets
+    next_cursor: Option<String>,
+}
+
+/// the position of the `WorkStream` in the response
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// nothing was read yet
+    Start,
+    /// inside the `items` array, before the first item
+    FirstItem,
+    /// inside the `items` array, after an item
+    NextItem,
+    /// the whole response was read or an error occurred
+    Done,
+}
+
+impl<R: Read> WorkStream<R> {
+    /// creates a new stream over a `work-list` response body
+    pub fn new(body: R) -> Self {
+        WorkStream {
+            scanner: Scanner::new(BufReader::new(body)),
+            state: State::Start,
+            buf: Vec::new(),
+            total_results: None,
+            items_per_page: None,
+            next_cursor: None,
+        }
+    }
+
+    /// the number of items that match the query, once it was read
+    pub fn total_results(&self) -> Option<usize> {
+        self.total_results
+    }
+
+    /// the number of items to expect in this page, once it was read
+    pub fn items_per_page(&self) -> Option<usize> {
+        self.items_per_page
+    }
+
+    /// the cursor to request the next page with, once it was read
+    pub fn next_cursor(&self) -> Option<&str> {
+        self.next_cursor.as_deref()
+    }
+
+    /// reads the top level response until the first item of the `items` array
+    fn start(&mut self) -> Result<()> {
+        self.scanner.expect(b'{')?;
+        while let Some(key) = self.scanner.next_key(&mut self.buf)? {
+            match key.as_str() {
+                "message-type" => {
+                    let got: MessageType = self.scanner.value(&mut self.buf)?;
+                    if got != MessageType::WorkList {
+                        return Err(ErrorKind::UnexpectedItem {
+                            expected: MessageType::WorkList,
+                            got,
+                        }
+                        .into());
+                    }
+                }
+                "message" => {
+                    if self.scanner.peek_token()? == b'n' {
+                        // a `null` message holds no items
+                        self.scanner.skip_value(&mut self.buf)?;
+                        break;
+                    }
+                    self.scanner.expect(b'{')?;
+                    if self.message_fields()? {
+                        return Ok(());
+                    }
+                }
+                _ => self.scanner.skip_value(&mut self.buf)?,
+            }
+        }
+        Err(ErrorKind::MissingMessage {
+            expected: MessageType::WorkList,
+        }
+        .into())
+    }
+
+    /// reads the fields of the message, returns `true` if the start of the `items` array was reached
+    fn message_fields(&mut self) -> Result<bool> {
+        while let Some(key) = self.scanner.next_key(&mut self.buf)? {
+            match key.as_str() {
+                "total-results" => self.total_results = self.scanner.value(&mut self.buf)?,
+                "items-per-page" => self.items_per_page = self.scanner.value(&mut self.buf)?,
+                "next-cursor" => self.next_cursor = self.scanner.value(&mut self.buf)?,
+                "items" => {
+                    self.scanner.expect(b'[')?;
+                    return Ok(true);
+                }
+                _ => self.scanner.skip_value(&mut self.buf)?,
+            }
+        }
+        Ok(false)
+    }
+
+    /// reads the next item, or the remaining fields after the `items` array
+    fn next_item(&mut self) -> Result<Option<Work>> {
+        if self.state == State::Start {
+            self.start()?;
+            self.state = State::FirstItem;
+        }
+        let has_item = match self.scanner.peek_token()? {
+            b']' => false,
+            b',' if self.state == State::NextItem => {
+                self.scanner.expect(b',')?;
+                true
+            }
+            _ if self.state == State::FirstItem => true,
+            token => return Err(self.scanner.unexpected(token)),
+        };
+        if has_item {
+            let work = self.scanner.value(&mut self.buf)?;
+            self.state = State::NextItem;
+            return Ok(Some(work));
+        }
+        self.scanner.expect(b']')?;
+        // pick up the fields crossref sends after the items
+        self.message_fields()?;
+        self.state = State::Done;
+        Ok(None)
+    }
+}
+
+impl<R: Read> Iterator for WorkStream<R> {
+    type Item = Result<Work>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.state == State::Done {
+            return None;
+        }
+        match self.next_item() {
+            Ok(item) => item.map(Ok),
+            Err(err) => {
+                self.state = State::Done;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+/// Scans a json document and copies out the raw bytes of single values
+struct Scanner<R> {
+    reader: R,
+}
+
+impl<R: BufRead> Scanner<R> {
+    fn new(reader: R) -> Self {
+        Scanner { reader }
+    }
+
+    /// the next byte without consuming it
+    fn peek(&mut self) -> Result<Option<u8>> {
+        let buf = self.reader.fill_buf().map_err(serde_json::Error::io)?;
+        Ok(buf.first().cloned())
+    }
+
+    /// consumes the next byte, fails at the end of the input
+    fn bump(&mut self) -> Result<u8> {
+        let byte = self.peek()?.ok_or_else(eof)?;
+        self.reader.consume(1);
+        Ok(byte)
+    }
+
+    /// the next byte that is not whitespace, without consuming it
+    fn peek_token(&mut self) -> Result<u8> {
+        while let Some(byte) = self.peek()? {
+            if byte.is_ascii_whitespace() {
+                self.reader.consume(1);
+            } else {
+                return Ok(byte);
+            }
+        }
+        Err(eof())
+    }
+
+    /// consumes the next token, which must be `token`
+    fn expect(&mut self, token: u8) -> Result<()> {
+        match self.peek_token()? {
+            byte if byte == token => {
+                self.reader.consume(1);
+                Ok(())
+            }
+            byte => Err(self.unexpected(byte)),
+        }
+    }
+
+    fn unexpected(&self, byte: u8) -> crate::Error {
+        serde_json::Error::custom(format!("unexpected character `{}`", byte as char)).into()
+    }
+
+    /// reads the next key of an object, `None` if the end of the object was reached
+    fn next_key(&mut self, buf: &mut Vec<u8>) -> Result<Option<String>> {
+        match self.peek_token()? {
+            b'}' => {
+                self.reader.consume(1);
+                return Ok(None);
+            }
+            b',' => self.reader.consume(1),
+            _ => (),
+        }
+        let key = self.value(buf)?;
+        self.expect(b':')?;
+        Ok(Some(key))
+    }
+
+    /// deserializes the next value
+    fn value<T: DeserializeOwned>(&mut self, buf: &mut Vec<u8>) -> Result<T> {
+        self.skip_value(buf)?;
+        Ok(serde_json::from_slice(buf)?)
+    }
+
+    /// copies the raw bytes of the next value into `buf`
+    fn skip_value(&mut self, buf: &mut Vec<u8>) -> Result<()> {
+        buf.clear();
+        let first = self.peek_token()?;
+        match first {
+            b'"' => {
+                self.string(buf)?;
+            }
+            b'{' | b'[' => {
+                let mut depth = 0usize;
+                loop {
+                    match self.peek_token()? {
+                        b'"' => self.string(buf)?,
+                        byte => {
+                            self.reader.consume(1);
+                            buf.push(byte);
+                            match byte {
+                                b'{' | b'[' => depth += 1,
+                                b'}' | b']' => {
+                                    depth -= 1;
+                                    if depth == 0 {
+                                        break;
+                                    }
+                                }
+                                _ => (),
+                            }
+                        }
+                    }
+                }
+            }
+            _ => {
+                // numbers and literals run until the next delimiter
+                while let Some(byte) = self.peek()? {
+                    match byte {
+                        b',' | b'}' | b']' => break,
+                        byte if byte.is_ascii_whitespace() => break,
+                        byte => {
+                            self.reader.consume(1);
+                            buf.push(byte)
+                        }
+                    }
+                }
+                if buf.is_empty() {
+                    return Err(self.unexpected(first));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// copies a whole string including its quotes
+    fn string(&mut self, buf: &mut Vec<u8>) -> Result<()> {
+        buf.push(self.bump()?);
+        loop {
+            let byte = self.bump()?;
+            buf.push(byte);
+            match byte {
+                b'\\' => buf.push(self.bump()?),
+                b'"' => return Ok(()),
+                _ => (),
+            }
+        }
+    }
+}
+
+fn eof() -> crate::Error {
+    serde_json::Error::io(io::Error::new(
+        io::ErrorKind::UnexpectedEof,
+        "unexpected end of response",
+    ))
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORK: &str = r#"{"DOI":"10.1037/0003-066x.59.1.29","title":["How the Mind Hurts and Heals the Body. [\"quoted\"]"],"is-referenced-by-count":84,"issued":{"date-parts":[[2004]]},"author":[{"given":"Oakley","family":"Ray"}]}"#;
+
+    #[test]
+    fn stream_work_list() {
+        let body = format!(
+            r#"{{"status":"ok","message-type":"work-list","message-version":"1.0.0","message":{{"facets":{{}},"next-cursor":"AoJ/","total-results":2,"items":[{work}, {work}],"items-per-page":2,"query":{{"start-index":0,"search-terms":null}}}}}}"#,
+            work = WORK
+        );
+        let mut stream = WorkStream::new(body.as_bytes());
+        assert_eq!(None, stream.total_results());
+
+        let first = stream.next().unwrap().unwrap();
+        assert_eq!("10.1037/0003-066x.59.1.29", first.doi);
+        assert_eq!(Some(2), stream.total_results());
+        assert_eq!(Some("AoJ/"), stream.next_cursor());
+        assert_eq!(None, stream.items_per_page());
+
+        assert!(stream.next().unwrap().is_ok());
+        assert!(stream.next().is_none());
+        assert_eq!(Some(2), stream.items_per_page());
+        assert!(stream.next().is_none());
+    }
+
+    #[test]
+    fn stream_empty_work_list() {
+        let body = r#"{"message":{"items":[ ],"total-results":0},"message-type":"work-list","status":"ok"}"#;
+        let mut stream = WorkStream::new(body.as_bytes());
+
+        assert!(stream.next().is_none());
+        assert_eq!(Some(0), stream.total_results());
+    }
+
+    #[test]
+    fn stream_unexpected_msg() {
+        let body = r#"{"status":"ok","message-type":"type","message":{"id":"book-section","label":"Book Section"}}"#;
+        let mut stream = WorkStream::new(body.as_bytes());
+
+        assert!(stream.next().unwrap().is_err());
+        assert!(stream.next().is_none());
+    }
+
+    #[test]
+    fn stream_null_msg() {
+        let body = r#"{"status":"ok","message-type":"work-list","message":null}"#;
+        let mut stream = WorkStream::new(body.as_bytes());
+
+        assert_eq!(
+            "No message found but expected message of type `work-list`",
+            stream.next().unwrap().unwrap_err().to_string()
+        );
+        assert!(stream.next().is_none());
+    }
+
+    #[test]
+    fn stream_truncated() {
+        let body = format!(
+            r#"{{"status":"ok","message-type":"work-list","message":{{"items":[{}, {{"DOI":"#,
+            WORK
+        );
+        let mut stream = WorkStream::new(body.as_bytes());
+
+        assert!(stream.next().unwrap().is_ok());
+        assert!(stream.next().unwrap().is_err());
+        assert!(stream.next().is_none());
+    }
+}
@@ -5,7 +5,7 @@ use crate::response::{FacetMap, QueryResponse};
 use crate::{Crossref, WorkListQuery, WorksQuery};
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{Map, Value};
 
 /// A hashmap containing relation name, `Relation` pairs.
 /// [crossref rest-api-doc](https://github.com/CrossRef/rest-api-doc/blob/master/api_format.md#relations)
@@ -13,43 +13,152 @@ use serde_json::Value;
 /// Therefor the `serde_json::Value` type is used instead to prevent an invalid length error
 pub type Relations = std::collections::HashMap<String, Value>;
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
-#[serde(rename_all = "kebab-case")]
-#[allow(missing_docs)]
-pub struct WorkList {
-    pub facets: FacetMap,
-    /// the number of items that match the response
-    pub total_results: usize,
-    /// crossref responses for large number of items are divided in pages, number of elements to expect in `items`
-    pub items_per_page: Option<usize>,
-    /// if a query was set in the request, this will also be part in the response
-    pub query: Option<QueryResponse>,
-    /// all work items that are returned
-    pub items: Vec<Work>,
-    /// deep page through `/works` result sets
-    pub next_cursor: Option<String>,
+extra_fields! {
+    #[derive(Debug, Clone)]
+    #[serde(rename_all = "kebab-case")]
+    #[allow(missing_docs)]
+    pub struct WorkList {
+        /// if facets where part in the request they are also included in the response
+        #[serde(default)]
+        pub facets: FacetMap,
+        /// the number of items that match the response
+        pub total_results: usize,
+        /// crossref responses for large number of items are divided in pages, number of elements to expect in `items`
+        pub items_per_page: Option<usize>,
+        /// if a query was set in the request, this will also be part in the response
+        pub query: Option<QueryResponse>,
+        /// all work items that are returned
+        pub items: Vec<Work>,
+        /// deep page through `/works` result sets
+        pub next_cursor: Option<String>,
+        /// fields of the work list that are not explicitly modeled
+        #[serde(flatten, skip_deserializing)]
+        pub extra: Map<String, Value>,
+    }
 }
 
-/// the main return type of the crossref api
-/// represents a publication
-/// based on the [crossref rest-api-doc](https://github.com/CrossRef/rest-api-doc/blob/master/api_format.md#work)
-/// with minor adjustments
-#[derive(Debug, Clone, Deserialize, Serialize)]
-#[serde(rename_all = "kebab-case")]
-#[allow(missing_docs)]
-pub struct Work {
-    /// Work titles, including translated titles
-    pub title: Vec<String>,
-    #[serde(rename = "abstract")]
-    pub abstract_: Option<String>,
-    /// Count of inbound references deposited with Crossref
-    pub is_referenced_by_count: i32,
-    /// DOI of the work
-    #[serde(rename = "DOI")]
-    pub doi: String,
-    pub issued: PartialDate,
-    pub author: Option<Vec<Contributor>>,
-    pub reference: Option<Vec<Reference>>,
+extra_fields! {
+    /// the main return type of the crossref api
+    /// represents a publication
+    /// based on the [crossref rest-api-doc](https://github.com/CrossRef/rest-api-doc/blob/master/api_format.md#work)
+    /// with minor adjustments
+    #[derive(Debug, Clone)]
+    #[serde(rename_all = "kebab-case")]
+    #[allow(missing_docs)]
+    pub struct Work {
+        /// Name of work's publisher
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub publisher: Option<String>,
+        /// Work titles, including translated titles
+        pub title: Vec<String>,
+        /// Work titles in the work's original publication language
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub original_title: Option<Vec<String>>,
+        /// Short or abbreviated work titles
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub short_title: Option<Vec<String>>,
+        /// Work subtitles, including original language and translated
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub subtitle: Option<Vec<String>>,
+        #[serde(rename = "abstract")]
+        pub abstract_: Option<String>,
+        /// Count of inbound references deposited with Crossref
+        pub is_referenced_by_count: i32,
+        /// DOI prefix identifier of the form `http://id.crossref.org/prefix/DOI_PREFIX`
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub prefix: Option<String>,
+        /// DOI of the work
+        #[serde(rename = "DOI")]
+        pub doi: String,
+        /// URL form of the work's DOI
+        #[serde(rename = "URL", skip_serializing_if = "Option::is_none")]
+        pub url: Option<String>,
+        /// Member identifier of the form `http://id.crossref.org/member/MEMBER_ID`
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub member: Option<String>,
+        /// Enumeration, one of the type ids from `https://api.crossref.org/v1/types`
+        #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+        pub type_: Option<String>,
+        /// Date on which the work was published in print
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub published_print: Option<PartialDate>,
+        /// Date on which the work was published online
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub published_online: Option<PartialDate>,
+        /// Date on which posted content was made available online
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub posted: Option<PartialDate>,
+        /// Date on which a work was accepted, after being submitted, during a submission process
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub accepted: Option<PartialDate>,
+        /// Earliest of `published-print` and `published-online`
+        pub issued: PartialDate,
+        /// Full titles of the containing work (usually a book or journal)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub container_title: Option<Vec<String>>,
+        /// Abbreviated titles of the containing work
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub short_container_title: Option<Vec<String>>,
+        /// Issue number of an article's journal
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub issue: Option<String>,
+        /// Volume number of an article's journal
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub volume: Option<String>,
+        /// Pages numbers of an article within its journal
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub page: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub article_number: Option<String>,
+        /// ISSNs of the containing journal
+        #[serde(rename = "ISSN", skip_serializing_if = "Option::is_none")]
+        pub issn: Option<Vec<String>>,
+        /// List of ISSNs with ISSN type information
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub issn_type: Option<Vec<ISSN>>,
+        /// ISBNs of the work or containing book
+        #[serde(rename = "ISBN", skip_serializing_if = "Option::is_none")]
+        pub isbn: Option<Vec<String>>,
+        /// Subject category names, a controlled vocabulary from Sci-Val
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub subject: Option<Vec<String>>,
+        /// Language of the work as ISO 639 code
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub language: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub license: Option<Vec<License>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub funder: Option<Vec<FundingBody>>,
+        pub author: Option<Vec<Contributor>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub editor: Option<Vec<Contributor>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub chair: Option<Vec<Contributor>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub translator: Option<Vec<Contributor>>,
+        /// URLs to full-text locations
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub link: Option<Vec<ResourceLink>>,
+        pub reference: Option<Vec<Reference>>,
+        /// Relations to other works, by relation type like `is-supplemented-by`
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub relation: Option<Relations>,
+        /// fields of the work that are not explicitly modeled, like `source`, `score` or `indexed`
+        #[serde(flatten, skip_deserializing)]
+        pub extra: Map<String, Value>,
+    }
+}
+
+impl Work {
+    /// the date the work was published, the first of `issued`, `published-print`,
+    /// `published-online` and `posted` that has at least a year
+    pub fn published(&self) -> Option<&PartialDate> {
+        std::iter::once(&self.issued)
+            .chain(self.published_print.as_ref())
+            .chain(self.published_online.as_ref())
+            .chain(self.posted.as_ref())
+            .find(|date| date.date_parts.ymd().is_some())
+    }
 }
 
 /// Helper struct to represent dates in the cross ref api as nested arrays of numbers
@@ -57,6 +166,15 @@ pub struct Work {
 pub struct DateParts(pub Vec<Vec<Option<u32>>>);
 
 impl DateParts {
+    /// year, month and day of the first date, `None` if not even the year is present
+    pub fn ymd(&self) -> Option<(i32, Option<u32>, Option<u32>)> {
+        let first = self.0.first()?;
+        let year = (*first.first()?)? as i32;
+        let month = first.get(1).cloned().unwrap_or_default();
+        let day = month.and(first.get(2).cloned().unwrap_or_default());
+        Some((year, month, day))
+    }
+
     /// converts the nested array of numbers into the corresponding [DateField]
     /// standalone years are allowed.
     /// if an array is empty, [None] will be returned
@@ -89,60 +207,100 @@ impl DateParts {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
-#[allow(missing_docs)]
-pub struct FundingBody {
-    /// Funding body primary name
-    pub name: String,
-    /// Optional [Open Funder Registry](http://www.crossref.org/fundingdata/registry.html) DOI uniquely identifing the funding body
-    #[serde(rename = "DOI")]
-    pub doi: Option<String>,
-    /// Award number(s) for awards given by the funding body
-    pub award: Option<Vec<String>>,
-    /// Either `crossref` or `publisher`
-    #[serde(rename = "doi-asserted-by")]
-    pub doi_asserted_by: Option<String>,
+extra_fields! {
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[allow(missing_docs)]
+    pub struct FundingBody {
+        /// Funding body primary name
+        pub name: String,
+        /// Optional [Open Funder Registry](http://www.crossref.org/fundingdata/registry.html) DOI uniquely identifing the funding body
+        #[serde(rename = "DOI")]
+        pub doi: Option<String>,
+        /// Award number(s) for awards given by the funding body
+        pub award: Option<Vec<String>>,
+        /// Either `crossref` or `publisher`
+        #[serde(rename = "doi-asserted-by")]
+        pub doi_asserted_by: Option<String>,
+        /// all fields of the message that are not explicitly modeled
+        #[serde(flatten, skip_deserializing)]
+        pub extra: Map<String, Value>,
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
-#[allow(missing_docs)]
-pub struct ClinicalTrialNumber {
-    /// Identifier of the clinical trial
-    #[serde(rename = "clinical-trial-number")]
-    pub clinical_trial_number: String,
-    /// DOI of the clinical trial regsitry that assigned the trial number
-    pub registry: String,
-    /// One of `preResults`, `results` or `postResults`
-    #[serde(rename = "type")]
-    pub type_: Option<String>,
+extra_fields! {
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[allow(missing_docs)]
+    pub struct ClinicalTrialNumber {
+        /// Identifier of the clinical trial
+        #[serde(rename = "clinical-trial-number")]
+        pub clinical_trial_number: String,
+        /// DOI of the clinical trial regsitry that assigned the trial number
+        pub registry: String,
+        /// One of `preResults`, `results` or `postResults`
+        #[serde(rename = "type")]
+        pub type_: Option<String>,
+        /// all fields of the message that are not explicitly modeled
+        #[serde(flatten, skip_deserializing)]
+        pub extra: Map<String, Value>,
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
-#[allow(missing_docs)]
-pub struct Contributor {
-    pub family: Option<String>,
-    pub given: Option<String>,
+extra_fields! {
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[allow(missing_docs)]
+    pub struct Contributor {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub family: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub given: Option<String>,
+        /// name of an organization, when the contributor is not a person
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub name: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub suffix: Option<String>,
+        /// URL-form of an [ORCID](http://orcid.org) identifier
+        #[serde(rename = "ORCID", skip_serializing_if = "Option::is_none")]
+        pub orcid: Option<String>,
+        /// Either `first` or `additional`
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub sequence: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub affiliation: Option<Vec<Affiliation>>,
+        /// all fields of the message that are not explicitly modeled
+        #[serde(flatten, skip_deserializing)]
+        pub extra: Map<String, Value>,
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
-#[allow(missing_docs)]
-pub struct Affiliation {
-    /// the affiliation's name
-    pub name: String,
+extra_fields! {
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[allow(missing_docs)]
+    pub struct Affiliation {
+        /// the affiliation's name
+        pub name: String,
+        /// all fields of the message that are not explicitly modeled
+        #[serde(flatten, skip_deserializing)]
+        pub extra: Map<String, Value>,
+    }
 }
 
-/// represents full date information for an item
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
-#[serde(rename_all = "kebab-case")]
-pub struct Date {
-    /// Contains an ordered array of year, month, day of month.
-    /// Only year is required. Note that the field contains a nested array,
-    /// e.g. [ [ 2006, 5, 19 ] ] to conform to citeproc JSON dates
-    pub date_parts: DateParts,
-    /// Seconds since UNIX epoch
-    pub timestamp: usize,
-    /// ISO 8601 date time
-    pub date_time: String,
+extra_fields! {
+    /// represents full date information for an item
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[serde(rename_all = "kebab-case")]
+    pub struct Date {
+        /// Contains an ordered array of year, month, day of month.
+        /// Only year is required. Note that the field contains a nested array,
+        /// e.g. [ [ 2006, 5, 19 ] ] to conform to citeproc JSON dates
+        pub date_parts: DateParts,
+        /// Seconds since UNIX epoch
+        pub timestamp: usize,
+        /// ISO 8601 date time
+        pub date_time: String,
+        /// all fields of the message that are not explicitly modeled
+        #[serde(flatten, skip_deserializing)]
+        pub extra: Map<String, Value>,
+    }
 }
 
 impl Date {
@@ -152,17 +310,27 @@ impl Date {
     }
 }
 
-/// represents an incomplete date only consisting of year or year and month
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
-pub struct PartialDate {
-    /// Contains an ordered array of year, month, day of month.
-    /// Only year is required
-    /// e.g. `[ [`2006`] ]` to conform to citeproc JSON dates
-    #[serde(rename = "date-parts")]
-    pub date_parts: DateParts,
+extra_fields! {
+    /// represents an incomplete date only consisting of year or year and month
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct PartialDate {
+        /// Contains an ordered array of year, month, day of month.
+        /// Only year is required
+        /// e.g. `[ [`2006`] ]` to conform to citeproc JSON dates
+        #[serde(rename = "date-parts")]
+        pub date_parts: DateParts,
+        /// all fields of the message that are not explicitly modeled
+        #[serde(flatten, skip_deserializing)]
+        pub extra: Map<String, Value>,
+    }
 }
 
 impl PartialDate {
+    /// year, month and day of the date, `None` if not even the year is present
+    pub fn ymd(&self) -> Option<(i32, Option<u32>, Option<u32>)> {
+        self.date_parts.ymd()
+    }
+
     /// converts the nested array of numbers into the correct representation of chrono [NaiveDate]
     pub fn as_date_field(&self) -> Option<DateField> {
         self.date_parts.as_date()
@@ -185,142 +353,241 @@ pub enum DateField {
     Multi(Vec<NaiveDate>),
 }
 
-/// metadata about when the `Work` entry was updated
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
-pub struct Update {
-    /// Date on which the update was published
-    pub updated: PartialDate,
-    /// DOI of the updated work
-    #[serde(rename = "DOI")]
-    pub doi: String,
-    /// The type of update, for example retraction or correction
-    #[serde(rename = "type")]
-    pub type_: String,
-    /// A display-friendly label for the update type
-    pub label: Option<String>,
+extra_fields! {
+    /// metadata about when the `Work` entry was updated
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Update {
+        /// Date on which the update was published
+        pub updated: PartialDate,
+        /// DOI of the updated work
+        #[serde(rename = "DOI")]
+        pub doi: String,
+        /// The type of update, for example retraction or correction
+        #[serde(rename = "type")]
+        pub type_: String,
+        /// A display-friendly label for the update type
+        pub label: Option<String>,
+        /// all fields of the message that are not explicitly modeled
+        #[serde(flatten, skip_deserializing)]
+        pub extra: Map<String, Value>,
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
-#[allow(missing_docs)]
-pub struct Assertion {
-    pub name: String,
-    pub value: Option<String>,
-    #[serde(rename = "URL")]
-    pub url: Option<String>,
-    pub explanation: Option<String>,
-    pub label: Option<String>,
-    pub order: Option<i32>,
-    pub group: Option<AssertionGroup>,
+extra_fields! {
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[allow(missing_docs)]
+    pub struct Assertion {
+        pub name: String,
+        pub value: Option<String>,
+        #[serde(rename = "URL")]
+        pub url: Option<String>,
+        pub explanation: Option<String>,
+        pub label: Option<String>,
+        pub order: Option<i32>,
+        pub group: Option<AssertionGroup>,
+        /// all fields of the message that are not explicitly modeled
+        #[serde(flatten, skip_deserializing)]
+        pub extra: Map<String, Value>,
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
-#[serde(rename_all = "kebab-case")]
-#[allow(missing_docs)]
-pub struct Issue {
-    /// Date on which the work was published in print
-    pub published_print: Option<PartialDate>,
-    /// Date on which the work was published online
-    pub published_online: Option<PartialDate>,
-    /// Issue number of an article's journal
-    pub issue: Option<String>,
+extra_fields! {
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[serde(rename_all = "kebab-case")]
+    #[allow(missing_docs)]
+    pub struct Issue {
+        /// Date on which the work was published in print
+        pub published_print: Option<PartialDate>,
+        /// Date on which the work was published online
+        pub published_online: Option<PartialDate>,
+        /// Issue number of an article's journal
+        pub issue: Option<String>,
+        /// all fields of the message that are not explicitly modeled
+        #[serde(flatten, skip_deserializing)]
+        pub extra: Map<String, Value>,
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
-#[allow(missing_docs)]
-pub struct AssertionGroup {
-    pub name: String,
-    pub label: Option<String>,
+extra_fields! {
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[allow(missing_docs)]
+    pub struct AssertionGroup {
+        pub name: String,
+        pub label: Option<String>,
+        /// all fields of the message that are not explicitly modeled
+        #[serde(flatten, skip_deserializing)]
+        pub extra: Map<String, Value>,
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
-#[allow(missing_docs)]
-pub struct Agency {
-    pub id: String,
-    pub label: Option<String>,
+extra_fields! {
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[allow(missing_docs)]
+    pub struct Agency {
+        pub id: String,
+        pub label: Option<String>,
+        /// all fields of the message that are not explicitly modeled
+        #[serde(flatten, skip_deserializing)]
+        pub extra: Map<String, Value>,
+    }
 }
 
-/// how the `Work` is licensed
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
-#[serde(rename_all = "kebab-case")]
-pub struct License {
-    /// Either `vor` (version of record,) `am` (accepted manuscript) or `unspecified`
-    pub content_version: String,
-    /// Number of days between the publication date of the work and the start date of this license
-    pub delay_in_days: i32,
-    /// Date on which this license begins to take effect
-    pub start: PartialDate,
-    /// Link to a web page describing this license
-    #[serde(rename = "URL")]
-    pub url: String,
+extra_fields! {
+    /// how the `Work` is licensed
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[serde(rename_all = "kebab-case")]
+    pub struct License {
+        /// Either `vor` (version of record,) `am` (accepted manuscript) or `unspecified`
+        pub content_version: String,
+        /// Number of days between the publication date of the work and the start date of this license
+        pub delay_in_days: i32,
+        /// Date on which this license begins to take effect
+        pub start: PartialDate,
+        /// Link to a web page describing this license
+        #[serde(rename = "URL")]
+        pub url: String,
+        /// all fields of the message that are not explicitly modeled
+        #[serde(flatten, skip_deserializing)]
+        pub extra: Map<String, Value>,
+    }
 }
 
-/// metadata about a related resource
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
-#[serde(rename_all = "kebab-case")]
-pub struct ResourceLink {
-    /// Either `text-mining`, `similarity-checking` or `unspecified`
-    pub intended_application: String,
-    /// Either `vor` (version of record,) `am` (accepted manuscript) or `unspecified`
-    pub content_version: String,
-    /// Direct link to a full-text download location
-    #[serde(rename = "URL")]
-    pub url: String,
-    /// Content type (or MIME type) of the full-text object
-    pub content_type: Option<String>,
+extra_fields! {
+    /// metadata about a related resource
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[serde(rename_all = "kebab-case")]
+    pub struct ResourceLink {
+        /// Either `text-mining`, `similarity-checking` or `unspecified`
+        pub intended_application: String,
+        /// Either `vor` (version of record,) `am` (accepted manuscript) or `unspecified`
+        pub content_version: String,
+        /// Direct link to a full-text download location
+        #[serde(rename = "URL")]
+        pub url: String,
+        /// Content type (or MIME type) of the full-text object
+        pub content_type: Option<String>,
+        /// all fields of the message that are not explicitly modeled
+        #[serde(flatten, skip_deserializing)]
+        pub extra: Map<String, Value>,
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
-#[serde(rename_all = "kebab-case")]
-#[allow(missing_docs)]
-pub struct Reference {
-    #[serde(rename = "DOI")]
-    pub doi: Option<String>,
-    pub year: Option<String>,
+extra_fields! {
+    /// a reference in the bibliography of the `Work`
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    #[serde(rename_all = "kebab-case", default)]
+    #[allow(missing_docs)]
+    pub struct Reference {
+        /// identifier of the reference within the work
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub key: Option<String>,
+        #[serde(rename = "DOI")]
+        pub doi: Option<String>,
+        /// Either `crossref` or `publisher`
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub doi_asserted_by: Option<String>,
+        pub year: Option<String>,
+        /// the reference as it was written, if it could not be structured
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub unstructured: Option<String>,
+        /// first author of the referenced work
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub author: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub article_title: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub journal_title: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub series_title: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub volume_title: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub volume: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub issue: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub first_page: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub edition: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub component: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub standard_designator: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub standards_body: Option<String>,
+        #[serde(rename = "ISSN", skip_serializing_if = "Option::is_none")]
+        pub issn: Option<String>,
+        #[serde(rename = "ISBN", skip_serializing_if = "Option::is_none")]
+        pub isbn: Option<String>,
+        /// all fields of the message that are not explicitly modeled
+        #[serde(flatten, skip_deserializing)]
+        pub extra: Map<String, Value>,
+    }
 }
 
-/// ISSN info for the `Work`
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
-#[serde(rename_all = "kebab-case")]
-pub struct ISSN {
-    /// identifier
-    pub value: String,
-    /// One of `eissn`, `pissn` or `lissn`
-    #[serde(rename = "type")]
-    pub type_: String,
+extra_fields! {
+    /// ISSN info for the `Work`
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[serde(rename_all = "kebab-case")]
+    pub struct ISSN {
+        /// identifier
+        pub value: String,
+        /// One of `eissn`, `pissn` or `lissn`
+        #[serde(rename = "type")]
+        pub type_: String,
+        /// all fields of the message that are not explicitly modeled
+        #[serde(flatten, skip_deserializing)]
+        pub extra: Map<String, Value>,
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
-#[serde(rename_all = "kebab-case")]
-#[allow(missing_docs)]
-pub struct ContentDomain {
-    pub domain: Vec<String>,
-    pub crossmark_restriction: bool,
+extra_fields! {
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[serde(rename_all = "kebab-case")]
+    #[allow(missing_docs)]
+    pub struct ContentDomain {
+        pub domain: Vec<String>,
+        pub crossmark_restriction: bool,
+        /// all fields of the message that are not explicitly modeled
+        #[serde(flatten, skip_deserializing)]
+        pub extra: Map<String, Value>,
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
-#[serde(rename_all = "kebab-case")]
-#[allow(missing_docs)]
-pub struct Relation {
-    pub id_type: Option<String>,
-    pub id: Option<String>,
-    pub asserted_by: Option<String>,
+extra_fields! {
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[serde(rename_all = "kebab-case")]
+    #[allow(missing_docs)]
+    pub struct Relation {
+        pub id_type: Option<String>,
+        pub id: Option<String>,
+        pub asserted_by: Option<String>,
+        /// all fields of the message that are not explicitly modeled
+        #[serde(flatten, skip_deserializing)]
+        pub extra: Map<String, Value>,
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
-#[serde(rename_all = "kebab-case")]
-#[allow(missing_docs)]
-pub struct Review {
-    pub running_number: Option<String>,
-    pub revision_round: Option<String>,
-    /// One of `pre-publication` or `post-publication`
-    pub stage: Option<String>,
-    /// One of `major-revision` or `minor-revision` or `reject` or `reject-with-resubmit` or `accept`
-    pub recommendation: Option<String>,
-    /// One of `referee-report` or `editor-report` or `author-comment` or `community-comment` or `aggregate`
-    #[serde(rename = "type")]
-    pub type_: String,
-    pub competing_interest_statement: Option<String>,
-    pub language: Option<String>,
+extra_fields! {
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[serde(rename_all = "kebab-case")]
+    #[allow(missing_docs)]
+    pub struct Review {
+        pub running_number: Option<String>,
+        pub revision_round: Option<String>,
+        /// One of `pre-publication` or `post-publication`
+        pub stage: Option<String>,
+        /// One of `major-revision` or `minor-revision` or `reject` or `reject-with-resubmit` or `accept`
+        pub recommendation: Option<String>,
+        /// One of `referee-report` or `editor-report` or `author-comment` or `community-comment` or `aggregate`
+        #[serde(rename = "type")]
+        pub type_: String,
+        pub competing_interest_statement: Option<String>,
+        pub language: Option<String>,
+        /// all fields of the message that are not explicitly modeled
+        #[serde(flatten, skip_deserializing)]
+        pub extra: Map<String, Value>,
+    }
 }
 
 #[cfg(test)]
@@ -471,5 +738,56 @@ mod tests {
 "##;
 
         let work: Work = from_str(work_str).unwrap();
+
+        // re-serializing keeps all fields that are not modeled by `Work`
+        let original: Value = from_str(work_str).unwrap();
+        let serialized = to_value(&work).unwrap();
+        assert_eq!(
+            Some("American Psychological Association (APA)"),
+            work.publisher.as_deref()
+        );
+        assert_eq!(Some("journal-article"), work.type_.as_deref());
+        assert_eq!("Crossref", work.extra["source"]);
+        for (key, value) in original.as_object().unwrap() {
+            assert_eq!(value, &serialized[key], "{}", key);
+        }
+    }
+
+    #[test]
+    fn nested_extra_roundtrip() {
+        let work_str = r#"{
+            "title": ["How the Mind Hurts and Heals the Body."],
+            "is-referenced-by-count": 84,
+            "DOI": "10.1037/0003-066x.59.1.29",
+            "issued": {"date-parts": [[2004]], "version": 2},
+            "author": [{
+                "given": "Oakley",
+                "family": "Ray",
+                "ORCID": "http://orcid.org/0000-0002-0270-1711",
+                "authenticated-orcid": true,
+                "affiliation": [{"name": "Vanderbilt University", "id": [{"id": "02vm5rt34"}]}]
+            }],
+            "reference": [{"key": "ref1", "DOI": "10.1097/00006842-197509000-00006", "doi-asserted-by": "crossref", "isbn-type": "print"}]
+        }"#;
+
+        let work: Work = from_str(work_str).unwrap();
+        let author = &work.author.as_ref().unwrap()[0];
+        assert_eq!(Some("Ray"), author.family.as_deref());
+        assert_eq!(true, author.extra["authenticated-orcid"]);
+        assert_eq!(
+            json!([{"id": "02vm5rt34"}]),
+            author.affiliation.as_ref().unwrap()[0].extra["id"]
+        );
+        assert_eq!(2, work.issued.extra["version"]);
+        assert_eq!(
+            "print",
+            work.reference.as_ref().unwrap()[0].extra["isbn-type"]
+        );
+
+        let original: Value = from_str(work_str).unwrap();
+        assert_eq!(original["author"], to_value(&work).unwrap()["author"]);
+        let back: Work = from_value(to_value(&work).unwrap()).unwrap();
+        assert_eq!(work.author, back.author);
+        assert_eq!(work.reference, back.reference);
     }
 }
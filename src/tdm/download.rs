@@ -0,0 +1,570 @@
+// see https://www.crossref.org/documentation/retrieve-metadata/rest-api/text-and-data-mining/
+
+use crate::error::{ErrorKind, Result};
+use crate::response::work::Work;
+use crate::tdm::license::{normalize_url, DEFAULT_LICENSES};
+use crate::tdm::TdmLink;
+use crate::Crossref;
+use chrono::{NaiveDate, Utc};
+use reqwest::header::{HeaderMap, HeaderValue, RETRY_AFTER};
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The name of the file in the output directory the downloads are recorded in.
+pub const MANIFEST: &str = "manifest.jsonl";
+
+/// the headers the client token is sent with
+const TOKEN_HEADERS: &[&str] = &["CR-TDM-Client-Token", "CR-Clickthrough-Client-Token"];
+
+/// how often a request that was rate limited is retried
+const MAX_RETRIES: usize = 3;
+
+/// The outcome of downloading the full text of a work
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DownloadStatus {
+    /// the full text was stored
+    Downloaded,
+    /// the work has no links intended for text mining
+    NoLink,
+    /// none of the links has a license in the allowlist that is in force
+    Refused,
+    /// the publisher did not deliver the full text
+    Failed,
+}
+
+/// A line of the manifest
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ManifestEntry {
+    /// the DOI of the work
+    pub doi: String,
+    /// what happened
+    pub status: DownloadStatus,
+    /// the url of the full text that was requested
+    #[serde(rename = "URL", skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// the content type of the link
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    /// the content version of the link
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_version: Option<String>,
+    /// the url of the license that applies to the link
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+    /// whether the license is in the allowlist and in force, `false` flags downloads of unlicensed links
+    pub licensed: bool,
+    /// where the full text was stored, relative to the output directory
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    /// the size of the stored file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bytes: Option<u64>,
+    /// the status code of the publisher response
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub http_status: Option<u16>,
+    /// why the download failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ManifestEntry {
+    fn new(doi: &str, status: DownloadStatus) -> Self {
+        ManifestEntry {
+            doi: doi.to_string(),
+            status,
+            url: None,
+            content_type: None,
+            content_version: None,
+            license: None,
+            licensed: false,
+            path: None,
+            bytes: None,
+            http_status: None,
+            error: None,
+        }
+    }
+}
+
+/// Downloads the full texts of works for text and data mining.
+///
+/// Only links with a license from the allowlist that is in force are downloaded,
+/// unless unlicensed downloads are enabled, then those are flagged in the manifest.
+/// Every work is recorded as a line in the `manifest.jsonl` of the output directory.
+///
+/// # Example
+///
+/// ```edition2018
+/// use crossref::Crossref;
+/// use crossref::tdm::Downloader;
+/// # fn run() -> Result<(), crossref::Error> {
+/// let client = Crossref::builder().build()?;
+/// let mut downloader = Downloader::builder("fulltext").tdm_token("token").build()?;
+///
+/// let entry = downloader.download_doi(&client, "10.1016/j.cell.2019.05.031")?;
+/// println!("{:?} {:?}", entry.status, entry.path);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Downloader {
+    client: Client,
+    out: PathBuf,
+    licenses: Vec<String>,
+    download_unlicensed: bool,
+    manifest: BufWriter<File>,
+    /// no requests are sent before this time
+    wait_until: Option<SystemTime>,
+}
+
+impl Downloader {
+    /// Constructs a new `DownloaderBuilder` that stores the files in the `out` directory.
+    pub fn builder<P: AsRef<Path>>(out: P) -> DownloaderBuilder {
+        DownloaderBuilder::new(out)
+    }
+
+    /// the directory the files and the manifest are stored in
+    pub fn out(&self) -> &Path {
+        &self.out
+    }
+
+    /// Whether the license url is in the allowlist.
+    pub fn is_allowed(&self, license: &str) -> bool {
+        let license = normalize_url(license);
+        self.licenses
+            .iter()
+            .any(|allowed| license.starts_with(allowed.as_str()))
+    }
+
+    /// Requests the `Work` for the `doi` and downloads its full text.
+    pub fn download_doi(&mut self, client: &Crossref, doi: &str) -> Result<ManifestEntry> {
+        let work = client.work(doi)?;
+        self.download(&work)
+    }
+
+    /// Downloads the full text of the work with the best ranked link whose license is allowed.
+    ///
+    /// Publisher errors are recorded in the returned `ManifestEntry`,
+    /// this only fails if the file or the manifest can not be written.
+    pub fn download(&mut self, work: &Work) -> Result<ManifestEntry> {
+        let entry = self.fetch(work, Utc::now().naive_utc().date())?;
+        serde_json::to_writer(&mut self.manifest, &entry)?;
+        self.manifest.write_all(b"\n")?;
+        self.manifest.flush()?;
+        Ok(entry)
+    }
+
+    /// Downloads the full texts of all works, stops at the first error writing the files.
+    pub fn download_all<'a, I>(&mut self, works: I) -> Result<Vec<ManifestEntry>>
+    where
+        I: IntoIterator<Item = &'a Work>,
+    {
+        works.into_iter().map(|work| self.download(work)).collect()
+    }
+
+    fn fetch(&mut self, work: &Work, today: NaiveDate) -> Result<ManifestEntry> {
+        let links = work.text_mining_links_at(today);
+        if links.is_empty() {
+            return Ok(ManifestEntry::new(&work.doi, DownloadStatus::NoLink));
+        }
+        let licensed = links.iter().find(|link| self.is_licensed(link));
+        let (link, licensed) = match licensed {
+            Some(link) => (link, true),
+            None if self.download_unlicensed => (&links[0], false),
+            None => {
+                let mut entry = ManifestEntry::new(&work.doi, DownloadStatus::Refused);
+                entry.license = links[0].license_url().map(str::to_string);
+                return Ok(entry);
+            }
+        };
+
+        let mut entry = ManifestEntry::new(&work.doi, DownloadStatus::Failed);
+        entry.url = Some(link.url().to_string());
+        entry.content_type = link.link.content_type.clone();
+        entry.content_version = Some(link.link.content_version.clone());
+        entry.license = link.license_url().map(str::to_string);
+        entry.licensed = licensed;
+
+        let mut response = match self.get(link.url()) {
+            Ok(response) => response,
+            Err(err) => {
+                entry.error = Some(err.to_string());
+                return Ok(entry);
+            }
+        };
+        entry.http_status = Some(response.status().as_u16());
+        if !response.status().is_success() {
+            entry.error = Some(format!("publisher responded with {}", response.status()));
+            return Ok(entry);
+        }
+
+        let path = storage_path(&work.doi, link.content_type());
+        let file = self.out.join(&path);
+        if let Some(dir) = file.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        // the full text is only moved into place once it is complete
+        let mut part = file.clone().into_os_string();
+        part.push(".part");
+        let part = PathBuf::from(part);
+        let mut writer = BufWriter::new(File::create(&part)?);
+        let bytes = match response.copy_to(&mut writer) {
+            Ok(bytes) => bytes,
+            Err(err) => {
+                drop(writer);
+                fs::remove_file(&part)?;
+                entry.error = Some(err.to_string());
+                return Ok(entry);
+            }
+        };
+        if let Err(err) = writer.flush() {
+            drop(writer);
+            let _ = fs::remove_file(&part);
+            return Err(err.into());
+        }
+        drop(writer);
+        fs::rename(&part, &file)?;
+
+        entry.status = DownloadStatus::Downloaded;
+        entry.path = Some(path);
+        entry.bytes = Some(bytes);
+        Ok(entry)
+    }
+
+    fn is_licensed(&self, link: &TdmLink) -> bool {
+        link.in_force
+            && link
+                .license_url()
+                .map(|url| self.is_allowed(url))
+                .unwrap_or_default()
+    }
+
+    /// sends the request once the rate limit allows it, retries rate limited requests
+    fn get(&mut self, url: &str) -> Result<reqwest::Response> {
+        let mut retries = 0;
+        loop {
+            if let Some(wait) = self
+                .wait_until
+                .take()
+                .and_then(|until| until.duration_since(SystemTime::now()).ok())
+            {
+                thread::sleep(wait);
+            }
+            let response = self.client.get(url).send()?;
+            self.wait_until = rate_limit(response.headers(), response.status());
+            if response.status() != StatusCode::TOO_MANY_REQUESTS || retries == MAX_RETRIES {
+                return Ok(response);
+            }
+            if self.wait_until.is_none() {
+                self.wait_until = Some(SystemTime::now() + Duration::from_secs(1 << retries));
+            }
+            retries += 1;
+        }
+    }
+}
+
+/// A `DownloaderBuilder` can be used to create a `Downloader` with custom configuration.
+#[derive(Debug, Clone)]
+pub struct DownloaderBuilder {
+    out: PathBuf,
+    /// the Crossref TDM client token, sent with every request to a publisher
+    tdm_token: Option<String>,
+    user_agent: Option<String>,
+    licenses: Vec<String>,
+    download_unlicensed: bool,
+}
+
+impl DownloaderBuilder {
+    /// Constructs a new `DownloaderBuilder` that stores the files in the `out` directory.
+    ///
+    /// This is the same as `Downloader::builder(out)`.
+    pub fn new<P: AsRef<Path>>(out: P) -> Self {
+        DownloaderBuilder {
+            out: out.as_ref().to_path_buf(),
+            tdm_token: None,
+            user_agent: None,
+            licenses: DEFAULT_LICENSES.iter().map(|l| l.to_string()).collect(),
+            download_unlicensed: false,
+        }
+    }
+
+    /// set the Crossref TDM client token, some publishers require it to deliver full texts
+    pub fn tdm_token(mut self, token: &str) -> Self {
+        self.tdm_token = Some(token.to_string());
+        self
+    }
+
+    /// be polite and set your email as `User-Agent`
+    pub fn polite(mut self, email: &str) -> Self {
+        self.user_agent = Some(format!("mailto:{}", email));
+        self
+    }
+
+    /// set the user agent directly
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = Some(user_agent.to_string());
+        self
+    }
+
+    /// add a license url to the allowlist, all urls starting with it are allowed
+    pub fn allow_license(mut self, license: &str) -> Self {
+        self.licenses.push(normalize_url(license));
+        self
+    }
+
+    /// replace the allowlist
+    pub fn licenses<T: AsRef<str>>(mut self, licenses: &[T]) -> Self {
+        self.licenses = licenses
+            .iter()
+            .map(|license| normalize_url(license.as_ref()))
+            .collect();
+        self
+    }
+
+    /// download the best link even if its license is not allowed, the manifest entry is flagged as unlicensed
+    pub fn download_unlicensed(mut self, download_unlicensed: bool) -> Self {
+        self.download_unlicensed = download_unlicensed;
+        self
+    }
+
+    /// Returns a `Downloader` that uses this `DownloaderBuilder` configuration.
+    ///
+    /// # Errors
+    ///
+    /// This will fail if the output directory or the manifest can not be created
+    /// or the TLS backend cannot be initialized.
+    pub fn build(self) -> Result<Downloader> {
+        use reqwest::header;
+        let mut headers = HeaderMap::new();
+        if let Some(agent) = &self.user_agent {
+            headers.insert(
+                header::USER_AGENT,
+                HeaderValue::from_str(agent).map_err(|_| ErrorKind::Config {
+                    msg: format!("failed to create User Agent header for `{}`", agent),
+                })?,
+            );
+        }
+        if let Some(token) = &self.tdm_token {
+            let value = HeaderValue::from_str(token).map_err(|_| ErrorKind::Config {
+                msg: format!("failed to create TDM client token header for `{}`", token),
+            })?;
+            for name in TOKEN_HEADERS {
+                headers.insert(*name, value.clone());
+            }
+        }
+        let client = Client::builder()
+            .default_headers(headers)
+            .build()
+            .map_err(|_| ErrorKind::Config {
+                msg: "failed to initialize TLS backend".to_string(),
+            })?;
+
+        fs::create_dir_all(&self.out)?;
+        let manifest = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.out.join(MANIFEST))?;
+
+        Ok(Downloader {
+            client,
+            out: self.out,
+            licenses: self.licenses,
+            download_unlicensed: self.download_unlicensed,
+            manifest: BufWriter::new(manifest),
+            wait_until: None,
+        })
+    }
+}
+
+/// The path of the full text relative to the output directory.
+///
+/// The DOI is lowercased, the prefix becomes a directory and characters of the suffix
+/// that are not safe in file names are percent encoded, e.g. `10.1000/a/b` becomes `10.1000/a%2Fb.xml`.
+pub fn storage_path(doi: &str, content_type: &str) -> PathBuf {
+    let doi = doi.trim().to_lowercase();
+    let mut parts = doi.splitn(2, '/');
+    let prefix = encode(parts.next().unwrap_or_default());
+    let suffix = encode(parts.next().unwrap_or_default());
+    let content_type = content_type.to_lowercase();
+    let extension = if content_type.contains("xml") {
+        "xml"
+    } else if content_type.contains("pdf") {
+        "pdf"
+    } else if content_type.contains("html") {
+        "html"
+    } else if content_type.starts_with("text/plain") {
+        "txt"
+    } else {
+        "bin"
+    };
+    Path::new(&prefix).join(format!("{}.{}", suffix, extension))
+}
+
+/// percent encodes everything but ascii alphanumerics and `-._`
+fn encode(s: &str) -> String {
+    let mut encoded = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'.' | b'_' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    if encoded.is_empty() || encoded.chars().all(|c| c == '.') {
+        encoded = encoded.replace('.', "%2E");
+        encoded.push('_');
+    }
+    encoded
+}
+
+/// The time until which no further requests should be sent.
+///
+/// Publishers report their limits with the `CR-TDM-Rate-Limit-Remaining` and
+/// `CR-TDM-Rate-Limit-Reset` headers, rate limited responses may have a `Retry-After` header.
+fn rate_limit(headers: &HeaderMap, status: StatusCode) -> Option<SystemTime> {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+    };
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        if let Some(seconds) = header(RETRY_AFTER.as_str()).and_then(|s| s.parse().ok()) {
+            return Some(SystemTime::now() + Duration::from_secs(seconds));
+        }
+    }
+    let remaining: Option<u64> = header("CR-TDM-Rate-Limit-Remaining").and_then(|s| s.parse().ok());
+    if remaining == Some(0) || status == StatusCode::TOO_MANY_REQUESTS {
+        let reset: u64 = header("CR-TDM-Rate-Limit-Reset")?.parse().ok()?;
+        return Some(UNIX_EPOCH + Duration::from_secs(reset));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::serve;
+    use serde_json::*;
+    use std::process;
+
+    fn out_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("crossref-tdm-{}-{}", name, process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn work(url: &str, license: &str) -> Work {
+        let work = json!({
+            "DOI": "10.5555/TDM/1",
+            "is-referenced-by-count": 0,
+            "title": ["TDM"],
+            "issued": {"date-parts": [[2019, 6, 13]]},
+            "license": [{"URL": license, "start": {"date-parts": [[2019, 6, 13]]}, "delay-in-days": 0, "content-version": "vor"}],
+            "link": [{"URL": format!("{}/fulltext.xml", url), "content-type": "text/xml", "content-version": "vor", "intended-application": "text-mining"}]
+        });
+        from_value(work).unwrap()
+    }
+
+    #[test]
+    fn download_licensed() {
+        let (url, server) = serve(vec![
+            ("429 Too Many Requests", "Retry-After: 0\r\n", ""),
+            (
+                "200 OK",
+                "CR-TDM-Rate-Limit-Remaining: 10\r\n",
+                "<article/>",
+            ),
+        ]);
+        let out = out_dir("licensed");
+        let mut downloader = Downloader::builder(&out)
+            .tdm_token("secret")
+            .build()
+            .unwrap();
+
+        let entry = downloader
+            .download(&work(&url, "http://creativecommons.org/licenses/by/4.0/"))
+            .unwrap();
+
+        assert_eq!(DownloadStatus::Downloaded, entry.status);
+        assert!(entry.licensed);
+        assert_eq!(Some(200), entry.http_status);
+        assert_eq!(
+            Some(Path::new("10.5555/tdm%2F1.xml").to_path_buf()),
+            entry.path
+        );
+        assert_eq!(
+            "<article/>",
+            fs::read_to_string(out.join("10.5555/tdm%2F1.xml")).unwrap()
+        );
+        assert!(!out.join("10.5555/tdm%2F1.xml.part").exists());
+
+        let requests = server.join().unwrap();
+        assert_eq!(2, requests.len());
+        assert!(requests[1].starts_with("GET /fulltext.xml"));
+        assert!(requests[1]
+            .to_lowercase()
+            .contains("cr-tdm-client-token: secret"));
+
+        let manifest = fs::read_to_string(out.join(MANIFEST)).unwrap();
+        let line: ManifestEntry = from_str(manifest.lines().next().unwrap()).unwrap();
+        assert_eq!(entry, line);
+        fs::remove_dir_all(&out).unwrap();
+    }
+
+    #[test]
+    fn refuse_unlicensed() {
+        let out = out_dir("refused");
+        let mut downloader = Downloader::builder(&out).build().unwrap();
+        let work = work(
+            "http://127.0.0.1:9",
+            "http://creativecommons.org/licenses/by-nc/4.0/",
+        );
+
+        let entry = downloader.download(&work).unwrap();
+        assert_eq!(DownloadStatus::Refused, entry.status);
+        assert_eq!(None, entry.path);
+
+        let (url, server) = serve(vec![("200 OK", "", "<article/>")]);
+        let mut downloader = Downloader::builder(&out)
+            .download_unlicensed(true)
+            .build()
+            .unwrap();
+        let entry = downloader
+            .download(&self::work(
+                &url,
+                "http://creativecommons.org/licenses/by-nc/4.0/",
+            ))
+            .unwrap();
+        server.join().unwrap();
+        assert_eq!(DownloadStatus::Downloaded, entry.status);
+        assert!(!entry.licensed);
+
+        assert_eq!(
+            2,
+            fs::read_to_string(out.join(MANIFEST))
+                .unwrap()
+                .lines()
+                .count()
+        );
+        fs::remove_dir_all(&out).unwrap();
+    }
+
+    #[test]
+    fn allowlist() {
+        let downloader = Downloader::builder(out_dir("allowlist")).build().unwrap();
+        assert!(downloader.is_allowed("https://creativecommons.org/licenses/by/4.0/"));
+        assert!(downloader.is_allowed("http://creativecommons.org/publicdomain/zero/1.0/"));
+        assert!(downloader.is_allowed("https://www.elsevier.com/tdm/userlicense/1.0/"));
+        assert!(!downloader.is_allowed("http://creativecommons.org/licenses/by-nc-nd/4.0/"));
+        assert!(!downloader.is_allowed("https://www.elsevier.com/open-access/userlicense/1.0/"));
+        fs::remove_dir_all(downloader.out()).unwrap();
+    }
+}
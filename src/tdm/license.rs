@@ -0,0 +1,372 @@
+use crate::response::work::{License, Work};
+use crate::tdm::license_start;
+use chrono::NaiveDate;
+use std::fmt;
+
+/// The license urls a `Downloader` accepts by default.
+///
+/// Creative Commons Attribution and CC0 licenses followed by the text and data mining
+/// licenses of publishers. Urls are compared without scheme and `www.`.
+pub const DEFAULT_LICENSES: &[&str] = &[
+    "creativecommons.org/licenses/by/",
+    "creativecommons.org/publicdomain/zero/",
+    "elsevier.com/tdm/",
+    "springer.com/tdm",
+    "springernature.com/gp/researchers/text-and-data-mining",
+    "doi.wiley.com/10.1002/tdm_license",
+    "onlinelibrary.wiley.com/termsandconditions",
+    "iopscience.iop.org/info/page/text-and-data-mining",
+    "iopscience.iop.org/page/copyright",
+    "pubs.acs.org/page/policy/authorchoice_termsofuse",
+    "academic.oup.com/journals/pages/open_access/funder_policies/chorus/standard_publication_model",
+    "tandfonline.com/action/showcopyright",
+    "link.aps.org/licenses/aps-default-text-mining-license",
+];
+
+/// The kind of a license, derived from its url
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LicenseKind {
+    /// Creative Commons Attribution
+    CcBy,
+    /// Creative Commons Attribution NonCommercial, including the NoDerivatives and ShareAlike variants
+    CcByNc,
+    /// Creative Commons Attribution ShareAlike
+    CcBySa,
+    /// Creative Commons Attribution NoDerivatives
+    CcByNd,
+    /// Creative Commons public domain dedication
+    Cc0,
+    /// a license of the publisher that permits text and data mining
+    PublisherTdm,
+    /// the Elsevier user license for open archive articles
+    ElsevierUserLicense,
+    /// any other license
+    Unknown,
+}
+
+impl LicenseKind {
+    /// Classifies the license url, scheme, `www.` and case are ignored.
+    ///
+    /// # Example
+    ///
+    /// ```edition2018
+    /// use crossref::tdm::LicenseKind;
+    ///
+    /// assert_eq!(LicenseKind::CcBy, LicenseKind::classify("http://creativecommons.org/licenses/by/4.0/"));
+    /// assert_eq!(LicenseKind::CcByNc, LicenseKind::classify("https://creativecommons.org/licenses/by-nc-nd/4.0/"));
+    /// ```
+    pub fn classify(url: &str) -> LicenseKind {
+        let url = normalize_url(url);
+        if let Some(terms) = url.strip_prefix("creativecommons.org/licenses/") {
+            let terms = terms.split('/').next().unwrap_or_default();
+            match terms {
+                "by" => LicenseKind::CcBy,
+                "by-sa" => LicenseKind::CcBySa,
+                "by-nd" => LicenseKind::CcByNd,
+                "by-nc" | "by-nc-nd" | "by-nc-sa" => LicenseKind::CcByNc,
+                _ => LicenseKind::Unknown,
+            }
+        } else if url.starts_with("creativecommons.org/publicdomain/zero/") {
+            LicenseKind::Cc0
+        } else if url.starts_with("elsevier.com/open-access/userlicense/") {
+            LicenseKind::ElsevierUserLicense
+        } else if DEFAULT_LICENSES
+            .iter()
+            .any(|prefix| url.starts_with(prefix))
+        {
+            // the Creative Commons entries are classified above
+            LicenseKind::PublisherTdm
+        } else {
+            LicenseKind::Unknown
+        }
+    }
+
+    /// Whether this is a Creative Commons license, that makes the work open access.
+    pub fn is_open(self) -> bool {
+        matches!(
+            self,
+            LicenseKind::CcBy
+                | LicenseKind::CcByNc
+                | LicenseKind::CcBySa
+                | LicenseKind::CcByNd
+                | LicenseKind::Cc0
+        )
+    }
+
+    /// the short name of the license kind
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LicenseKind::CcBy => "cc-by",
+            LicenseKind::CcByNc => "cc-by-nc",
+            LicenseKind::CcBySa => "cc-by-sa",
+            LicenseKind::CcByNd => "cc-by-nd",
+            LicenseKind::Cc0 => "cc0",
+            LicenseKind::PublisherTdm => "publisher-tdm",
+            LicenseKind::ElsevierUserLicense => "elsevier-user-license",
+            LicenseKind::Unknown => "unknown",
+        }
+    }
+}
+
+impl fmt::Display for LicenseKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl License {
+    /// the kind of the license derived from its url
+    pub fn kind(&self) -> LicenseKind {
+        LicenseKind::classify(&self.url)
+    }
+}
+
+/// the url without scheme and `www.`, lowercased
+pub(crate) fn normalize_url(url: &str) -> String {
+    let url = url.trim().to_lowercase();
+    let url = url
+        .trim_start_matches("https://")
+        .trim_start_matches("http://");
+    url.trim_start_matches("www.").to_string()
+}
+
+/// How a work can be read, in the usual open access terms
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OpenAccessStatus {
+    /// the version of record is openly licensed from publication on
+    Gold,
+    /// the version of record is openly licensed next to a publisher license or only after an embargo
+    Hybrid,
+    /// only the accepted manuscript is openly licensed
+    Green,
+    /// no version is openly licensed
+    Closed,
+}
+
+impl OpenAccessStatus {
+    /// the lowercase name of the status
+    pub fn as_str(self) -> &'static str {
+        match self {
+            OpenAccessStatus::Gold => "gold",
+            OpenAccessStatus::Hybrid => "hybrid",
+            OpenAccessStatus::Green => "green",
+            OpenAccessStatus::Closed => "closed",
+        }
+    }
+}
+
+impl fmt::Display for OpenAccessStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl Work {
+    /// The open access status of the work at the date `at`.
+    ///
+    /// Crossref does not know whether a journal is fully open access, so this is derived from the licenses:
+    /// an open license for the version of record in force at `at` makes the work `Gold` if it has no delay
+    /// and no other license applies to the version of record, otherwise `Hybrid`.
+    /// An open license for the accepted manuscript, or an openly licensed link to it, makes the work `Green`.
+    ///
+    /// # Example
+    ///
+    /// ```edition2018
+    /// use chrono::NaiveDate;
+    /// use crossref::tdm::OpenAccessStatus;
+    /// use crossref::Crossref;
+    /// # fn run() -> Result<(), crossref::Error> {
+    /// let client = Crossref::builder().build()?;
+    ///
+    /// let work = client.work("10.1371/journal.pone.0033693")?;
+    /// let status = work.open_access_status(NaiveDate::from_ymd_opt(2020, 1, 1).unwrap());
+    /// assert_eq!(OpenAccessStatus::Gold, status);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn open_access_status(&self, at: NaiveDate) -> OpenAccessStatus {
+        let licenses = self.license.as_deref().unwrap_or_default();
+        let in_force = |license: &License| {
+            license_start(license, self)
+                .map(|start| start <= at)
+                .unwrap_or_default()
+        };
+        let is_vor = |license: &License| {
+            license.content_version == "vor" || license.content_version == "unspecified"
+        };
+
+        let open_vor: Vec<_> = licenses
+            .iter()
+            .filter(|license| is_vor(license) && license.kind().is_open() && in_force(license))
+            .collect();
+        if !open_vor.is_empty() {
+            let immediate = open_vor.iter().any(|license| license.delay_in_days <= 0);
+            let restricted = licenses
+                .iter()
+                .any(|license| is_vor(license) && !license.kind().is_open());
+            return if immediate && !restricted {
+                OpenAccessStatus::Gold
+            } else {
+                OpenAccessStatus::Hybrid
+            };
+        }
+
+        let open_am = licenses.iter().any(|license| {
+            license.content_version == "am" && license.kind().is_open() && in_force(license)
+        });
+        let open_am_link = self.text_mining_links_at(at).iter().any(|link| {
+            link.link.content_version == "am"
+                && link.in_force
+                && link.license.map(|l| l.kind().is_open()).unwrap_or_default()
+        });
+        if open_am || open_am_link {
+            OpenAccessStatus::Green
+        } else {
+            OpenAccessStatus::Closed
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::*;
+
+    fn work(licenses: Value) -> Work {
+        from_value(json!({
+            "DOI": "10.5555/oa",
+            "is-referenced-by-count": 0,
+            "title": ["OA"],
+            "issued": {"date-parts": [[2019, 6, 13]]},
+            "license": licenses
+        }))
+        .unwrap()
+    }
+
+    fn license(url: &str, version: &str, start: [u32; 3], delay: i32) -> Value {
+        json!({"URL": url, "content-version": version, "delay-in-days": delay, "start": {"date-parts": [start]}})
+    }
+
+    #[test]
+    fn classify_licenses() {
+        let cases = [
+            (
+                "http://creativecommons.org/licenses/by/4.0",
+                LicenseKind::CcBy,
+            ),
+            (
+                "https://creativecommons.org/licenses/by-sa/3.0/",
+                LicenseKind::CcBySa,
+            ),
+            (
+                "http://creativecommons.org/licenses/by-nc-sa/4.0/",
+                LicenseKind::CcByNc,
+            ),
+            (
+                "http://creativecommons.org/licenses/by-nd/4.0/",
+                LicenseKind::CcByNd,
+            ),
+            (
+                "https://creativecommons.org/publicdomain/zero/1.0/",
+                LicenseKind::Cc0,
+            ),
+            (
+                "https://www.elsevier.com/tdm/userlicense/1.0/",
+                LicenseKind::PublisherTdm,
+            ),
+            (
+                "http://doi.wiley.com/10.1002/tdm_license_1.1",
+                LicenseKind::PublisherTdm,
+            ),
+            ("http://www.springer.com/tdm", LicenseKind::PublisherTdm),
+            (
+                "https://www.elsevier.com/open-access/userlicense/1.0/",
+                LicenseKind::ElsevierUserLicense,
+            ),
+            (
+                "http://creativecommons.org/publicdomain/mark/1.0/",
+                LicenseKind::Unknown,
+            ),
+            (
+                "https://www.elsevier.com/legal/tdmrep-license",
+                LicenseKind::Unknown,
+            ),
+        ];
+        for (url, kind) in cases.iter() {
+            assert_eq!(*kind, LicenseKind::classify(url), "{}", url);
+        }
+        assert!(LicenseKind::Cc0.is_open());
+        assert!(!LicenseKind::PublisherTdm.is_open());
+    }
+
+    #[test]
+    fn open_access_status() {
+        let at = NaiveDate::from_ymd_opt(2020, 1, 1).unwrap();
+
+        let gold = work(json!([license(
+            "http://creativecommons.org/licenses/by/4.0/",
+            "vor",
+            [2019, 6, 13],
+            0
+        )]));
+        assert_eq!(OpenAccessStatus::Gold, gold.open_access_status(at));
+
+        let hybrid = work(json!([
+            license(
+                "https://www.elsevier.com/tdm/userlicense/1.0/",
+                "tdm",
+                [2019, 6, 13],
+                0
+            ),
+            license(
+                "https://www.elsevier.com/legal/tdmrep-license",
+                "vor",
+                [2019, 6, 13],
+                0
+            ),
+            license(
+                "http://creativecommons.org/licenses/by/4.0/",
+                "vor",
+                [2019, 6, 13],
+                0
+            )
+        ]));
+        assert_eq!(OpenAccessStatus::Hybrid, hybrid.open_access_status(at));
+
+        let embargo = work(json!([license(
+            "http://creativecommons.org/licenses/by-nc-nd/4.0/",
+            "vor",
+            [2019, 12, 13],
+            183
+        )]));
+        assert_eq!(OpenAccessStatus::Hybrid, embargo.open_access_status(at));
+        assert_eq!(
+            OpenAccessStatus::Closed,
+            embargo.open_access_status(NaiveDate::from_ymd_opt(2019, 12, 12).unwrap())
+        );
+
+        let green = work(json!([
+            license(
+                "https://www.elsevier.com/tdm/userlicense/1.0/",
+                "vor",
+                [2019, 6, 13],
+                0
+            ),
+            license(
+                "http://creativecommons.org/licenses/by-nc-nd/4.0/",
+                "am",
+                [2019, 6, 13],
+                0
+            )
+        ]));
+        assert_eq!(OpenAccessStatus::Green, green.open_access_status(at));
+
+        let closed = work(json!([license(
+            "https://www.elsevier.com/tdm/userlicense/1.0/",
+            "tdm",
+            [2019, 6, 13],
+            0
+        )]));
+        assert_eq!(OpenAccessStatus::Closed, closed.open_access_status(at));
+    }
+}
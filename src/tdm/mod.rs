@@ -1 +1,233 @@
+//! Resolves the full-text links of a `Work` that are intended for text and data mining.
+//!
+//! See the [Crossref TDM documentation](https://www.crossref.org/documentation/retrieve-metadata/rest-api/text-and-data-mining/)
 
+use crate::response::work::{License, PartialDate, ResourceLink, Work};
+use chrono::{Duration, NaiveDate, Utc};
+use std::cmp::Ordering;
+
+mod download;
+mod license;
+
+pub use self::download::{
+    storage_path, DownloadStatus, Downloader, DownloaderBuilder, ManifestEntry, MANIFEST,
+};
+pub use self::license::{LicenseKind, OpenAccessStatus, DEFAULT_LICENSES};
+
+/// A full-text link intended for text mining together with the license that applies to it
+#[derive(Debug, Clone, PartialEq)]
+pub struct TdmLink<'a> {
+    /// the link as deposited by the publisher
+    pub link: &'a ResourceLink,
+    /// the license with the same content version as the link, if any
+    pub license: Option<&'a License>,
+    /// the date from which on the license applies
+    pub license_start: Option<NaiveDate>,
+    /// whether the license is in force at the date the links were resolved for
+    pub in_force: bool,
+}
+
+impl<'a> TdmLink<'a> {
+    /// the url of the full text
+    pub fn url(&self) -> &str {
+        &self.link.url
+    }
+
+    /// the content type of the full text, `unspecified` if the publisher did not deposit one
+    pub fn content_type(&self) -> &str {
+        self.link.content_type.as_deref().unwrap_or("unspecified")
+    }
+
+    /// the url of the applicable license
+    pub fn license_url(&self) -> Option<&str> {
+        self.license.map(|license| license.url.as_str())
+    }
+}
+
+impl Work {
+    /// The full-text links of the work intended for `text-mining` with the licenses in force today.
+    ///
+    /// The links are ranked by content type, XML before PDF before plain text before anything else,
+    /// and then by version, the version of record before the accepted manuscript.
+    ///
+    /// # Example
+    ///
+    /// ```edition2018
+    /// use crossref::Crossref;
+    /// # fn run() -> Result<(), crossref::Error> {
+    /// let client = Crossref::builder().build()?;
+    ///
+    /// let work = client.work("10.1016/j.cell.2019.05.031")?;
+    /// if let Some(link) = work.text_mining_links().iter().find(|link| link.in_force) {
+    ///     println!("{} {}", link.content_type(), link.url());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn text_mining_links(&self) -> Vec<TdmLink<'_>> {
+        self.text_mining_links_at(Utc::now().naive_utc().date())
+    }
+
+    /// The full-text links of the work intended for `text-mining` with the licenses in force at `date`.
+    ///
+    /// Ranked the same way as [`text_mining_links`](#method.text_mining_links).
+    pub fn text_mining_links_at(&self, date: NaiveDate) -> Vec<TdmLink<'_>> {
+        let licenses = self.license.as_deref().unwrap_or_default();
+        let mut links: Vec<_> = self
+            .link
+            .iter()
+            .flatten()
+            .filter(|link| link.intended_application == "text-mining")
+            .map(|link| {
+                let license = applicable_license(link, licenses, self, date);
+                let license_start = license.and_then(|license| license_start(license, self));
+                TdmLink {
+                    link,
+                    license,
+                    license_start,
+                    in_force: license_start.map(|start| start <= date).unwrap_or_default(),
+                }
+            })
+            .collect();
+        links.sort_by_key(|link| rank(link.link));
+        links
+    }
+}
+
+/// the first day of the date, missing months and days count as the first one
+pub(crate) fn first_day(date: &PartialDate) -> Option<NaiveDate> {
+    let (year, month, day) = date.ymd()?;
+    NaiveDate::from_ymd_opt(year, month.unwrap_or(1), day.unwrap_or(1))
+}
+
+/// The date from which on the license applies.
+///
+/// This is the `start` of the license, crossref already includes the `delay-in-days` there.
+/// If the start is missing the delay is added to the publication date of the work.
+pub(crate) fn license_start(license: &License, work: &Work) -> Option<NaiveDate> {
+    first_day(&license.start).or_else(|| {
+        let published = first_day(work.published()?)?;
+        Some(published + Duration::days(i64::from(license.delay_in_days)))
+    })
+}
+
+/// The license for the version of the link.
+///
+/// Licenses for the same version are preferred over `tdm` and `unspecified` ones,
+/// among those the one in force at `date` that started last wins.
+fn applicable_license<'a>(
+    link: &ResourceLink,
+    licenses: &'a [License],
+    work: &Work,
+    date: NaiveDate,
+) -> Option<&'a License> {
+    let candidates = [link.content_version.as_str(), "tdm", "unspecified"];
+    candidates.iter().find_map(|version| {
+        licenses
+            .iter()
+            .filter(|license| license.content_version == *version)
+            .max_by(|a, b| {
+                let a = license_start(a, work);
+                let b = license_start(b, work);
+                let a_in_force = a.map(|start| start <= date).unwrap_or_default();
+                let b_in_force = b.map(|start| start <= date).unwrap_or_default();
+                match a_in_force.cmp(&b_in_force) {
+                    Ordering::Equal if a_in_force => a.cmp(&b),
+                    Ordering::Equal => b.cmp(&a),
+                    ordering => ordering,
+                }
+            })
+    })
+}
+
+/// lower is better, content type first then version
+fn rank(link: &ResourceLink) -> (u8, u8) {
+    let content_type = link
+        .content_type
+        .as_ref()
+        .map(|c| c.to_lowercase())
+        .unwrap_or_default();
+    let content_type = if content_type.contains("xml") {
+        0
+    } else if content_type.contains("pdf") {
+        1
+    } else if content_type.starts_with("text/plain") {
+        2
+    } else {
+        3
+    };
+    let version = match link.content_version.as_str() {
+        "vor" => 0,
+        "am" => 1,
+        _ => 2,
+    };
+    (content_type, version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::*;
+
+    #[test]
+    fn rank_text_mining_links() {
+        let work_str = r#"{"DOI":"10.5555/tdm","is-referenced-by-count":0,"title":["TDM"],"issued":{"date-parts":[[2019,6,13]]},
+        "license":[
+            {"URL":"https://www.elsevier.com/tdm/userlicense/1.0/","start":{"date-parts":[[2019,6,1]]},"delay-in-days":0,"content-version":"tdm"},
+            {"URL":"http://creativecommons.org/licenses/by/4.0/","start":{"date-parts":[[2020,6,13]]},"delay-in-days":366,"content-version":"am"}
+        ],
+        "link":[
+            {"URL":"https://example.org/tdm.pdf","content-type":"application/pdf","content-version":"vor","intended-application":"text-mining"},
+            {"URL":"https://example.org/sim.pdf","content-type":"application/pdf","content-version":"vor","intended-application":"similarity-checking"},
+            {"URL":"https://example.org/am.xml","content-type":"text/xml","content-version":"am","intended-application":"text-mining"},
+            {"URL":"https://example.org/vor.xml","content-type":"text/xml","content-version":"vor","intended-application":"text-mining"},
+            {"URL":"https://example.org/tdm","content-type":"unspecified","content-version":"vor","intended-application":"text-mining"},
+            {"URL":"https://example.org/tdm.txt","content-type":"text/plain","content-version":"vor","intended-application":"text-mining"}
+        ]}"#;
+        let work: Work = from_str(work_str).unwrap();
+
+        let links = work.text_mining_links_at(NaiveDate::from_ymd_opt(2020, 1, 1).unwrap());
+        let urls: Vec<_> = links.iter().map(TdmLink::url).collect();
+        assert_eq!(
+            vec![
+                "https://example.org/vor.xml",
+                "https://example.org/am.xml",
+                "https://example.org/tdm.pdf",
+                "https://example.org/tdm.txt",
+                "https://example.org/tdm",
+            ],
+            urls
+        );
+
+        assert_eq!(
+            Some("https://www.elsevier.com/tdm/userlicense/1.0/"),
+            links[0].license_url()
+        );
+        assert!(links[0].in_force);
+        assert_eq!(
+            Some("http://creativecommons.org/licenses/by/4.0/"),
+            links[1].license_url()
+        );
+        assert_eq!(
+            Some(NaiveDate::from_ymd_opt(2020, 6, 13).unwrap()),
+            links[1].license_start
+        );
+        assert!(!links[1].in_force);
+
+        let links = work.text_mining_links_at(NaiveDate::from_ymd_opt(2020, 6, 13).unwrap());
+        assert!(links[1].in_force);
+    }
+
+    #[test]
+    fn license_start_from_delay() {
+        let work_str = r#"{"DOI":"10.5555/tdm","is-referenced-by-count":0,"title":["TDM"],"issued":{"date-parts":[[2019,6]]},
+        "license":[{"URL":"http://creativecommons.org/licenses/by/4.0/","start":{"date-parts":[[null]]},"delay-in-days":30,"content-version":"vor"}]}"#;
+        let work: Work = from_str(work_str).unwrap();
+        let license = &work.license.as_ref().unwrap()[0];
+
+        assert_eq!(
+            Some(NaiveDate::from_ymd_opt(2019, 7, 1).unwrap()),
+            license_start(license, &work)
+        );
+    }
+}
@@ -0,0 +1,54 @@
+//! helpers shared by the unit tests
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::thread;
+
+/// Serves the `(status, headers, body)` responses in order, one connection each.
+///
+/// Returns the url of the server and the heads of the received requests.
+/// `headers` are raw header lines, each terminated by `\r\n`.
+pub fn serve(
+    responses: Vec<(&'static str, &'static str, &'static str)>,
+) -> (String, thread::JoinHandle<Vec<String>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let handle = thread::spawn(move || {
+        let mut requests = Vec::new();
+        for (status, headers, body) in responses {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = String::new();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                request.push_str(&line);
+            }
+            write!(
+                stream,
+                "HTTP/1.1 {}\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                headers,
+                body.len(),
+                body
+            )
+            .unwrap();
+            requests.push(request);
+        }
+        requests
+    });
+    (url, handle)
+}
+
+/// serves a single request with `status` and `body`, returns the url and the received request
+pub fn serve_once(
+    status: &'static str,
+    body: &'static str,
+) -> (String, thread::JoinHandle<String>) {
+    let (url, handle) = serve(vec![(status, "", body)]);
+    let handle = thread::spawn(move || handle.join().unwrap().remove(0));
+    (url, handle)
+}
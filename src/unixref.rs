@@ -0,0 +1,990 @@
+// see https://www.crossref.org/documentation/retrieve-metadata/xml-api/ for unixref and unixsd
+
+use crate::error::{ErrorKind, Result};
+use crate::response::work::{
+    Affiliation, Contributor, DateParts, FundingBody, License, PartialDate, Reference, Relations,
+    ResourceLink, Work, ISSN,
+};
+use crate::{CnFormat, Crossref};
+use chrono::{Datelike, NaiveDate};
+use roxmltree::{Document, Node};
+use serde_json::{json, Map, Value};
+
+/// Reads all records of a unixref or unixsd document, as returned by content negotiation
+/// with `CnFormat::CrossrefXml` and `CnFormat::CrossrefTdm`.
+///
+/// Journal, book, conference, dissertation, report, dataset and posted content records are supported.
+///
+/// # Errors
+///
+/// This fails if the document is not valid xml or a record holds an `<error>` instead of metadata.
+pub fn parse(xml: &str) -> Result<Vec<Work>> {
+    let doc = Document::parse(xml).map_err(|err| ErrorKind::Xml {
+        msg: err.to_string(),
+    })?;
+    doc.descendants()
+        .filter(|node| node.tag_name().name() == "doi_record")
+        .map(record)
+        .collect()
+}
+
+impl Work {
+    /// Reads the first record of a unixref or unixsd document.
+    ///
+    /// # Example
+    ///
+    /// ```edition2018
+    /// use crossref::{Crossref, CnFormat, Work};
+    /// # fn run() -> Result<(), crossref::Error> {
+    /// let client = Crossref::builder().build()?;
+    ///
+    /// let xml = client.content_negotiate("10.1037/0003-066x.59.1.29", CnFormat::CrossrefXml)?;
+    /// let work = Work::from_unixref(&xml)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_unixref(xml: &str) -> Result<Work> {
+        parse(xml)?.into_iter().next().ok_or_else(|| {
+            ErrorKind::Xml {
+                msg: "no `doi_record` found".to_string(),
+            }
+            .into()
+        })
+    }
+}
+
+impl Crossref {
+    /// Return the `Work` of the DOI as read from its unixsd record.
+    ///
+    /// The xml records include fields the `/works` route does not,
+    /// like the `key` of each reference and the full relation programs.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the resolver could not find the DOI `DoiNotFound`
+    /// or the DOI is not registered with crossref `NotAcceptable`
+    pub fn unixsd_work(&self, doi: &str) -> Result<Work> {
+        Work::from_unixref(&self.content_negotiate(doi, CnFormat::CrossrefTdm)?)
+    }
+}
+
+/// reads a single `<doi_record>`
+fn record(doi_record: Node) -> Result<Work> {
+    let crossref = child(doi_record, "crossref").ok_or_else(|| ErrorKind::Xml {
+        msg: "missing `crossref` element".to_string(),
+    })?;
+    if let Some(error) = child(crossref, "error") {
+        return Err(ErrorKind::Xml {
+            msg: text(error).unwrap_or_else(|| "unknown error".to_string()),
+        }
+        .into());
+    }
+    let kind = crossref
+        .first_element_child()
+        .ok_or_else(|| ErrorKind::Xml {
+            msg: "empty `crossref` element".to_string(),
+        })?;
+    // the work itself is the most specific element with its own `doi_data`,
+    // the figures and tables in a `component_list` are records of their own
+    let item = kind
+        .descendants()
+        .filter(|node| child(*node, "doi_data").is_some())
+        .rfind(|node| {
+            !node
+                .ancestors()
+                .take_while(|ancestor| *ancestor != kind)
+                .any(|ancestor| ancestor.tag_name().name() == "component_list")
+        })
+        .unwrap_or(kind);
+    let container = kind.children().find(|node| {
+        let name = node.tag_name().name();
+        name.ends_with("_metadata") && name != "event_metadata"
+    });
+    let parent = container.filter(|container| *container != item);
+    let doi_data = child(item, "doi_data");
+
+    // dates are inherited from the issue and the container
+    let mut sources = vec![item];
+    sources.extend(path(kind, &["journal_issue"]));
+    sources.extend(container);
+    let dated = |name: &str, media_type: Option<&str>| {
+        sources.iter().find_map(|source| {
+            children(*source, name)
+                .find(|date| media_type.is_none() || date.attribute("media_type") == media_type)
+                .and_then(date)
+        })
+    };
+    let published_print = dated("publication_date", Some("print"));
+    let published_online = dated("publication_date", Some("online"));
+    let posted = dated("posted_date", None);
+    let accepted = dated("acceptance_date", None);
+    let issued = [&published_print, &published_online]
+        .iter()
+        .filter_map(|date| date.as_ref())
+        .min_by_key(|date| date.ymd())
+        .cloned()
+        .or_else(|| dated("publication_date", None))
+        .or_else(|| dated("approval_date", None))
+        .or_else(|| posted.clone())
+        .or_else(|| path(item, &["database_date", "publication_date"]).and_then(date))
+        .unwrap_or_else(|| PartialDate {
+            date_parts: DateParts(vec![vec![None]]),
+            extra: Map::new(),
+        });
+
+    let mut author = Vec::new();
+    let mut editor = Vec::new();
+    let mut chair = Vec::new();
+    let mut translator = Vec::new();
+    let mut people: Vec<_> = contributors(item);
+    if let Some(parent) = parent {
+        // editors of the book or proceedings a chapter or paper is part of
+        people.extend(
+            contributors(parent)
+                .into_iter()
+                .filter(|(r, _)| r == "editor"),
+        );
+    }
+    if kind != item {
+        // chairs of a conference
+        people.extend(contributors(kind).into_iter().filter(|(r, _)| r == "chair"));
+    }
+    for (role, contributor) in people {
+        match role.as_str() {
+            "author" => author.push(contributor),
+            "editor" => editor.push(contributor),
+            "chair" => chair.push(contributor),
+            "translator" => translator.push(contributor),
+            _ => (),
+        }
+    }
+
+    let mut issn = Vec::new();
+    let mut issn_type = Vec::new();
+    let mut isbn = Vec::new();
+    for node in container.iter().flat_map(|c| c.descendants()) {
+        match (node.tag_name().name(), text(node)) {
+            ("issn", Some(value)) => {
+                issn_type.push(ISSN {
+                    value: value.clone(),
+                    type_: match node.attribute("media_type") {
+                        Some("electronic") => "electronic".to_string(),
+                        _ => "print".to_string(),
+                    },
+                    extra: Map::new(),
+                });
+                issn.push(value);
+            }
+            ("isbn", Some(value)) => isbn.push(value),
+            _ => (),
+        }
+    }
+
+    let container_title = match parent {
+        Some(parent) => title(parent),
+        // books are contained in their series
+        None => container
+            .and_then(|c| path(c, &["series_metadata", "titles", "title"]))
+            .and_then(text),
+    };
+    let short_container_title = parent
+        .and_then(|parent| child(parent, "abbrev_title"))
+        .and_then(text);
+
+    let crm_items: Vec<_> = doi_record
+        .parent_element()
+        .map(|query| children(query, "crm-item").collect())
+        .unwrap_or_default();
+    let crm = |name: &str| {
+        crm_items
+            .iter()
+            .find(|item| item.attribute("name") == Some(name))
+            .and_then(|item| text(*item))
+    };
+
+    let mut extra = Map::new();
+    let institution = child(item, "institution").or_else(|| {
+        sources
+            .iter()
+            .find_map(|source| child(*source, "institution"))
+    });
+    if let Some(name) = institution
+        .and_then(|i| child(i, "institution_name"))
+        .and_then(text)
+    {
+        extra.insert("institution".to_string(), json!([{ "name": name }]));
+    }
+    if let Some(degree) = child(item, "degree").and_then(text) {
+        extra.insert("degree".to_string(), json!([degree]));
+    }
+    if let Some(event) = child(kind, "event_metadata") {
+        let mut meta = Map::new();
+        for (key, name) in &[
+            ("name", "conference_name"),
+            ("location", "conference_location"),
+            ("acronym", "conference_acronym"),
+        ] {
+            if let Some(value) = child(event, name).and_then(text) {
+                meta.insert(key.to_string(), value.into());
+            }
+        }
+        extra.insert("event".to_string(), meta.into());
+    }
+    if let Some(group_title) = child(item, "group_title").and_then(text) {
+        extra.insert("group-title".to_string(), group_title.into());
+    }
+    if kind.tag_name().name() == "posted_content" {
+        if let Some(subtype) = kind.attribute("type") {
+            extra.insert("subtype".to_string(), subtype.into());
+        }
+    }
+    if let Some(edition) = sources
+        .iter()
+        .find_map(|source| child(*source, "edition_number"))
+        .and_then(text)
+    {
+        extra.insert("edition-number".to_string(), edition.into());
+    }
+
+    let pages = child(item, "pages");
+    let first_page = pages.and_then(|p| child(p, "first_page")).and_then(text);
+    let last_page = pages.and_then(|p| child(p, "last_page")).and_then(text);
+    let page = match (first_page, last_page) {
+        (Some(first), Some(last)) => Some(format!("{}-{}", first, last)),
+        (first, _) => first,
+    };
+
+    let language = item
+        .attribute("language")
+        .or_else(|| container.and_then(|c| c.attribute("language")))
+        .map(str::to_string);
+
+    Ok(Work {
+        publisher: crm("publisher-name").or_else(|| {
+            sources
+                .iter()
+                .find_map(|source| path(*source, &["publisher", "publisher_name"]))
+                .and_then(text)
+        }),
+        title: title(item).into_iter().collect(),
+        original_title: non_empty(
+            path(item, &["titles", "original_language_title"])
+                .and_then(text)
+                .into_iter()
+                .collect(),
+        ),
+        short_title: None,
+        subtitle: non_empty(
+            path(item, &["titles", "subtitle"])
+                .and_then(text)
+                .into_iter()
+                .collect(),
+        ),
+        abstract_: children(item, "abstract").next().and_then(markup),
+        is_referenced_by_count: crm("citedby-count")
+            .and_then(|count| count.parse().ok())
+            .unwrap_or_default(),
+        prefix: doi_record.attribute("owner").map(str::to_string),
+        doi: doi_data
+            .and_then(|d| child(d, "doi"))
+            .and_then(text)
+            .ok_or_else(|| ErrorKind::Xml {
+                msg: "missing `doi_data`".to_string(),
+            })?,
+        url: doi_data.and_then(|d| child(d, "resource")).and_then(text),
+        member: crm("member-id"),
+        type_: Some(work_type(kind, item).to_string()),
+        published_print,
+        published_online,
+        posted,
+        accepted,
+        issued: issued.clone(),
+        container_title: container_title.map(|title| vec![title]),
+        short_container_title: short_container_title.map(|title| vec![title]),
+        issue: path(kind, &["journal_issue", "issue"]).and_then(text),
+        volume: path(kind, &["journal_issue", "journal_volume", "volume"])
+            .or_else(|| sources.iter().find_map(|source| child(*source, "volume")))
+            .and_then(text),
+        page,
+        article_number: child(item, "publisher_item")
+            .and_then(|publisher_item| {
+                children(publisher_item, "item_number").find(|number| {
+                    let number_type = number.attribute("item_number_type");
+                    number_type.is_none() || number_type == Some("article_number")
+                })
+            })
+            .and_then(text),
+        issn: non_empty(issn),
+        issn_type: non_empty(issn_type),
+        isbn: non_empty(isbn),
+        subject: None,
+        language,
+        license: non_empty(licenses(item, &issued)),
+        funder: non_empty(funders(item)),
+        author: non_empty(author),
+        editor: non_empty(editor),
+        chair: non_empty(chair),
+        translator: non_empty(translator),
+        link: non_empty(links(doi_data)),
+        reference: child(item, "citation_list")
+            .map(|list| children(list, "citation").map(reference).collect()),
+        relation: relations(item),
+        extra,
+    })
+}
+
+/// the crossref type of the work
+fn work_type(kind: Node, item: Node) -> &'static str {
+    match (kind.tag_name().name(), item.tag_name().name()) {
+        ("journal", "journal_article") => "journal-article",
+        ("journal", "journal_issue") => "journal-issue",
+        ("journal", "journal_volume") => "journal-volume",
+        ("journal", _) => "journal",
+        ("book", "content_item") => match item.attribute("component_type") {
+            Some("section") => "book-section",
+            Some("part") => "book-part",
+            Some("track") => "book-track",
+            Some("reference_entry") => "reference-entry",
+            _ => "book-chapter",
+        },
+        ("book", "book_series_metadata") => "book-series",
+        ("book", "book_set_metadata") => "book-set",
+        ("book", _) => match kind.attribute("book_type") {
+            Some("edited_book") => "edited-book",
+            Some("monograph") => "monograph",
+            Some("reference") => "reference-book",
+            _ => "book",
+        },
+        ("conference", "conference_paper") => "proceedings-article",
+        ("conference", _) => "proceedings",
+        ("dissertation", _) => "dissertation",
+        ("report-paper", "content_item") => "report-component",
+        ("report-paper", _) => "report",
+        ("database", "dataset") => "dataset",
+        ("database", _) => "database",
+        ("posted_content", _) => "posted-content",
+        ("standard", _) => "standard",
+        ("peer_review", _) => "peer-review",
+        ("sa_component", _) => "component",
+        _ => "other",
+    }
+}
+
+/// the title of an item, containers use different element names for it
+fn title(node: Node) -> Option<String> {
+    path(node, &["titles", "title"])
+        .or_else(|| child(node, "full_title"))
+        .or_else(|| child(node, "proceedings_title"))
+        .and_then(text)
+}
+
+/// all persons and organizations of the item with their role
+fn contributors(item: Node) -> Vec<(String, Contributor)> {
+    // dissertations list their authors without a `contributors` element
+    let list = child(item, "contributors").unwrap_or(item);
+    list.children()
+        .filter(Node::is_element)
+        .filter_map(|node| {
+            let role = node.attribute("contributor_role").unwrap_or("author");
+            let mut contributor = Contributor {
+                family: None,
+                given: None,
+                name: None,
+                suffix: None,
+                orcid: None,
+                sequence: node.attribute("sequence").map(str::to_string),
+                affiliation: None,
+                extra: Map::new(),
+            };
+            match node.tag_name().name() {
+                "person_name" => {
+                    contributor.family = child(node, "surname").and_then(text);
+                    contributor.given = child(node, "given_name").and_then(text);
+                    contributor.suffix = child(node, "suffix").and_then(text);
+                    contributor.orcid = child(node, "ORCID").and_then(text);
+                    let affiliations: Vec<_> = node
+                        .descendants()
+                        .filter(|n| {
+                            let name = n.tag_name().name();
+                            name == "affiliation" || name == "institution_name"
+                        })
+                        .filter_map(text)
+                        .map(|name| Affiliation {
+                            name,
+                            extra: Map::new(),
+                        })
+                        .collect();
+                    contributor.affiliation = Some(affiliations);
+                }
+                "organization" => contributor.name = text(node),
+                _ => return None,
+            }
+            Some((role.to_string(), contributor))
+        })
+        .collect()
+}
+
+/// the licenses of the `AccessIndicators` program
+fn licenses(item: Node, issued: &PartialDate) -> Vec<License> {
+    let published = issued.ymd().and_then(|(year, month, day)| {
+        NaiveDate::from_ymd_opt(year, month.unwrap_or(1), day.unwrap_or(1))
+    });
+    item.descendants()
+        .filter(|node| node.tag_name().name() == "license_ref")
+        .filter_map(|node| {
+            let url = text(node)?;
+            let start_date = node
+                .attribute("start_date")
+                .and_then(|start| NaiveDate::parse_from_str(start, "%Y-%m-%d").ok());
+            let delay_in_days = match (start_date, published) {
+                (Some(start), Some(published)) => (start - published).num_days().max(0) as i32,
+                _ => 0,
+            };
+            let start = match start_date {
+                Some(start) => PartialDate {
+                    date_parts: DateParts(vec![vec![
+                        Some(start.year() as u32),
+                        Some(start.month()),
+                        Some(start.day()),
+                    ]]),
+                    extra: Map::new(),
+                },
+                None => issued.clone(),
+            };
+            Some(License {
+                content_version: node
+                    .attribute("applies_to")
+                    .unwrap_or("unspecified")
+                    .to_string(),
+                delay_in_days,
+                start,
+                url,
+                extra: Map::new(),
+            })
+        })
+        .collect()
+}
+
+/// the full-text links of the `doi_data` collections
+fn links(doi_data: Option<Node>) -> Vec<ResourceLink> {
+    let doi_data = match doi_data {
+        Some(doi_data) => doi_data,
+        None => return Vec::new(),
+    };
+    children(doi_data, "collection")
+        .flat_map(|collection| {
+            let intended_application = match collection.attribute("property") {
+                Some("text-mining") => "text-mining",
+                Some("crawler-based") => "similarity-checking",
+                _ => "unspecified",
+            };
+            collection
+                .descendants()
+                .filter(|node| node.tag_name().name() == "resource")
+                .filter_map(move |resource| {
+                    Some(ResourceLink {
+                        intended_application: intended_application.to_string(),
+                        content_version: resource
+                            .attribute("content_version")
+                            .unwrap_or("unspecified")
+                            .to_string(),
+                        url: text(resource)?,
+                        content_type: Some(
+                            resource
+                                .attribute("mime_type")
+                                .unwrap_or("unspecified")
+                                .to_string(),
+                        ),
+                        extra: Map::new(),
+                    })
+                })
+        })
+        .collect()
+}
+
+/// the funders of the `fundref` program
+fn funders(item: Node) -> Vec<FundingBody> {
+    let program = match item.descendants().find(|node| {
+        node.tag_name().name() == "program" && node.attribute("name") == Some("fundref")
+    }) {
+        Some(program) => program,
+        None => return Vec::new(),
+    };
+    let groups: Vec<_> = assertions(program, "fundgroup").collect();
+    let groups = if groups.is_empty() {
+        vec![program]
+    } else {
+        groups
+    };
+    groups
+        .into_iter()
+        .filter_map(|group| {
+            let funder = assertions(group, "funder_name").next()?;
+            // the name is the text of the assertion without the nested identifier
+            let name: String = funder
+                .children()
+                .filter(Node::is_text)
+                .filter_map(|n| n.text())
+                .collect();
+            let doi = assertions(group, "funder_identifier")
+                .next()
+                .and_then(text)
+                .map(|id| match id.find("10.") {
+                    Some(start) => id[start..].to_string(),
+                    None => id,
+                });
+            let award: Vec<_> = assertions(group, "award_number").filter_map(text).collect();
+            Some(FundingBody {
+                name: name.split_whitespace().collect::<Vec<_>>().join(" "),
+                doi_asserted_by: doi.as_ref().map(|_| "publisher".to_string()),
+                doi,
+                award: Some(award),
+                extra: Map::new(),
+            })
+        })
+        .collect()
+}
+
+/// all `<assertion>` elements with the `name` below the `node`
+fn assertions<'a, 'i: 'a>(
+    node: Node<'a, 'i>,
+    name: &'static str,
+) -> impl Iterator<Item = Node<'a, 'i>> {
+    node.descendants()
+        .filter(move |n| n.tag_name().name() == "assertion" && n.attribute("name") == Some(name))
+}
+
+/// a single `<citation>` of the `citation_list`
+fn reference(citation: Node) -> Reference {
+    let mut reference = Reference {
+        key: citation.attribute("key").map(str::to_string),
+        ..Reference::default()
+    };
+    for node in citation.children().filter(Node::is_element) {
+        let value = text(node);
+        let field = match node.tag_name().name() {
+            "doi" => &mut reference.doi,
+            "cYear" => &mut reference.year,
+            "unstructured_citation" => &mut reference.unstructured,
+            "author" => &mut reference.author,
+            "article_title" => &mut reference.article_title,
+            "journal_title" => &mut reference.journal_title,
+            "series_title" => &mut reference.series_title,
+            "volume_title" => &mut reference.volume_title,
+            "volume" => &mut reference.volume,
+            "issue" => &mut reference.issue,
+            "first_page" => &mut reference.first_page,
+            "edition_number" => &mut reference.edition,
+            "component_number" => &mut reference.component,
+            "std_designator" => &mut reference.standard_designator,
+            "standards_body" => &mut reference.standards_body,
+            "issn" => &mut reference.issn,
+            "isbn" => &mut reference.isbn,
+            _ => continue,
+        };
+        if value.is_some() {
+            *field = value;
+        }
+    }
+    reference
+}
+
+/// the related items of the relations program, by relation type
+fn relations(item: Node) -> Option<Relations> {
+    let mut relations = Relations::new();
+    for node in item.descendants().filter(|node| {
+        let name = node.tag_name().name();
+        name == "inter_work_relation" || name == "intra_work_relation"
+    }) {
+        let (relation_type, id) = match (node.attribute("relationship-type"), text(node)) {
+            (Some(relation_type), Some(id)) => (relation_type, id),
+            _ => continue,
+        };
+        // `isSupplementedBy` becomes `is-supplemented-by`
+        let mut key = String::with_capacity(relation_type.len() + 4);
+        for c in relation_type.chars() {
+            if c.is_uppercase() {
+                key.push('-');
+            }
+            key.extend(c.to_lowercase());
+        }
+        let relation = json!({
+            "id-type": node.attribute("identifier-type").unwrap_or("other"),
+            "id": id,
+            "asserted-by": "subject",
+        });
+        if let Value::Array(related) = relations
+            .entry(key)
+            .or_insert_with(|| Value::Array(Vec::new()))
+        {
+            related.push(relation);
+        }
+    }
+    if relations.is_empty() {
+        None
+    } else {
+        Some(relations)
+    }
+}
+
+/// a date of `<year>`, `<month>` and `<day>` elements
+fn date(node: Node) -> Option<PartialDate> {
+    let part = |name| child(node, name).and_then(text)?.parse::<u32>().ok();
+    let mut parts = vec![Some(part("year")?)];
+    if let Some(month) = part("month") {
+        parts.push(Some(month));
+        parts.extend(part("day").map(Some));
+    }
+    Some(PartialDate {
+        date_parts: DateParts(vec![parts]),
+        extra: Map::new(),
+    })
+}
+
+/// the raw markup inside an element, like the JATS paragraphs of an abstract
+fn markup(node: Node) -> Option<String> {
+    let start = node.first_child()?.range().start;
+    let end = node.last_child()?.range().end;
+    let markup = node.document().input_text()[start..end].trim();
+    if markup.is_empty() {
+        None
+    } else {
+        Some(markup.to_string())
+    }
+}
+
+/// the first child element with the local `name`
+fn child<'a, 'i>(node: Node<'a, 'i>, name: &str) -> Option<Node<'a, 'i>> {
+    children(node, name).next()
+}
+
+/// all child elements with the local `name`
+fn children<'a, 'i: 'a, 'n>(
+    node: Node<'a, 'i>,
+    name: &'n str,
+) -> impl Iterator<Item = Node<'a, 'i>> + 'n
+where
+    'a: 'n,
+{
+    node.children()
+        .filter(move |child| child.is_element() && child.tag_name().name() == name)
+}
+
+/// follows the child elements along the `path`
+fn path<'a, 'i>(node: Node<'a, 'i>, path: &[&str]) -> Option<Node<'a, 'i>> {
+    path.iter().try_fold(node, |node, name| child(node, name))
+}
+
+/// the whole text of an element including nested markup, with collapsed whitespace
+fn text(node: Node) -> Option<String> {
+    let text: String = node
+        .descendants()
+        .filter(Node::is_text)
+        .filter_map(|n| n.text())
+        .collect();
+    let text: Vec<_> = text.split_whitespace().collect();
+    if text.is_empty() {
+        None
+    } else {
+        Some(text.join(" "))
+    }
+}
+
+/// `None` for empty lists, like the optional lists of a `Work`
+fn non_empty<T>(values: Vec<T>) -> Option<Vec<T>> {
+    if values.is_empty() {
+        None
+    } else {
+        Some(values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(xml: &str) -> Work {
+        let mut works = parse(xml).unwrap();
+        assert_eq!(1, works.len());
+        works.remove(0)
+    }
+
+    #[test]
+    fn journal_article() {
+        let work = fixture(include_str!("../tests/fixtures/unixref/journal.xml"));
+
+        assert_eq!("10.1037/0003-066x.59.1.29", work.doi);
+        assert_eq!(Some("journal-article"), work.type_.as_deref());
+        assert_eq!(vec!["How the Mind Hurts and Heals the Body."], work.title);
+        assert_eq!(
+            Some("American Psychological Association (APA)"),
+            work.publisher.as_deref()
+        );
+        assert_eq!(Some("15"), work.member.as_deref());
+        assert_eq!(Some("10.1037"), work.prefix.as_deref());
+        assert_eq!(84, work.is_referenced_by_count);
+        assert_eq!(
+            Some(vec!["American Psychologist".to_string()]),
+            work.container_title
+        );
+        assert_eq!(
+            Some(vec!["Am Psychol".to_string()]),
+            work.short_container_title
+        );
+        assert_eq!(
+            Some(vec!["0003-066X".to_string(), "1935-990X".to_string()]),
+            work.issn
+        );
+        assert_eq!("electronic", work.issn_type.as_ref().unwrap()[1].type_);
+        assert_eq!(
+            (Some("59"), Some("1")),
+            (work.volume.as_deref(), work.issue.as_deref())
+        );
+        assert_eq!(Some("29-40"), work.page.as_deref());
+        assert_eq!(Some("en"), work.language.as_deref());
+        assert_eq!(
+            Some((2004, None, None)),
+            work.published_print.as_ref().unwrap().ymd()
+        );
+        assert_eq!(
+            Some((2004, Some(1), Some(21))),
+            work.published_online.as_ref().unwrap().ymd()
+        );
+        assert_eq!(Some((2004, None, None)), work.issued.ymd());
+
+        let authors = work.author.as_ref().unwrap();
+        assert_eq!(Some("Ray"), authors[0].family.as_deref());
+        assert_eq!(Some("first"), authors[0].sequence.as_deref());
+        assert_eq!(
+            Some("http://orcid.org/0000-0002-1825-0097"),
+            authors[0].orcid.as_deref()
+        );
+        assert_eq!(
+            "Vanderbilt University",
+            authors[0].affiliation.as_ref().unwrap()[0].name
+        );
+        assert_eq!(Some("APA Task Force"), authors[1].name.as_deref());
+
+        assert_eq!(
+            Some("<jats:p>Mind and <jats:italic>body</jats:italic>.</jats:p>"),
+            work.abstract_.as_deref()
+        );
+
+        let funder = &work.funder.as_ref().unwrap()[0];
+        assert_eq!("National Institutes of Health", funder.name);
+        assert_eq!(Some("10.13039/100000002"), funder.doi.as_deref());
+        assert_eq!(Some(vec!["R01 MH-12345".to_string()]), funder.award);
+
+        let licenses = work.license.as_ref().unwrap();
+        assert_eq!(
+            "http://creativecommons.org/licenses/by/4.0/",
+            licenses[0].url
+        );
+        assert_eq!("vor", licenses[0].content_version);
+        assert_eq!(200, licenses[0].delay_in_days);
+        assert_eq!("tdm", licenses[1].content_version);
+
+        let links = work.link.as_ref().unwrap();
+        assert_eq!("text-mining", links[0].intended_application);
+        assert_eq!(Some("application/pdf"), links[0].content_type.as_deref());
+        assert_eq!("similarity-checking", links[1].intended_application);
+
+        let references = work.reference.as_ref().unwrap();
+        assert_eq!(
+            Some("10.1097/00006842-197507000-00007"),
+            references[0].doi.as_deref()
+        );
+        assert_eq!(Some("1975"), references[0].year.as_deref());
+        assert_eq!(Some("2004-10043-004_ref1"), references[0].key.as_deref());
+        assert_eq!(
+            Some("Psychosomatic Medicine"),
+            references[0].journal_title.as_deref()
+        );
+        assert_eq!(Some("333"), references[0].first_page.as_deref());
+        assert!(references[1]
+            .unstructured
+            .as_ref()
+            .unwrap()
+            .starts_with("Cannon"));
+
+        let relation = work.relation.as_ref().unwrap();
+        assert_eq!("10.5555/data.1", relation["is-supplemented-by"][0]["id"]);
+        assert_eq!("doi", relation["is-supplemented-by"][0]["id-type"]);
+    }
+
+    #[test]
+    fn journal_article_with_components() {
+        let work = fixture(include_str!(
+            "../tests/fixtures/unixref/journal_components.xml"
+        ));
+
+        assert_eq!("10.1371/journal.pone.0217958", work.doi);
+        assert_eq!(Some("journal-article"), work.type_.as_deref());
+        assert_eq!(
+            vec!["Sleep duration and memory in older adults"],
+            work.title
+        );
+        assert_eq!(Some(vec!["PLOS ONE".to_string()]), work.container_title);
+        assert_eq!(Some("e0217958"), work.article_number.as_deref());
+        assert_eq!(Some((2019, Some(6), Some(10))), work.issued.ymd());
+    }
+
+    #[test]
+    fn book_chapter() {
+        let work = fixture(include_str!("../tests/fixtures/unixref/book_chapter.xml"));
+
+        assert_eq!("10.1007/978-3-319-00001-7_1", work.doi);
+        assert_eq!(Some("book-chapter"), work.type_.as_deref());
+        assert_eq!(vec!["The Difference Engine"], work.title);
+        assert_eq!(Some(vec!["A Retrospective".to_string()]), work.subtitle);
+        assert_eq!(
+            Some(vec!["Handbook of Analytical Engines".to_string()]),
+            work.container_title
+        );
+        assert_eq!(
+            Some("Springer International Publishing"),
+            work.publisher.as_deref()
+        );
+        assert_eq!(
+            Some("Jr."),
+            work.author.as_ref().unwrap()[0].suffix.as_deref()
+        );
+        assert_eq!(
+            Some("Lovelace"),
+            work.editor.as_ref().unwrap()[0].family.as_deref()
+        );
+        assert_eq!(
+            Some("Menabrea"),
+            work.translator.as_ref().unwrap()[0].family.as_deref()
+        );
+        assert_eq!(2, work.isbn.as_ref().unwrap().len());
+        assert_eq!(Some(vec!["0302-9743".to_string()]), work.issn);
+        assert_eq!(Some("11000"), work.volume.as_deref());
+        assert_eq!(Some("1-24"), work.page.as_deref());
+        assert_eq!("2", work.extra["edition-number"]);
+        assert_eq!(Some((2019, None, None)), work.issued.ymd());
+        assert_eq!(
+            Some((2019, Some(5), Some(3))),
+            work.published_online.as_ref().unwrap().ymd()
+        );
+    }
+
+    #[test]
+    fn conference_paper() {
+        let work = fixture(include_str!("../tests/fixtures/unixref/conference.xml"));
+
+        assert_eq!(Some("proceedings-article"), work.type_.as_deref());
+        assert_eq!(vec!["Compiling & Linking"], work.title);
+        assert_eq!(
+            Some(vec![
+                "Proceedings of the 47th Symposium on Principles of Programming Languages"
+                    .to_string()
+            ]),
+            work.container_title
+        );
+        assert_eq!(Some("ACM"), work.publisher.as_deref());
+        assert_eq!(
+            Some("Liskov"),
+            work.chair.as_ref().unwrap()[0].family.as_deref()
+        );
+        assert_eq!(Some("7"), work.article_number.as_deref());
+        assert_eq!(Some((2020, Some(1), None)), work.issued.ymd());
+        assert_eq!("POPL '20", work.extra["event"]["acronym"]);
+        assert_eq!("New Orleans, LA, USA", work.extra["event"]["location"]);
+    }
+
+    #[test]
+    fn dissertation() {
+        let work = fixture(include_str!("../tests/fixtures/unixref/dissertation.xml"));
+
+        assert_eq!(Some("dissertation"), work.type_.as_deref());
+        assert_eq!(
+            Some("Turing"),
+            work.author.as_ref().unwrap()[0].family.as_deref()
+        );
+        assert_eq!(Some((1938, Some(5), None)), work.issued.ymd());
+        assert_eq!("Princeton University", work.extra["institution"][0]["name"]);
+        assert_eq!("PhD", work.extra["degree"][0]);
+        assert_eq!(Some("en"), work.language.as_deref());
+        assert!(work.to_bibtex().contains("school = {Princeton University}"));
+    }
+
+    #[test]
+    fn report() {
+        let work = fixture(include_str!("../tests/fixtures/unixref/report.xml"));
+
+        assert_eq!(Some("report"), work.type_.as_deref());
+        assert_eq!("10.2172/4390578", work.doi);
+        let authors = work.author.as_ref().unwrap();
+        assert_eq!(
+            Some("Los Alamos National Laboratory"),
+            authors[0].name.as_deref()
+        );
+        assert_eq!(Some("Ulam"), authors[1].family.as_deref());
+        assert_eq!(None, work.article_number);
+        assert_eq!(None, work.container_title);
+        assert_eq!(
+            Some("Office of Scientific and Technical Information (OSTI)"),
+            work.publisher.as_deref()
+        );
+        assert_eq!(Some((1949, Some(3), Some(1))), work.issued.ymd());
+    }
+
+    #[test]
+    fn dataset() {
+        let work = fixture(include_str!("../tests/fixtures/unixref/dataset.xml"));
+
+        assert_eq!(Some("dataset"), work.type_.as_deref());
+        assert_eq!("10.5061/dryad.b000/1", work.doi);
+        assert_eq!(
+            Some(vec!["Dryad Digital Repository".to_string()]),
+            work.container_title
+        );
+        assert_eq!(Some("Dryad"), work.publisher.as_deref());
+        assert_eq!(Some((2017, Some(4), Some(25))), work.issued.ymd());
+        let relation = work.relation.as_ref().unwrap();
+        assert_eq!("10.5061/dryad.b000", relation["is-part-of"][0]["id"]);
+        assert_eq!("10.1038/171740a0", relation["is-supplement-to"][0]["id"]);
+    }
+
+    #[test]
+    fn posted_content() {
+        let work = fixture(include_str!("../tests/fixtures/unixref/posted_content.xml"));
+
+        assert_eq!(Some("posted-content"), work.type_.as_deref());
+        assert_eq!("preprint", work.extra["subtype"]);
+        assert_eq!("Bioinformatics", work.extra["group-title"]);
+        assert_eq!(
+            Some((2020, Some(3), Some(30))),
+            work.posted.as_ref().unwrap().ymd()
+        );
+        assert_eq!(
+            Some((2020, Some(3), Some(28))),
+            work.accepted.as_ref().unwrap().ymd()
+        );
+        assert_eq!(Some((2020, Some(3), Some(30))), work.issued.ymd());
+        assert_eq!(
+            "National Biomedical Research Foundation",
+            work.author.as_ref().unwrap()[0]
+                .affiliation
+                .as_ref()
+                .unwrap()[0]
+                .name
+        );
+        assert_eq!(
+            Some("<jats:title>Abstract</jats:title><jats:p>Protein sequences.</jats:p>"),
+            work.abstract_.as_deref()
+        );
+    }
+
+    #[test]
+    fn invalid_records() {
+        let not_found = r#"<doi_records><doi_record><crossref><error>doi:10.5555/missing not found in CrossRef</error></crossref></doi_record></doi_records>"#;
+        assert_eq!(
+            "invalid unixref: doi:10.5555/missing not found in CrossRef",
+            parse(not_found).unwrap_err().to_string()
+        );
+        assert!(parse("<doi_records><doi_record>").is_err());
+        assert!(Work::from_unixref("<doi_records/>").is_err());
+    }
+}